@@ -74,50 +74,50 @@ fn bench_shift_integer(c: &mut Criterion) {
 	group.bench_function("shift_ne_by", |b| {
 		//let bb = ChessBoard::compute_ray_n_mask(25).clone();
 		b.iter(|| {
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift_ne_by(black_box(nb_shift) as u8);
 		})
 	});
 	group.bench_function("shift", |b| {
 		//let bb = ChessBoard::compute_ray_n_mask(25).clone();
 		b.iter(|| {
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
-			let mut x = black_box(bb.clone());
+			let mut x = black_box(bb);
 			x.shift(black_box(nb_shift), black_box(nb_shift));
 		})
 	});
@@ -0,0 +1,49 @@
+use bitboard::batch::BoardBatch;
+use bitboard_proc_macro::bitboard;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=19,height=19)]
+struct Go19;
+
+fn random_boards(seed: u64) -> Vec<Go19> {
+	let mut rng = Rng(seed);
+	(0..64).map(|_| random_board(Go19::EMPTY, 3, &mut rng)).collect()
+}
+
+/// Liberties and their number on 64 Go boards, one board at a time and bit-sliced.
+fn bench_liberties(c: &mut Criterion) {
+	let mut group = c.benchmark_group("liberties_64x19x19");
+	let (stones, occupied) = (random_boards(0x9E37_79B9_7F4A_7C15), random_boards(0x2545_F491_4F6C_DD1D));
+	group.bench_function("boards", |bench| {
+		bench.iter(|| {
+			black_box(&stones).iter().zip(black_box(&occupied)).map(|(s, o)| (s.neighbors_of_any() & o.not_const()).count()).collect::<Vec<_>>()
+		})
+	});
+	let (stones, occupied) = (BoardBatch::<Go19>::from_boards(&stones), BoardBatch::<Go19>::from_boards(&occupied));
+	group.bench_function("batch", |bench| {
+		bench.iter(|| (black_box(&stones).neighbors_of_any() & !black_box(&occupied).clone()).count())
+	});
+	group.finish();
+}
+
+/// Five in a row on 64 boards.
+fn bench_aligned(c: &mut Criterion) {
+	let mut group = c.benchmark_group("has_aligned_5_64x19x19");
+	let boards = random_boards(0xD1B5_4A32_D192_ED03);
+	group.bench_function("boards", |bench| {
+		bench.iter(|| black_box(&boards).iter().enumerate().fold(0u64, |acc, (i, b)| acc | (b.has_aligned::<5>() as u64) << i))
+	});
+	let batch = BoardBatch::<Go19>::from_boards(&boards);
+	group.bench_function("batch", |bench| {
+		bench.iter(|| black_box(&batch).has_aligned::<5>())
+	});
+	group.finish();
+}
+
+criterion_group!(benches, bench_liberties, bench_aligned);
+criterion_main!(benches);
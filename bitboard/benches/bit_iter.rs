@@ -0,0 +1,43 @@
+use bitboard::{BitIter, Bitboard, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard}};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::Rng;
+
+/// A 255x255 board with `n` stones spread over it.
+fn sparse(n: usize) -> LargeBitboard {
+	let mut b = LargeBitboard::empty(255, 255, false);
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for _ in 0..n {
+		b.set_at_index((rng.next() % (255 * 255)) as usize);
+	}
+	b
+}
+
+fn bench_iter(c: &mut Criterion) {
+	let mut group = c.benchmark_group("iter_large_255x255");
+	for n in [8, 256, 4096] {
+		let b = sparse(n);
+		group.bench_function(BenchmarkId::new("pop_lsb", n), |bench| {
+			bench.iter(|| {
+				let (mut bits, mut sum) = (black_box(&b).clone(), 0u32);
+				while bits.any() {
+					sum = sum.wrapping_add(bits.pop_lsb());
+				}
+				sum
+			})
+		});
+		group.bench_function(BenchmarkId::new("bits", n), |bench| {
+			bench.iter(|| black_box(&b).bits().fold(0u32, u32::wrapping_add))
+		});
+		group.bench_function(BenchmarkId::new("bits_rev", n), |bench| {
+			bench.iter(|| black_box(&b).bits().rev().fold(0u32, u32::wrapping_add))
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, bench_iter);
+criterion_main!(benches);
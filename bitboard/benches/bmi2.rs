@@ -0,0 +1,64 @@
+use bitboard::bmi2::{self, Bmi2Policy};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// A row and a column of an 8x8 row-major board, a diagonal, and scattered bits.
+const MASKS: [(&str, u64); 4] = [
+	("row", 0x0000_0000_FF00_0000),
+	("column", 0x0808_0808_0808_0808),
+	("diagonal", 0x8040_2010_0804_0201),
+	("scattered", 0xCCCC_CCCC_CCCC_CCCC),
+];
+const VALUE: u64 = 0xF0F0_9E37_79B9_7C15;
+
+/// The fallback before the portable version: one iteration per bit up to the highest of the mask.
+fn pext_bit_loop(value: u64, mask: u64) -> u64 {
+	let (mut res, mut bit, mut m, mut v) = (0, 1, mask, value);
+	while m != 0 {
+		if m & 1 != 0 {
+			if v & 1 != 0 {
+				res |= bit;
+			}
+			bit <<= 1;
+		}
+		m >>= 1;
+		v >>= 1;
+	}
+	res
+}
+
+fn bench_pext(c: &mut Criterion) {
+	let mut group = c.benchmark_group("pext");
+	for (name, mask) in MASKS {
+		group.bench_function(BenchmarkId::new("bit_loop", name), |b| {
+			b.iter(|| pext_bit_loop(black_box(VALUE), black_box(mask)))
+		});
+		group.bench_function(BenchmarkId::new("portable", name), |b| {
+			b.iter(|| bmi2::portable::pext_u64(black_box(VALUE), black_box(mask)))
+		});
+		bmi2::set_policy(Bmi2Policy::Hardware);
+		group.bench_function(BenchmarkId::new("dispatched", name), |b| {
+			b.iter(|| bmi2::pext_u64(black_box(VALUE), black_box(mask)))
+		});
+		bmi2::set_policy(Bmi2Policy::Auto);
+	}
+	group.finish();
+}
+
+fn bench_pdep(c: &mut Criterion) {
+	let mut group = c.benchmark_group("pdep");
+	for (name, mask) in MASKS {
+		group.bench_function(BenchmarkId::new("portable", name), |b| {
+			b.iter(|| bmi2::portable::pdep_u64(black_box(VALUE), black_box(mask)))
+		});
+		bmi2::set_policy(Bmi2Policy::Hardware);
+		group.bench_function(BenchmarkId::new("dispatched", name), |b| {
+			b.iter(|| bmi2::pdep_u64(black_box(VALUE), black_box(mask)))
+		});
+		bmi2::set_policy(Bmi2Policy::Auto);
+	}
+	group.finish();
+}
+
+criterion_group!(benches, bench_pext, bench_pdep);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use bitboard::{Bitboard, sliding::{ROOK_DIRECTIONS, attacks_of_all, sliding_attacks}};
+use bitboard_proc_macro::bitboard;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width = 8, height = 8)]
+struct ChessBoard;
+
+#[bitboard(width = 16, height = 16, tables(ray_n, ray_s, ray_e, ray_w))]
+struct Board16x16;
+
+/// Rook attacks from the ray tables: each ray loses the part behind its first blocker.
+fn rook_attacks_rays(square: usize, occupancy: &Board16x16) -> Board16x16 {
+	let mut attacks = Board16x16::EMPTY;
+	for (rays, positive) in [(Board16x16::RAY_N, true), (Board16x16::RAY_E, true), (Board16x16::RAY_S, false), (Board16x16::RAY_W, false)] {
+		let ray = rays[square].clone();
+		let blockers = ray.clone() & occupancy.clone();
+		match if positive { blockers.first() } else { blockers.last() } {
+			None => attacks |= ray,
+			Some(blocker) => attacks |= ray ^ rays[blocker as usize].clone(),
+		}
+	}
+	attacks
+}
+
+fn bench_rook_8x8(c: &mut Criterion) {
+	let mut group = c.benchmark_group("rook_8x8");
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	let occupancy = random_board(ChessBoard::EMPTY, 4, &mut rng);
+	let table = ChessBoard::generate_rook_sliding_table();
+
+	group.bench_function("sliding_table", |b| {
+		b.iter(|| {
+			for square in 0..ChessBoard::NB_SQUARES {
+				black_box(table.attacks(black_box(square), black_box(&occupancy)));
+			}
+		})
+	});
+	group.bench_function("kogge_stone", |b| {
+		b.iter(|| {
+			for square in 0..ChessBoard::NB_SQUARES {
+				black_box(sliding_attacks(black_box(square), black_box(&occupancy), &ROOK_DIRECTIONS));
+			}
+		})
+	});
+	group.finish();
+}
+
+fn bench_rook_16x16(c: &mut Criterion) {
+	let mut group = c.benchmark_group("rook_16x16");
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	let occupancy = random_board(Board16x16::EMPTY, 4, &mut rng);
+	let rooks = random_board(Board16x16::EMPTY, 16, &mut rng);
+	let all = occupancy.clone() | rooks.clone();
+	let rook_squares: Vec<usize> = (0..Board16x16::NB_SQUARES).filter(|&i| rooks.get_at_index(i)).collect();
+
+	group.bench_function("ray_tables", |b| {
+		b.iter(|| {
+			for square in 0..Board16x16::NB_SQUARES {
+				black_box(rook_attacks_rays(black_box(square), black_box(&occupancy)));
+			}
+		})
+	});
+	group.bench_function("kogge_stone", |b| {
+		b.iter(|| {
+			for square in 0..Board16x16::NB_SQUARES {
+				black_box(sliding_attacks(black_box(square), black_box(&occupancy), &ROOK_DIRECTIONS));
+			}
+		})
+	});
+	group.bench_function("ray_tables_all_rooks", |b| {
+		b.iter(|| {
+			let mut attacks = Board16x16::EMPTY;
+			for &square in &rook_squares {
+				attacks |= rook_attacks_rays(square, black_box(&all));
+			}
+			black_box(attacks)
+		})
+	});
+	group.bench_function("kogge_stone_all_rooks", |b| {
+		b.iter(|| {
+			black_box(attacks_of_all(black_box(&rooks), black_box(&all), &ROOK_DIRECTIONS))
+		})
+	});
+	group.finish();
+}
+
+criterion_group!(benches, bench_rook_8x8, bench_rook_16x16);
+criterion_main!(benches);
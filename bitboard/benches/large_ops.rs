@@ -0,0 +1,38 @@
+use bitboard::{Bitboard, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, large_bitboard_n::LargeBitboardN}};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::{Rng, random_board};
+
+/// Words of a 40x40 board.
+const WORDS: usize = 25;
+
+/// `(a & b) | c`, as in a pathfinding step.
+fn bench_and_or(c: &mut Criterion) {
+	let mut group = c.benchmark_group("and_or_40x40");
+	let boards = [0x9E37_79B9_7F4A_7C15, 0x2545_F491_4F6C_DD1D, 0xD1B5_4A32_D192_ED03].map(|seed| random_board(LargeBitboard::empty(40, 40, false), 3, &mut Rng(seed)));
+	let [a, b, m] = &boards;
+	group.bench_function("cloned", |bench| {
+		bench.iter(|| (black_box(a).clone() & black_box(b).clone()) | black_box(m).clone())
+	});
+	group.bench_function("borrowed", |bench| {
+		bench.iter(|| (black_box(a) & black_box(b)) | black_box(m))
+	});
+	let mut out = LargeBitboard::empty(40, 40, false);
+	group.bench_function("into", |bench| {
+		bench.iter(|| {
+			LargeBitboard::and_into(black_box(a), black_box(b), &mut out);
+			out |= black_box(m);
+		})
+	});
+	let [a, b, m] = boards.map(|l| LargeBitboardN::<WORDS>::new(40, 40, false, l.storage()[..].try_into().unwrap()));
+	group.bench_function("large_bitboard_n", |bench| {
+		bench.iter(|| (black_box(a) & black_box(b)) | black_box(m))
+	});
+	group.finish();
+}
+
+criterion_group!(benches, bench_and_or);
+criterion_main!(benches);
@@ -29,6 +29,7 @@ impl BB {
 
 		#[cfg(not(target_feature = "bmi2"))]
 		{
+			let _ = mask;
 			unreachable!("BMI2 version compiled without BMI2")
 		}
 	}
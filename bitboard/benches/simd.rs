@@ -0,0 +1,80 @@
+use bitboard::simd;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::Rng;
+
+/// Words of a 19x19 board (`#[bitboard]` array storage) and of a 64x64 `LargeBitboard`.
+const LENS: [usize; 2] = [6, 64];
+
+fn words(len: usize, seed: u64) -> Vec<u64> {
+	let mut rng = Rng(seed);
+	(0..len).map(|_| rng.next()).collect()
+}
+
+fn bench_binary(c: &mut Criterion) {
+	let mut group = c.benchmark_group("xor_assign");
+	for len in LENS {
+		let (mut a, b) = (words(len, 0x9E37_79B9_7F4A_7C15), words(len, 0x2545_F491_4F6C_DD1D));
+		group.bench_function(BenchmarkId::new("scalar", len), |bench| {
+			bench.iter(|| simd::scalar::xor_assign(black_box(&mut a), black_box(&b)))
+		});
+		group.bench_function(BenchmarkId::new("simd", len), |bench| {
+			bench.iter(|| simd::xor_assign(black_box(&mut a), black_box(&b)))
+		});
+	}
+	group.finish();
+}
+
+fn bench_shifts(c: &mut Criterion) {
+	let mut group = c.benchmark_group("shl_shr_assign");
+	for len in LENS {
+		let mut a = words(len, 0xD1B5_4A32_D192_ED03);
+		group.bench_function(BenchmarkId::new("scalar", len), |bench| {
+			bench.iter(|| {
+				simd::scalar::shl_assign(black_box(&mut a), black_box(19));
+				simd::scalar::shr_assign(black_box(&mut a), black_box(19));
+			})
+		});
+		group.bench_function(BenchmarkId::new("simd", len), |bench| {
+			bench.iter(|| {
+				simd::shl_assign(black_box(&mut a), black_box(19));
+				simd::shr_assign(black_box(&mut a), black_box(19));
+			})
+		});
+	}
+	group.finish();
+}
+
+fn bench_count(c: &mut Criterion) {
+	let mut group = c.benchmark_group("count_ones");
+	for len in LENS {
+		let a = words(len, 0x94D0_49BB_1331_11EB);
+		group.bench_function(BenchmarkId::new("scalar", len), |bench| {
+			bench.iter(|| simd::scalar::count_ones(black_box(&a)))
+		});
+		group.bench_function(BenchmarkId::new("simd", len), |bench| {
+			bench.iter(|| simd::count_ones(black_box(&a)))
+		});
+	}
+	group.finish();
+}
+
+fn bench_is_zero(c: &mut Criterion) {
+	let mut group = c.benchmark_group("is_zero");
+	for len in LENS {
+		let a = vec![0; len];
+		group.bench_function(BenchmarkId::new("scalar", len), |bench| {
+			bench.iter(|| simd::scalar::is_zero(black_box(&a)))
+		});
+		group.bench_function(BenchmarkId::new("simd", len), |bench| {
+			bench.iter(|| simd::is_zero(black_box(&a)))
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, bench_binary, bench_shifts, bench_count, bench_is_zero);
+criterion_main!(benches);
@@ -1,5 +1,5 @@
 use bitboard::{
-	BitIter, Bitboard,
+	Bitboard,
 	runtime::{RuntimeBitboard, medium_bitboard::MediumBitboard},
 };
 
@@ -30,7 +30,7 @@ fn main() {
 	// Row and column masks
 	println!("Row mask 6:\n{}", board.row_mask_with_same_shape(6));
 	println!("Column mask 3:\n{}", board.col_mask_with_same_shape(3));
-	for e in board.iter_bits() {
-		println!("{:?}", board.coords_from_index_with_same_shape(e as usize))
+	for (x, y) in board.iter_coords() {
+		println!("({x}, {y})")
 	}
 }
\ No newline at end of file
@@ -0,0 +1,460 @@
+//! Bit-sliced batches: the same operation on up to 64 boards at once.
+//!
+//! A [`BoardBatch`] stores its boards transposed, one `u64` per square whose bit `i` is that
+//! square on board `i`. A logical operation is then one word operation per square for all the
+//! boards, and a shift moves words from square to square. This suits Monte-Carlo playouts or
+//! parameter sweeps, which apply the same steps to many boards of the same shape.
+//!
+//! Shifts, [`BoardBatch::dilated`] and the alignment checks follow the square neighbourhood
+//! without wrapping, as [`RuntimeBitboard`](crate::runtime::RuntimeBitboard) and the `#[bitboard]`
+//! types without `topology` nor `wrap_x`/`wrap_y` do.
+//!
+//! ```
+//! use bitboard::{Bitboard, batch::BoardBatch};
+//! use bitboard::runtime::{RuntimeBitboard, small_bitboard::SmallBitboard};
+//!
+//! let mut boards = vec![SmallBitboard::empty(8, 8, false); 3];
+//! boards[0].set(3, 3);
+//! boards[2].set(0, 0);
+//! let batch: BoardBatch<_> = BoardBatch::from_boards(&boards);
+//! let counts = batch.dilated().count();
+//! assert_eq!(counts[..3], [9, 0, 4]);
+//! ```
+
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::{BitIter, Bitboard};
+
+/// `N` boards of the shape of `B`, bit-sliced: one `u64` per square, one bit per board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardBatch<B, const N: usize = 64> {
+	/// Empty board giving the shape, and the type of the boards taken out.
+	empty: B,
+	squares: Box<[u64]>,
+}
+
+impl<B: Bitboard, const N: usize> BoardBatch<B, N> {
+	/// Bits of the boards of the batch in each square.
+	pub const LANES: u64 = {
+		assert!(N >= 1 && N <= 64, "a BoardBatch holds 1 to 64 boards");
+		if N == 64 { u64::MAX } else { (1 << N) - 1 }
+	};
+
+	/// `N` empty boards shaped like `board`.
+	pub fn new(board: &B) -> Self {
+		let _ = Self::LANES;
+		let empty = board.clone() ^ board.clone();
+		let nb_squares = board.width() as usize * board.height() as usize;
+		Self { empty, squares: vec![0; nb_squares].into_boxed_slice() }
+	}
+
+	/// Batch of `boards` in order, followed by empty boards up to `N`.
+	///
+	/// Panics if `boards` is empty, holds more than `N` boards, or boards of different shapes.
+	pub fn from_boards(boards: &[B]) -> Self {
+		assert!(!boards.is_empty() && boards.len() <= N, "expected 1 to {N} boards, got {}", boards.len());
+		let mut batch = Self::new(&boards[0]);
+		for (lane, board) in boards.iter().enumerate() {
+			batch.insert_board(lane, board);
+		}
+		batch
+	}
+
+	/// The `N` boards of the batch.
+	pub fn to_boards(&self) -> Vec<B> {
+		let mut boards = vec![self.empty.clone(); N];
+		for (index, &word) in self.squares.iter().enumerate() {
+			let mut lanes = word;
+			while lanes != 0 {
+				boards[lanes.trailing_zeros() as usize].set_at_index(index);
+				lanes &= lanes - 1;
+			}
+		}
+		boards
+	}
+
+	/// Board `lane` of the batch.
+	pub fn board(&self, lane: usize) -> B {
+		assert!(lane < N);
+		let mut board = self.empty.clone();
+		for (index, &word) in self.squares.iter().enumerate() {
+			if word >> lane & 1 != 0 {
+				board.set_at_index(index);
+			}
+		}
+		board
+	}
+
+	/// Replaces board `lane` of the batch by `board`.
+	pub fn set_board(&mut self, lane: usize, board: &B) {
+		assert!(lane < N);
+		for word in self.squares.iter_mut() {
+			*word &= !(1 << lane);
+		}
+		self.insert_board(lane, board);
+	}
+
+	/// Sets the squares of `board` in lane `lane`, which is empty.
+	fn insert_board(&mut self, lane: usize, board: &B) {
+		debug_assert!(board.width() == self.empty.width() && board.height() == self.empty.height() && board.col_major() == self.empty.col_major(), "boards of a batch have the same shape");
+		for index in board.bits() {
+			self.squares[index as usize] |= 1 << lane;
+		}
+	}
+
+	/// Width of the boards.
+	#[inline(always)]
+	pub fn width(&self) -> u8 {
+		self.empty.width()
+	}
+	/// Height of the boards.
+	#[inline(always)]
+	pub fn height(&self) -> u8 {
+		self.empty.height()
+	}
+	#[inline(always)]
+	fn index(&self, x: u8, y: u8) -> usize {
+		if self.empty.col_major() {
+			x as usize * self.height() as usize + y as usize
+		} else {
+			y as usize * self.width() as usize + x as usize
+		}
+	}
+
+	/// Square `(x, y)` of every board: bit `i` is set if it is set on board `i`.
+	#[inline]
+	pub fn get(&self, x: u8, y: u8) -> u64 {
+		self.squares[self.index(x, y)]
+	}
+	/// Sets square `(x, y)` on the boards of `lanes` and clears it on the others.
+	#[inline]
+	pub fn assign(&mut self, x: u8, y: u8, lanes: u64) {
+		let index = self.index(x, y);
+		self.squares[index] = lanes & Self::LANES;
+	}
+
+	/// `N` empty boards of the same shape.
+	pub fn empty_with_same_shape(&self) -> Self {
+		Self { empty: self.empty.clone(), squares: vec![0; self.squares.len()].into_boxed_slice() }
+	}
+	/// `N` full boards of the same shape.
+	pub fn full_with_same_shape(&self) -> Self {
+		Self { empty: self.empty.clone(), squares: vec![Self::LANES; self.squares.len()].into_boxed_slice() }
+	}
+
+	/// Boards having at least one square set.
+	#[inline]
+	pub fn any(&self) -> u64 {
+		self.squares.iter().fold(0, |acc, w| acc | w)
+	}
+	/// Number of squares set on each board.
+	///
+	/// The words are added as 1-bit numbers into bit-sliced counters, so each square costs a
+	/// few word operations for all the boards.
+	pub fn count(&self) -> [u32; N] {
+		// Up to 255 * 255 squares: 16 bits per counter
+		let mut planes = [0u64; 16];
+		for words in self.squares.chunks(15) {
+			// Up to 15 words in 4-bit counters, then added to the planes
+			let mut low = [0u64; 4];
+			for &word in words {
+				let mut carry = word;
+				for plane in low.iter_mut() {
+					(*plane, carry) = (*plane ^ carry, *plane & carry);
+				}
+			}
+			let mut carry = 0;
+			for (bit, plane) in planes.iter_mut().enumerate() {
+				let add = low.get(bit).copied().unwrap_or(0);
+				if bit >= low.len() && carry == 0 {
+					break;
+				}
+				(*plane, carry) = (*plane ^ add ^ carry, *plane & add | carry & (*plane ^ add));
+			}
+		}
+		core::array::from_fn(|lane| {
+			planes.iter().enumerate().fold(0, |acc, (bit, plane)| acc | ((plane >> lane & 1) as u32) << bit)
+		})
+	}
+
+	/// Calls `f(to, Some(from), len)` for the runs of `len` squares `to..` whose sources
+	/// `from..` (shifted back by `(dx, dy)`) are on the board, and `f(to, None, len)` for the
+	/// runs whose sources are not.
+	#[inline(always)]
+	fn for_each_shifted_run(&self, dx: i16, dy: i16, mut f: impl FnMut(usize, Option<usize>, usize)) {
+		// Squares are stored in lines of `inner` words
+		let (w, h) = (self.width() as isize, self.height() as isize);
+		let (outer, inner, d_outer, d_inner) = if self.empty.col_major() {
+			(w, h, dx as isize, dy as isize)
+		} else {
+			(h, w, dy as isize, dx as isize)
+		};
+		let len = (outer * inner) as usize;
+		if d_inner == 0 {
+			// Whole lines: one run
+			let shift = (d_outer.unsigned_abs() * inner as usize).min(len);
+			if d_outer >= 0 {
+				f(0, None, shift);
+				f(shift, Some(0), len - shift);
+			} else {
+				f(0, Some(shift), len - shift);
+				f(len - shift, None, shift);
+			}
+			return;
+		}
+		let (lo, hi) = (d_inner.clamp(0, inner), (inner + d_inner).clamp(0, inner));
+		for o in 0..outer {
+			let start = (o * inner) as usize;
+			let so = o - d_outer;
+			if !(0..outer).contains(&so) || lo >= hi {
+				f(start, None, inner as usize);
+				continue;
+			}
+			let from = Some((so * inner + lo - d_inner) as usize);
+			let (lo, hi) = (lo as usize, hi as usize);
+			f(start, None, lo);
+			f(start + lo, from, hi - lo);
+			f(start + hi, None, inner as usize - hi);
+		}
+	}
+	/// `self[s] |= source[s - (dx, dy)]`, squares from outside the board being empty.
+	#[inline]
+	fn or_shifted(&mut self, source: &Self, dx: i16, dy: i16) {
+		let mut squares = core::mem::take(&mut self.squares);
+		self.for_each_shifted_run(dx, dy, |to, from, len| {
+			if let Some(from) = from {
+				for (word, source) in squares[to..to + len].iter_mut().zip(&source.squares[from..from + len]) {
+					*word |= source;
+				}
+			}
+		});
+		self.squares = squares;
+	}
+	/// `self[s] &= source[s - (dx, dy)]`, squares from outside the board being empty.
+	#[inline]
+	fn and_shifted(&mut self, source: &Self, dx: i16, dy: i16) {
+		let mut squares = core::mem::take(&mut self.squares);
+		self.for_each_shifted_run(dx, dy, |to, from, len| match from {
+			None => squares[to..to + len].fill(0),
+			Some(from) => {
+				for (word, source) in squares[to..to + len].iter_mut().zip(&source.squares[from..from + len]) {
+					*word &= source;
+				}
+			}
+		});
+		self.squares = squares;
+	}
+	/// `out[s] = self[s] & self[s - (dx, dy)]`, squares from outside the board being empty.
+	#[inline]
+	fn and_shifted_into(&self, dx: i16, dy: i16, out: &mut Self) {
+		self.for_each_shifted_run(dx, dy, |to, from, len| match from {
+			None => out.squares[to..to + len].fill(0),
+			Some(from) => {
+				let words = out.squares[to..to + len].iter_mut().zip(&self.squares[to..to + len]);
+				for ((word, a), b) in words.zip(&self.squares[from..from + len]) {
+					*word = a & b;
+				}
+			}
+		});
+	}
+
+	/// Boards shifted by `dx` columns and `dy` rows; squares leaving the board are dropped.
+	pub fn shifted(&self, dx: i16, dy: i16) -> Self {
+		let mut res = self.empty_with_same_shape();
+		self.for_each_shifted_run(dx, dy, |to, from, len| {
+			if let Some(from) = from {
+				res.squares[to..to + len].copy_from_slice(&self.squares[from..from + len]);
+			}
+		});
+		res
+	}
+	/// Boards shifted one square north (up).
+	#[inline(always)]
+	pub fn shifted_n(&self) -> Self { self.shifted(0, 1) }
+	/// Boards shifted one square south (down).
+	#[inline(always)]
+	pub fn shifted_s(&self) -> Self { self.shifted(0, -1) }
+	/// Boards shifted one square east (right).
+	#[inline(always)]
+	pub fn shifted_e(&self) -> Self { self.shifted(1, 0) }
+	/// Boards shifted one square west (left).
+	#[inline(always)]
+	pub fn shifted_w(&self) -> Self { self.shifted(-1, 0) }
+	/// Boards shifted one square north-east.
+	#[inline(always)]
+	pub fn shifted_ne(&self) -> Self { self.shifted(1, 1) }
+	/// Boards shifted one square north-west.
+	#[inline(always)]
+	pub fn shifted_nw(&self) -> Self { self.shifted(-1, 1) }
+	/// Boards shifted one square south-east.
+	#[inline(always)]
+	pub fn shifted_se(&self) -> Self { self.shifted(1, -1) }
+	/// Boards shifted one square south-west.
+	#[inline(always)]
+	pub fn shifted_sw(&self) -> Self { self.shifted(-1, -1) }
+
+	/// Return the dilated boards
+	///
+	/// The 3x3 square is a row of 3 moved up and down, so this takes 4 shifts instead of 8.
+	pub fn dilated(&self) -> Self {
+		let mut row = self.clone();
+		row.or_shifted(self, 1, 0);
+		row.or_shifted(self, -1, 0);
+		let mut res = row.clone();
+		res.or_shifted(&row, 0, 1);
+		res.or_shifted(&row, 0, -1);
+		res
+	}
+	/// Return the eroded boards
+	pub fn eroded(&self) -> Self {
+		let mut row = self.clone();
+		row.and_shifted(self, 1, 0);
+		row.and_shifted(self, -1, 0);
+		let mut res = row.clone();
+		res.and_shifted(&row, 0, 1);
+		res.and_shifted(&row, 0, -1);
+		res
+	}
+	/// Return all neighbors of any stone of each board
+	pub fn neighbors_of_any(&self) -> Self {
+		let mut res = self.dilated();
+		for (word, stones) in res.squares.iter_mut().zip(self.squares.iter()) {
+			*word &= !stones;
+		}
+		res
+	}
+
+	/// Squares `s` such that `s - k * (dx, dy)` is set for every bit `k` of `mask`.
+	fn detect_pattern(&self, dx: i16, dy: i16, mut mask: u64) -> Self {
+		let mut res = self.full_with_same_shape();
+		while mask != 0 {
+			let k = mask.trailing_zeros() as i16;
+			mask &= mask - 1;
+			res.and_shifted(self, k * dx, k * dy);
+		}
+		res
+	}
+	/// Squares ending the horizontal pattern `mask` (bit `k` set: `k` squares to the west is set).
+	#[inline]
+	pub fn detect_pattern_h(&self, mask: u64) -> Self {
+		self.detect_pattern(1, 0, mask)
+	}
+	/// Squares ending the vertical pattern `mask` (bit `k` set: `k` squares to the south is set).
+	#[inline]
+	pub fn detect_pattern_v(&self, mask: u64) -> Self {
+		self.detect_pattern(0, 1, mask)
+	}
+	/// Squares ending the ascending diagonal pattern `mask` (bit `k` set: `k` squares to the south-west is set).
+	#[inline]
+	pub fn detect_pattern_diag_inc(&self, mask: u64) -> Self {
+		self.detect_pattern(1, 1, mask)
+	}
+	/// Squares ending the descending diagonal pattern `mask` (bit `k` set: `k` squares to the north-west is set).
+	#[inline]
+	pub fn detect_pattern_diag_dec(&self, mask: u64) -> Self {
+		self.detect_pattern(1, -1, mask)
+	}
+
+	/// Squares starting a run of `n` set squares in direction `(dx, dy)`, by doubling as
+	/// [`RuntimeBitboard::aligned_starts`](crate::runtime::RuntimeBitboard::aligned_starts).
+	fn aligned_starts(&self, n: usize, dx: i16, dy: i16) -> Self {
+		if n == 0 {
+			return self.full_with_same_shape();
+		}
+		if n > u8::MAX as usize {
+			return self.empty_with_same_shape();
+		}
+		// Each step reads `temp` and writes `next`, then they are swapped
+		let (mut temp, mut next) = (self.clone(), self.empty_with_same_shape());
+		let mut built: i16 = 1;
+		while built * 2 <= n as i16 {
+			temp.and_shifted_into(-dx * built, -dy * built, &mut next);
+			core::mem::swap(&mut temp, &mut next);
+			built *= 2;
+		}
+		let remaining = n as i16 - built;
+		if remaining > 0 {
+			temp.and_shifted_into(-dx * remaining, -dy * remaining, &mut next);
+			core::mem::swap(&mut temp, &mut next);
+		}
+		temp
+	}
+	/// Boards having `K` set squares aligned in any direction.
+	pub fn has_aligned<const K: usize>(&self) -> u64 {
+		if K == 0 {
+			return Self::LANES;
+		}
+		if K == 1 {
+			return self.any();
+		}
+		DIRECTIONS.iter().fold(0, |acc, &(dx, dy)| acc | self.aligned_starts(K, dx, dy).any())
+	}
+	/// Number of runs of `K` aligned set squares in all directions on each board (overlapping
+	/// runs are counted).
+	pub fn count_aligned<const K: usize>(&self) -> [u32; N] {
+		let mut counts = [0; N];
+		for (dx, dy) in DIRECTIONS {
+			for (total, count) in counts.iter_mut().zip(self.aligned_starts(K, dx, dy).count()) {
+				*total += count;
+			}
+		}
+		counts
+	}
+}
+
+/// Horizontal, vertical, descending and ascending diagonal.
+const DIRECTIONS: [(i16, i16); 4] = [(1, 0), (0, 1), (1, -1), (1, 1)];
+
+macro_rules! impl_batch_op {
+	($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $symbol:tt) => {
+		impl<B: Bitboard, const N: usize> core::ops::$op_assign<&Self> for BoardBatch<B, N> {
+			#[inline]
+			fn $method_assign(&mut self, rhs: &Self) {
+				debug_assert_eq!(self.squares.len(), rhs.squares.len(), "boards of a batch have the same shape");
+				for (a, b) in self.squares.iter_mut().zip(rhs.squares.iter()) {
+					*a $symbol *b;
+				}
+			}
+		}
+		impl<B: Bitboard, const N: usize> core::ops::$op_assign for BoardBatch<B, N> {
+			#[inline]
+			fn $method_assign(&mut self, rhs: Self) {
+				core::ops::$op_assign::$method_assign(self, &rhs);
+			}
+		}
+		impl<B: Bitboard, const N: usize> core::ops::$op<&Self> for BoardBatch<B, N> {
+			type Output = Self;
+
+			#[inline]
+			fn $method(mut self, rhs: &Self) -> Self {
+				core::ops::$op_assign::$method_assign(&mut self, rhs);
+				self
+			}
+		}
+		impl<B: Bitboard, const N: usize> core::ops::$op for BoardBatch<B, N> {
+			type Output = Self;
+
+			#[inline]
+			fn $method(mut self, rhs: Self) -> Self {
+				core::ops::$op_assign::$method_assign(&mut self, &rhs);
+				self
+			}
+		}
+	};
+}
+impl_batch_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+impl_batch_op!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+impl_batch_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+impl<B: Bitboard, const N: usize> core::ops::Not for BoardBatch<B, N> {
+	type Output = Self;
+
+	/// Complements every board, within the board and the `N` lanes.
+	#[inline]
+	fn not(mut self) -> Self {
+		for word in self.squares.iter_mut() {
+			*word = !*word & Self::LANES;
+		}
+		self
+	}
+}
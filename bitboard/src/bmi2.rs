@@ -0,0 +1,248 @@
+//! PEXT/PDEP, with the BMI2 instructions chosen at run time.
+//!
+//! `pext` gathers the bits of a value selected by a mask into the low bits of the result, `pdep`
+//! scatters the low bits of a value to the set bits of a mask. On `x86_64`, BMI2 is detected the
+//! first time one of them runs, so a binary built for generic x86-64 uses it where it exists and
+//! still runs on the CPUs without it. Other architectures always use the [`portable`] versions.
+//!
+//! AMD CPUs before Zen 3 (families `17h` and `18h`) implement PEXT/PDEP in microcode, at up to
+//! hundreds of cycles depending on the mask: [`Bmi2Policy::Auto`], the default, keeps them on the
+//! portable code, and [`set_policy`] overrides this choice.
+//!
+//! The `pext`/`pdep` of the `#[bitboard]` types and of the runtime bitboards, and the lookups of
+//! `SlidingTable`, go through this module.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Choice between the BMI2 instructions and the portable code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Bmi2Policy {
+	/// BMI2 when the CPU has it, except where it is microcoded (AMD before Zen 3).
+	#[default]
+	Auto,
+	/// BMI2 whenever the CPU has it.
+	Hardware,
+	/// Always the portable code.
+	Portable,
+}
+
+const UNRESOLVED: u8 = 0;
+const HARDWARE: u8 = 1;
+const PORTABLE: u8 = 2;
+
+static POLICY: AtomicU8 = AtomicU8::new(Bmi2Policy::Auto as u8);
+/// Backend resolved from the policy and the CPU, `UNRESOLVED` until the first call.
+static BACKEND: AtomicU8 = AtomicU8::new(UNRESOLVED);
+
+/// Sets the policy of `pext`/`pdep`, meant to be called once at startup.
+///
+/// A `SlidingTable` picks its indexing when it is built, so tables built before keep theirs;
+/// their lookups stay correct, only slower.
+pub fn set_policy(policy: Bmi2Policy) {
+	POLICY.store(policy as u8, Ordering::Relaxed);
+	BACKEND.store(UNRESOLVED, Ordering::Relaxed);
+}
+
+/// Current policy, [`Bmi2Policy::Auto`] unless set by [`set_policy`].
+pub fn policy() -> Bmi2Policy {
+	match POLICY.load(Ordering::Relaxed) {
+		p if p == Bmi2Policy::Hardware as u8 => Bmi2Policy::Hardware,
+		p if p == Bmi2Policy::Portable as u8 => Bmi2Policy::Portable,
+		_ => Bmi2Policy::Auto,
+	}
+}
+
+/// Returns `true` if `pext`/`pdep` run the BMI2 instructions, given the policy and the CPU.
+#[inline(always)]
+pub fn uses_hardware() -> bool {
+	match BACKEND.load(Ordering::Relaxed) {
+		HARDWARE => true,
+		PORTABLE => false,
+		_ => resolve(),
+	}
+}
+
+#[cold]
+fn resolve() -> bool {
+	let policy = policy();
+	#[cfg(target_arch = "x86_64")]
+	let hardware = match policy {
+		Bmi2Policy::Auto => x86::has_bmi2() && !x86::has_microcoded_bmi2(),
+		Bmi2Policy::Hardware => x86::has_bmi2(),
+		Bmi2Policy::Portable => false,
+	};
+	#[cfg(not(target_arch = "x86_64"))]
+	let hardware = { let _ = policy; false };
+	BACKEND.store(if hardware { HARDWARE } else { PORTABLE }, Ordering::Relaxed);
+	hardware
+}
+
+/// Bits of `value` selected by `mask`, packed into the low bits.
+#[inline]
+pub fn pext_u64(value: u64, mask: u64) -> u64 {
+	#[cfg(target_arch = "x86_64")]
+	if uses_hardware() {
+		// SAFETY: `uses_hardware` is only `true` on CPUs having BMI2.
+		return unsafe { x86::pext(value, mask) };
+	}
+	portable::pext_u64(value, mask)
+}
+
+/// Low bits of `value` scattered to the set bits of `mask`.
+#[inline]
+pub fn pdep_u64(value: u64, mask: u64) -> u64 {
+	#[cfg(target_arch = "x86_64")]
+	if uses_hardware() {
+		// SAFETY: `uses_hardware` is only `true` on CPUs having BMI2.
+		return unsafe { x86::pdep(value, mask) };
+	}
+	portable::pdep_u64(value, mask)
+}
+
+/// [`pext_u64`] on 128 bits, one half after the other.
+#[inline]
+pub fn pext_u128(value: u128, mask: u128) -> u128 {
+	let lo_mask = mask as u64;
+	let lo = pext_u64(value as u64, lo_mask) as u128;
+	let hi = pext_u64((value >> 64) as u64, (mask >> 64) as u64) as u128;
+	lo | hi << lo_mask.count_ones()
+}
+
+/// [`pdep_u64`] on 128 bits, one half after the other.
+#[inline]
+pub fn pdep_u128(value: u128, mask: u128) -> u128 {
+	let lo_mask = mask as u64;
+	let lo = pdep_u64(value as u64, lo_mask) as u128;
+	let hi = pdep_u64((value >> lo_mask.count_ones()) as u64, (mask >> 64) as u64) as u128;
+	lo | hi << 64
+}
+
+/// PEXT of words, least significant first: the bits of `value` selected by `mask` are packed
+/// into `out`, whose other bits are cleared. `out` must hold as many bits as `mask` has set.
+#[inline]
+pub fn pext_words(value: &[u64], mask: &[u64], out: &mut [u64]) {
+	out.fill(0);
+	let mut pos = 0;
+	for (&v, &m) in value.iter().zip(mask) {
+		if m == 0 {
+			continue;
+		}
+		let bits = pext_u64(v, m);
+		let (word, offset) = (pos / 64, pos % 64);
+		out[word] |= bits << offset;
+		let len = m.count_ones() as usize;
+		if offset + len > 64 {
+			out[word + 1] |= bits >> (64 - offset);
+		}
+		pos += len;
+	}
+}
+
+/// PDEP of words, least significant first: the low bits of `value` are scattered to the set bits
+/// of `mask` into `out`, of the length of `mask`. Missing words of `value` count as zeros.
+#[inline]
+pub fn pdep_words(value: &[u64], mask: &[u64], out: &mut [u64]) {
+	debug_assert_eq!(out.len(), mask.len());
+	let word = |i: usize| value.get(i).copied().unwrap_or(0);
+	let mut pos = 0;
+	for (o, &m) in out.iter_mut().zip(mask) {
+		let (i, offset) = (pos / 64, pos % 64);
+		let bits = if offset == 0 { word(i) } else { word(i) >> offset | word(i + 1) << (64 - offset) };
+		*o = pdep_u64(bits, m);
+		pos += m.count_ones() as usize;
+	}
+}
+
+/// PEXT/PDEP without BMI2.
+///
+/// They copy the runs of consecutive set bits of the mask one at a time, so a row of a row-major
+/// board, or a column of a column-major one, is a single shift and mask. When the mask has no two
+/// adjacent bits, e.g. a diagonal, a cheaper loop moves its bits one at a time.
+pub mod portable {
+	/// Bits of `value` selected by `mask`, packed into the low bits.
+	#[inline]
+	pub fn pext_u64(value: u64, mut mask: u64) -> u64 {
+		let (mut res, mut packed) = (0, 0);
+		if mask & (mask >> 1) == 0 {
+			while mask != 0 {
+				res |= ((value >> mask.trailing_zeros()) & 1) << packed;
+				packed += 1;
+				mask &= mask - 1;
+			}
+			return res;
+		}
+		while mask != 0 {
+			let start = mask.trailing_zeros();
+			// Adding the lowest bit of the run clears it and sets the bit right above it
+			let above = mask.wrapping_add(1 << start);
+			res |= ((value & mask & !above) >> start) << packed;
+			packed += above.trailing_zeros() - start;
+			mask &= above;
+		}
+		res
+	}
+
+	/// Low bits of `value` scattered to the set bits of `mask`.
+	#[inline]
+	pub fn pdep_u64(mut value: u64, mut mask: u64) -> u64 {
+		let mut res = 0;
+		if mask & (mask >> 1) == 0 {
+			while mask != 0 {
+				res |= (value & 1) << mask.trailing_zeros();
+				value >>= 1;
+				mask &= mask - 1;
+			}
+			return res;
+		}
+		let mut packed = 0;
+		while mask != 0 {
+			let start = mask.trailing_zeros();
+			let above = mask.wrapping_add(1 << start);
+			res |= ((value >> packed) << start) & mask & !above;
+			packed += above.trailing_zeros() - start;
+			mask &= above;
+		}
+		res
+	}
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+	use core::arch::x86_64::{__cpuid, _pdep_u64, _pext_u64};
+
+	#[inline]
+	#[target_feature(enable = "bmi2")]
+	pub fn pext(value: u64, mask: u64) -> u64 {
+		_pext_u64(value, mask)
+	}
+
+	#[inline]
+	#[target_feature(enable = "bmi2")]
+	pub fn pdep(value: u64, mask: u64) -> u64 {
+		_pdep_u64(value, mask)
+	}
+
+	#[cfg(feature = "std")]
+	pub fn has_bmi2() -> bool {
+		std::is_x86_feature_detected!("bmi2")
+	}
+
+	/// CPUID leaf 7, bit 8 of EBX.
+	#[cfg(not(feature = "std"))]
+	pub fn has_bmi2() -> bool {
+		__cpuid(0).eax >= 7 && core::arch::x86_64::__cpuid_count(7, 0).ebx & (1 << 8) != 0
+	}
+
+	/// AMD family `17h` (Zen, Zen+, Zen 2) and Hygon family `18h` (Dhyana, a Zen derivative).
+	pub fn has_microcoded_bmi2() -> bool {
+		let leaf0 = __cpuid(0);
+		let mut vendor = [0; 12];
+		vendor[..4].copy_from_slice(&leaf0.ebx.to_le_bytes());
+		vendor[4..8].copy_from_slice(&leaf0.edx.to_le_bytes());
+		vendor[8..].copy_from_slice(&leaf0.ecx.to_le_bytes());
+		let eax = __cpuid(1).eax;
+		let base_family = (eax >> 8) & 0xF;
+		let family = if base_family == 0xF { base_family + ((eax >> 20) & 0xFF) } else { base_family };
+		matches!((&vendor, family), (b"AuthenticAMD", 0x17) | (b"HygonGenuine", 0x18))
+	}
+}
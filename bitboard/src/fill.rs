@@ -0,0 +1,115 @@
+//! Flood fill and connected components.
+//!
+//! [`FloodFill`] is implemented by the `#[bitboard]` types and by the runtime bitboards.
+//! Everything is built on [`FloodFill::expanded`], one step of dilation, repeated until a fixpoint.
+
+use crate::Bitboard;
+use crate::runtime::{RuntimeBitboard, large_bitboard_n::LargeBitboardN, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard};
+#[cfg(feature = "alloc")]
+use crate::runtime::{dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard};
+
+/// Neighbourhood connecting two squares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+	/// Orthogonal neighbours (N, S, E, W).
+	Four,
+	/// Orthogonal and diagonal neighbours.
+	Eight,
+	/// The six neighbours of axial hex coordinates: orthogonal, NW and SE,
+	/// as on `#[bitboard(topology = "hex")]` boards.
+	Hex,
+}
+
+/// Flood fill and connected components.
+pub trait FloodFill: Bitboard {
+	/// Returns `self` and the neighbours of its squares.
+	fn expanded(&self, connectivity: Connectivity) -> Self;
+
+	/// Returns the squares of `passable` connected to the squares of `self` (the seed) in `passable`.
+	///
+	/// Seed squares outside `passable` do not spread.
+	fn flood_fill(&self, passable: &Self, connectivity: Connectivity) -> Self {
+		let mut filled = self.clone() & passable.clone();
+		loop {
+			let next = filled.expanded(connectivity) & passable.clone();
+			if next == filled {
+				return filled;
+			}
+			filled = next;
+		}
+	}
+
+	/// Iterates over the connected groups of `self`, one bitboard per group,
+	/// in the order of their lowest square.
+	fn components(&self, connectivity: Connectivity) -> Components<Self> {
+		Components { remaining: self.clone(), connectivity }
+	}
+
+	/// Returns `true` if a path of `passable` squares connects the region `self` to the region `other`.
+	///
+	/// Both ends of the path must be in `passable`. This is the usual border-to-border
+	/// query, e.g. `WEST_BORDER.connects(&EAST_BORDER, &stones, Connectivity::Hex)`.
+	fn connects(&self, other: &Self, passable: &Self, connectivity: Connectivity) -> bool {
+		let target = other.clone() & passable.clone();
+		let mut filled = self.clone() & passable.clone();
+		loop {
+			if (filled.clone() & target.clone()).any() {
+				return true;
+			}
+			let next = filled.expanded(connectivity) & passable.clone();
+			if next == filled {
+				return false;
+			}
+			filled = next;
+		}
+	}
+}
+
+/// Iterator over the connected groups of a bitboard, returned by [`FloodFill::components`].
+pub struct Components<B> {
+	remaining: B,
+	connectivity: Connectivity,
+}
+
+impl<B: FloodFill> Iterator for Components<B> {
+	type Item = B;
+
+	fn next(&mut self) -> Option<B> {
+		let first = self.remaining.first()?;
+		// Empty bitboard of the same shape
+		let mut seed = self.remaining.clone() ^ self.remaining.clone();
+		seed.set_at_index(first as usize);
+		let group = seed.flood_fill(&self.remaining, self.connectivity);
+		self.remaining ^= group.clone();
+		Some(group)
+	}
+}
+
+/// Expansion of the runtime bitboards, from their shifts.
+fn expanded_runtime<B: RuntimeBitboard>(b: &B, connectivity: Connectivity) -> B {
+	let mut res = b.clone() | b.shifted_n() | b.shifted_s() | b.shifted_e() | b.shifted_w();
+	match connectivity {
+		Connectivity::Four => {}
+		Connectivity::Eight => res |= b.shifted_ne() | b.shifted_nw() | b.shifted_se() | b.shifted_sw(),
+		Connectivity::Hex => res |= b.shifted_nw() | b.shifted_se(),
+	}
+	res
+}
+
+macro_rules! impl_flood_fill_runtime {
+	(@impl [$($g:tt)*] $t:ty) => {
+		impl<$($g)*> FloodFill for $t {
+			#[inline]
+			fn expanded(&self, connectivity: Connectivity) -> Self {
+				expanded_runtime(self, connectivity)
+			}
+		}
+	};
+	($($t:ty),*) => {
+		$(impl_flood_fill_runtime!(@impl [] $t);)*
+	};
+}
+impl_flood_fill_runtime!(SmallBitboard, MediumBitboard);
+impl_flood_fill_runtime!(@impl [const WORDS: usize] LargeBitboardN<WORDS>);
+#[cfg(feature = "alloc")]
+impl_flood_fill_runtime!(LargeBitboard, DynBitboard);
@@ -0,0 +1,309 @@
+//! Rules of Go on top of the bitboards.
+//!
+//! [`Position`] holds the black and white stones of a board of any size, either a `#[bitboard]`
+//! type or a runtime bitboard. Groups and liberties come from [`FloodFill`] with 4-connectivity.
+//! Moves resolve captures, reject suicide, and reject ko or positional superko depending on the
+//! [`KoRule`]. [`Position::area_score`] counts the board with the Tromp-Taylor rules.
+//!
+//! ```ignore
+//! let mut pos = Position::new(Board19::FULL, KoRule::PositionalSuperko);
+//! pos.play(3, 3)?;                      // black
+//! let captured = pos.play(15, 15)?;     // white, returns the captured stones
+//! let score = pos.area_score();
+//! ```
+
+use alloc::{vec, vec::Vec};
+
+use crate::{BitIter, Bitboard};
+use crate::fill::{Connectivity, FloodFill};
+use crate::zobrist::{DEFAULT_SEED, zobrist_key};
+
+/// Color of a stone, or of the player to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+	Black,
+	White,
+}
+impl Color {
+	/// The other color.
+	#[inline(always)]
+	pub fn opponent(self) -> Self {
+		match self {
+			Color::Black => Color::White,
+			Color::White => Color::Black,
+		}
+	}
+}
+
+/// Repetitions forbidden by [`Position::play`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KoRule {
+	/// A move may not retake a ko immediately.
+	Simple,
+	/// Simple ko, and a move may not repeat any earlier arrangement of the stones.
+	PositionalSuperko,
+}
+
+/// Reason of an illegal move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MoveError {
+	/// The point is not on the board.
+	OutOfBoard,
+	/// The point already holds a stone.
+	Occupied,
+	/// The move would leave its own group without liberties.
+	Suicide,
+	/// The move retakes a ko immediately.
+	Ko,
+	/// The move repeats an earlier arrangement of the stones.
+	Superko,
+}
+impl core::fmt::Display for MoveError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			MoveError::OutOfBoard => write!(f, "point is not on the board"),
+			MoveError::Occupied => write!(f, "point is occupied"),
+			MoveError::Suicide => write!(f, "suicide is not allowed"),
+			MoveError::Ko => write!(f, "ko cannot be retaken immediately"),
+			MoveError::Superko => write!(f, "position repeats an earlier one"),
+		}
+	}
+}
+impl core::error::Error for MoveError {}
+
+/// Area score of a position, see [`Position::area_score`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Score {
+	pub black: u32,
+	pub white: u32,
+}
+
+/// A Go position: the stones, the player to move and the history needed by the ko rules.
+#[derive(Clone, Debug)]
+pub struct Position<B: FloodFill> {
+	/// Points of the board.
+	board: B,
+	black: B,
+	white: B,
+	to_move: Color,
+	ko_rule: KoRule,
+	/// Point where the player to move may not play, after a ko capture.
+	ko: Option<B>,
+	/// Stones captured by black and by white.
+	prisoners: [u32; 2],
+	consecutive_passes: u32,
+	/// Hashes of the arrangements of the stones since the start, the current one last.
+	history: Vec<u64>,
+}
+
+/// Hash key of a stone of `color` on the square at `index`.
+#[inline(always)]
+fn stone_key(index: u32, color: Color) -> u64 {
+	zobrist_key(DEFAULT_SEED, color as usize, index as usize)
+}
+
+/// Hash of the stones of `color`.
+fn stones_hash<B: Bitboard>(stones: &B, color: Color) -> u64 {
+	stones.bits().fold(0, |h, i| h ^ stone_key(i, color))
+}
+
+impl<B: FloodFill> Position<B> {
+	/// Empty position on `board`, the mask of the points (e.g. `FULL`), black to move.
+	pub fn new(board: B, ko_rule: KoRule) -> Self {
+		let empty = board.clone() ^ board.clone();
+		Self::from_stones(board, empty.clone(), empty, Color::Black, ko_rule)
+	}
+
+	/// Position with the given stones, e.g. a handicap or a problem setup.
+	///
+	/// Stones outside `board` are dropped. Stones are kept even without liberties.
+	pub fn from_stones(board: B, black: B, white: B, to_move: Color, ko_rule: KoRule) -> Self {
+		let black = black & board.clone();
+		let white = white & board.clone();
+		debug_assert!((black.clone() & white.clone()).is_empty(), "black and white stones overlap");
+		let hash = stones_hash(&black, Color::Black) ^ stones_hash(&white, Color::White);
+		Self { board, black, white, to_move, ko_rule, ko: None, prisoners: [0; 2], consecutive_passes: 0, history: vec![hash] }
+	}
+
+	/// Mask of the points of the board.
+	#[inline(always)]
+	pub fn board(&self) -> &B {
+		&self.board
+	}
+	/// Black stones.
+	#[inline(always)]
+	pub fn black(&self) -> &B {
+		&self.black
+	}
+	/// White stones.
+	#[inline(always)]
+	pub fn white(&self) -> &B {
+		&self.white
+	}
+	/// Stones of `color`.
+	#[inline(always)]
+	pub fn stones(&self, color: Color) -> &B {
+		match color {
+			Color::Black => &self.black,
+			Color::White => &self.white,
+		}
+	}
+	/// Empty points.
+	#[inline]
+	pub fn empty_points(&self) -> B {
+		self.board.clone() & !(self.black.clone() | self.white.clone())
+	}
+	/// Player to move.
+	#[inline(always)]
+	pub fn to_move(&self) -> Color {
+		self.to_move
+	}
+	#[inline(always)]
+	pub fn ko_rule(&self) -> KoRule {
+		self.ko_rule
+	}
+	/// Point where the player to move may not play because of a ko, if any.
+	#[inline(always)]
+	pub fn ko(&self) -> Option<&B> {
+		self.ko.as_ref()
+	}
+	/// Number of stones captured by `color`.
+	#[inline(always)]
+	pub fn prisoners(&self, color: Color) -> u32 {
+		self.prisoners[color as usize]
+	}
+	/// Number of passes since the last move, the game ends after two.
+	#[inline(always)]
+	pub fn consecutive_passes(&self) -> u32 {
+		self.consecutive_passes
+	}
+	/// Hash of the arrangement of the stones, regardless of the player to move: the hash of
+	/// `[black, white]` by `ZobristKeys::new`.
+	#[inline(always)]
+	pub fn hash(&self) -> u64 {
+		*self.history.last().unwrap()
+	}
+
+	/// Returns the color of the stone at `(x, y)`, if any.
+	pub fn get(&self, x: u8, y: u8) -> Option<Color> {
+		if self.black.get(x, y) {
+			Some(Color::Black)
+		} else if self.white.get(x, y) {
+			Some(Color::White)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the group of the stone at `(x, y)`, empty if there is no stone.
+	pub fn group(&self, x: u8, y: u8) -> B {
+		let mut seed = self.board.clone() ^ self.board.clone();
+		match self.get(x, y) {
+			Some(color) => {
+				seed.set(x, y);
+				seed.flood_fill(self.stones(color), Connectivity::Four)
+			}
+			None => seed,
+		}
+	}
+
+	/// Returns the liberties of `stones`: the empty points next to them.
+	pub fn liberties_of(&self, stones: &B) -> B {
+		stones.expanded(Connectivity::Four) & self.empty_points()
+	}
+
+	/// Returns the liberties of the group of the stone at `(x, y)`, empty if there is no stone.
+	pub fn liberties(&self, x: u8, y: u8) -> B {
+		self.liberties_of(&self.group(x, y))
+	}
+
+	/// Passes, which clears the ko.
+	pub fn pass(&mut self) {
+		self.to_move = self.to_move.opponent();
+		self.ko = None;
+		self.consecutive_passes += 1;
+	}
+
+	/// Returns an error if the player to move may not play at `(x, y)`.
+	pub fn check_move(&self, x: u8, y: u8) -> Result<(), MoveError> {
+		self.resolve(x, y).map(|_| ())
+	}
+
+	/// Plays a stone of the player to move at `(x, y)` and returns the captured stones.
+	///
+	/// The position is left unchanged when the move is illegal.
+	pub fn play(&mut self, x: u8, y: u8) -> Result<B, MoveError> {
+		let (own, captured, ko, hash) = self.resolve(x, y)?;
+		let color = self.to_move;
+		match color {
+			Color::Black => (self.black, self.white) = (own, self.white.clone() ^ captured.clone()),
+			Color::White => (self.white, self.black) = (own, self.black.clone() ^ captured.clone()),
+		}
+		self.prisoners[color as usize] += captured.count();
+		self.ko = ko;
+		self.to_move = color.opponent();
+		self.consecutive_passes = 0;
+		self.history.push(hash);
+		Ok(captured)
+	}
+
+	/// Computes the outcome of a move: the stones of the player with the new one, the captured
+	/// stones, the resulting ko point and the resulting hash.
+	fn resolve(&self, x: u8, y: u8) -> Result<(B, B, Option<B>, u64), MoveError> {
+		if x >= self.board.width() || y >= self.board.height() || !self.board.get(x, y) {
+			return Err(MoveError::OutOfBoard);
+		}
+		if self.black.get(x, y) || self.white.get(x, y) {
+			return Err(MoveError::Occupied);
+		}
+		let mut stone = self.board.clone() ^ self.board.clone();
+		stone.set(x, y);
+		let index = stone.first().expect("the stone was just set");
+		if self.ko.as_ref() == Some(&stone) {
+			return Err(MoveError::Ko);
+		}
+
+		let color = self.to_move;
+		let own = self.stones(color).clone() | stone.clone();
+		let opponent = self.stones(color.opponent());
+		let empty = self.board.clone() & !(own.clone() | opponent.clone());
+
+		// Opponent groups next to the stone, captured when they have no liberties left
+		let mut captured = stone.clone() ^ stone.clone();
+		let touched = (stone.expanded(Connectivity::Four) & opponent.clone()).flood_fill(opponent, Connectivity::Four);
+		for group in touched.components(Connectivity::Four) {
+			if !(group.expanded(Connectivity::Four) & empty.clone()).any() {
+				captured |= group;
+			}
+		}
+
+		let group = stone.flood_fill(&own, Connectivity::Four);
+		let liberties = group.expanded(Connectivity::Four) & (empty | captured.clone());
+		if !liberties.any() {
+			return Err(MoveError::Suicide);
+		}
+
+		// A single stone capturing a single stone, with that point as its only liberty
+		let ko = (captured.count() == 1 && group == stone && liberties == captured).then(|| captured.clone());
+
+		let hash = self.hash() ^ stone_key(index, color) ^ stones_hash(&captured, color.opponent());
+		if self.ko_rule == KoRule::PositionalSuperko && self.history.contains(&hash) {
+			return Err(MoveError::Superko);
+		}
+		Ok((own, captured, ko, hash))
+	}
+
+	/// Points of `color` under the Tromp-Taylor rules: its stones and the empty points from which
+	/// only stones of `color` can be reached through empty points.
+	pub fn area(&self, color: Color) -> B {
+		let empty = self.empty_points();
+		let reach = |stones: &B| (stones.expanded(Connectivity::Four) & empty.clone()).flood_fill(&empty, Connectivity::Four);
+		let own = self.stones(color);
+		own.clone() | (reach(own) & !reach(self.stones(color.opponent())) & empty.clone())
+	}
+
+	/// Tromp-Taylor area score, without komi.
+	pub fn area_score(&self) -> Score {
+		Score { black: self.area(Color::Black).count(), white: self.area(Color::White).count() }
+	}
+}
@@ -0,0 +1,149 @@
+//! Positions made of parallel bitboard layers.
+//!
+//! [`Layers`] holds `N` disjoint bitboards of the same shape, e.g. one per piece type and color,
+//! with the cache of their union. Each square holds at most one piece, identified by the index of
+//! its layer: [`Layers::put`], [`Layers::remove`] and [`Layers::move_piece`] update the layers and
+//! the occupancy together.
+//!
+//! ```ignore
+//! const WHITE_PAWN: usize = 0;
+//! const BLACK_PAWN: usize = 6;
+//! let mut pos = Layers::<Chess, 12>::new(Chess::EMPTY);
+//! pos.put(WHITE_PAWN, Chess::index_from_coords(4, 1));
+//! pos.move_piece(from, to);                       // returns the captured layer, if any
+//! println!("{}", pos.display_with(['P', 'N', 'B', 'R', 'Q', 'K', 'p', 'n', 'b', 'r', 'q', 'k']));
+//! ```
+
+use crate::Bitboard;
+
+/// `N` disjoint layers of bitboards of type `B` and their occupancy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layers<B, const N: usize> {
+	layers: [B; N],
+	occupied: B,
+}
+
+impl<B: Bitboard, const N: usize> Layers<B, N> {
+	const AT_LEAST_ONE_LAYER: () = assert!(N > 0, "Layers needs at least one layer");
+
+	/// Layers shaped like `empty`, which should be empty (e.g. `EMPTY`).
+	pub fn new(empty: B) -> Self {
+		let () = Self::AT_LEAST_ONE_LAYER;
+		let empty = empty.clone() ^ empty;
+		Self { layers: core::array::from_fn(|_| empty.clone()), occupied: empty }
+	}
+
+	/// Layers from their bitboards, which must be disjoint.
+	///
+	/// There must be at least one layer, which gives the shape of the board:
+	///
+	/// ```compile_fail
+	/// use bitboard::{layers::Layers, runtime::small_bitboard::SmallBitboard};
+	/// let pos = Layers::<SmallBitboard, 0>::from_layers([]);
+	/// ```
+	pub fn from_layers(layers: [B; N]) -> Self {
+		let () = Self::AT_LEAST_ONE_LAYER;
+		let occupied = layers.iter().skip(1).fold(layers[0].clone(), |occupied, layer| occupied | layer.clone());
+		let res = Self { layers, occupied };
+		debug_assert!(res.is_consistent(), "layers overlap");
+		res
+	}
+
+	/// Squares of `layer`.
+	#[inline(always)]
+	pub fn layer(&self, layer: usize) -> &B {
+		&self.layers[layer]
+	}
+	/// All the layers, e.g. for [`ZobristKeys::hash_of`](crate::zobrist::ZobristKeys::hash_of).
+	#[inline(always)]
+	pub fn layers(&self) -> &[B; N] {
+		&self.layers
+	}
+	/// Squares of all the layers.
+	#[inline(always)]
+	pub fn occupied(&self) -> &B {
+		&self.occupied
+	}
+
+	/// Layer of the piece at `index`, if any.
+	#[inline]
+	pub fn piece_at(&self, index: usize) -> Option<usize> {
+		if !self.occupied.get_at_index(index) {
+			return None;
+		}
+		self.layers.iter().position(|layer| layer.get_at_index(index))
+	}
+
+	/// Puts a piece of `layer` at `index`, and returns the layer of the piece it replaces.
+	pub fn put(&mut self, layer: usize, index: usize) -> Option<usize> {
+		let previous = self.remove(index);
+		self.layers[layer].set_at_index(index);
+		self.occupied.set_at_index(index);
+		debug_assert!(self.is_consistent(), "layers overlap");
+		previous
+	}
+
+	/// Removes the piece at `index`, and returns its layer.
+	pub fn remove(&mut self, index: usize) -> Option<usize> {
+		let layer = self.piece_at(index)?;
+		self.layers[layer].reset_at_index(index);
+		self.occupied.reset_at_index(index);
+		debug_assert!(self.is_consistent(), "layers overlap");
+		Some(layer)
+	}
+
+	/// Moves the piece at `from` to `to`, and returns the layer of the piece captured at `to`.
+	///
+	/// Panics if there is no piece at `from`.
+	pub fn move_piece(&mut self, from: usize, to: usize) -> Option<usize> {
+		let layer = self.remove(from).expect("no piece to move");
+		self.put(layer, to)
+	}
+
+	/// Are the layers disjoint, and is the occupancy their union.
+	///
+	/// Checked in debug builds after every change.
+	pub fn is_consistent(&self) -> bool {
+		let empty = self.occupied.clone() ^ self.occupied.clone();
+		let mut union = empty.clone();
+		for layer in &self.layers {
+			if (union.clone() & layer.clone()) != empty {
+				return false;
+			}
+			union |= layer.clone();
+		}
+		union == self.occupied
+	}
+
+	/// Displays the board with `symbols[layer]` for the pieces of each layer and `.` elsewhere.
+	pub fn display_with(&self, symbols: [char; N]) -> LayersDisplay<'_, B, N> {
+		LayersDisplay { layers: self, symbols }
+	}
+}
+
+/// Board of [`Layers::display_with`].
+pub struct LayersDisplay<'a, B, const N: usize> {
+	layers: &'a Layers<B, N>,
+	symbols: [char; N],
+}
+
+impl<B: Bitboard, const N: usize> core::fmt::Display for LayersDisplay<'_, B, N> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		let layers = &self.layers.layers;
+		let occupied = &self.layers.occupied;
+		crate::fmt_grid(occupied.width(), occupied.height(), |x, y| {
+			if !occupied.get(x, y) {
+				return '.';
+			}
+			layers.iter().position(|layer| layer.get(x, y)).map_or('?', |layer| self.symbols[layer])
+		}, f)
+	}
+}
+
+/// Displays the board with the layer indexes as base-36 digits (`0`-`9` then `a`-`z`).
+impl<B: Bitboard, const N: usize> core::fmt::Display for Layers<B, N> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		let symbols = core::array::from_fn(|layer| core::char::from_digit(layer as u32, 36).unwrap_or('*'));
+		self.display_with(symbols).fmt(f)
+	}
+}
@@ -1,6 +1,55 @@
 
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod runtime;
+pub mod sliding;
+#[cfg(feature = "alloc")]
+pub mod parse;
+pub mod fill;
+#[cfg(feature = "alloc")]
+pub mod go;
+pub mod symmetry;
+pub mod zobrist;
+pub mod layers;
+#[cfg(feature = "alloc")]
+pub mod batch;
+pub mod simd;
+pub mod bmi2;
+#[cfg(feature = "serde")]
+mod serde_impls;
 extern crate self as bitboard;
+
+/// Items used by the code generated by `#[bitboard]`.
+#[doc(hidden)]
+pub mod __private {
+	#[cfg(feature = "alloc")]
+	pub use alloc::vec::Vec;
+	#[cfg(feature = "serde")]
+	pub use crate::serde_impls::{deserialize_words, serde, serialize_words};
+}
+/// Without the `serde` feature, `#[bitboard]` types do not implement `Serialize`/`Deserialize`.
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_serde {
+	($($tt:tt)*) => {};
+}
+/// Keeps the items generated by `#[bitboard]` that allocate, with the `alloc` feature.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_alloc {
+	($($tt:tt)*) => { $($tt)* };
+}
+/// Without the `alloc` feature, `#[bitboard]` types have no `Vec`-returning methods nor `FromStr`.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_alloc {
+	($($tt:tt)*) => {};
+}
 #[macro_export]
 macro_rules! bitboard_table {
 	($table:ident, $access_fn:ident, $mask_fn:ident, $ty:ty, $gen:expr) => {
@@ -22,19 +71,19 @@ macro_rules! bitboard_table {
 pub trait BitStorage:
 	Copy
 	+ From<u8>
-	+ std::ops::Add<Output = Self>
-	+ std::ops::Sub<Output = Self>
-	+ std::ops::Mul<Output = Self>
-	+ std::ops::Div<Output = Self>
-	+ std::ops::Rem<Output = Self>
-	+ std::ops::BitAnd<Output = Self>
-	+ std::ops::BitOr<Output = Self>
-	+ std::ops::BitXor<Output = Self>
-	+ std::ops::Not<Output = Self>
-	+ std::ops::Shl<usize, Output = Self>
-	+ std::ops::Shr<usize, Output = Self>
-	+ std::ops::ShlAssign<usize>
-	+ std::ops::ShrAssign<usize>
+	+ core::ops::Add<Output = Self>
+	+ core::ops::Sub<Output = Self>
+	+ core::ops::Mul<Output = Self>
+	+ core::ops::Div<Output = Self>
+	+ core::ops::Rem<Output = Self>
+	+ core::ops::BitAnd<Output = Self>
+	+ core::ops::BitOr<Output = Self>
+	+ core::ops::BitXor<Output = Self>
+	+ core::ops::Not<Output = Self>
+	+ core::ops::Shl<usize, Output = Self>
+	+ core::ops::Shr<usize, Output = Self>
+	+ core::ops::ShlAssign<usize>
+	+ core::ops::ShrAssign<usize>
 {
 }
 impl BitStorage for u8 {}
@@ -89,6 +138,51 @@ impl IntegerStorage for u128 {
 	fn from_u128(val: u128) -> Self { val }
 }
 
+/// Storage that can be viewed as a sequence of 64-bit words.
+///
+/// The storage of every [`Bitboard`]: set bit iteration and table lookups read the raw bits
+/// through it, independently of the backend.
+pub trait WordStorage {
+	/// Number of 64-bit words in the storage.
+	fn nb_words(&self) -> usize;
+	/// Returns the `i`-th 64-bit word (least significant first).
+	fn word(&self, i: usize) -> u64;
+}
+
+macro_rules! impl_word_storage_int {
+	($($t:ty),*) => {
+		$(
+			impl WordStorage for $t {
+				#[inline(always)]
+				fn nb_words(&self) -> usize { 1 }
+				#[inline(always)]
+				fn word(&self, _i: usize) -> u64 { *self as u64 }
+			}
+		)*
+	};
+}
+impl_word_storage_int!(u8, u16, u32, u64);
+
+impl WordStorage for u128 {
+	#[inline(always)]
+	fn nb_words(&self) -> usize { 2 }
+	#[inline(always)]
+	fn word(&self, i: usize) -> u64 { (*self >> (64 * i)) as u64 }
+}
+impl<const N: usize> WordStorage for [u64; N] {
+	#[inline(always)]
+	fn nb_words(&self) -> usize { N }
+	#[inline(always)]
+	fn word(&self, i: usize) -> u64 { self[i] }
+}
+#[cfg(feature = "alloc")]
+impl WordStorage for alloc::boxed::Box<[u64]> {
+	#[inline(always)]
+	fn nb_words(&self) -> usize { self.len() }
+	#[inline(always)]
+	fn word(&self, i: usize) -> u64 { self[i] }
+}
+
 /// A generic 2D bitboard abstraction.
 /// 
 /// This trait defines the core operations for representing and manipulating
@@ -103,20 +197,20 @@ impl IntegerStorage for u128 {
 /// x increases to the right (east).
 /// y increases upward (north).
 pub trait Bitboard : Clone + PartialEq
-	+ std::ops::BitAnd<Output = Self>
-	+ std::ops::BitOr<Output = Self>
-	+ std::ops::BitXor<Output = Self>
-	+ std::ops::BitAndAssign
-	+ std::ops::BitOrAssign
-	+ std::ops::BitXorAssign
-	+ std::ops::Not<Output = Self>
-	+ std::ops::Shl<usize, Output = Self>
-	+ std::ops::Shr<usize, Output = Self>
-	+ std::ops::ShlAssign<usize>
-	+ std::ops::ShrAssign<usize>
+	+ core::ops::BitAnd<Output = Self>
+	+ core::ops::BitOr<Output = Self>
+	+ core::ops::BitXor<Output = Self>
+	+ core::ops::BitAndAssign
+	+ core::ops::BitOrAssign
+	+ core::ops::BitXorAssign
+	+ core::ops::Not<Output = Self>
+	+ core::ops::Shl<usize, Output = Self>
+	+ core::ops::Shr<usize, Output = Self>
+	+ core::ops::ShlAssign<usize>
+	+ core::ops::ShrAssign<usize>
 {
 	/// Underlying storage type (e.g. `u64`, `u128`, `[u64; 2]`, …).
-	type Storage;
+	type Storage: WordStorage;
 
 	/// Is Bitboard empty
 	fn is_empty(&self) -> bool;
@@ -162,10 +256,24 @@ pub trait Bitboard : Clone + PartialEq
 
 	/// Get flipped bitboard (ie: !self & full)
 	fn flipped(&self) -> Self;
-	// Get first (lsb) one index (returns WIDTH*HEIGHT if bitboard is empty)
+	/// Get first (lsb) one index (returns WIDTH*HEIGHT if bitboard is empty).
+	#[deprecated(note = "use first()/last()")]
 	fn lsb(&self) -> u32;
-	// Get last (msb) one index (panic if bitboard is empty)
+	/// Get last (msb) one index (panic if bitboard is empty).
+	#[deprecated(note = "use first()/last()")]
 	fn msb(&self) -> u32;
+	/// Index of the first (lowest) set bit, or `None` if the bitboard is empty.
+	#[inline]
+	#[allow(deprecated)]
+	fn first(&self) -> Option<u32> {
+		if self.is_empty() { None } else { Some(self.lsb()) }
+	}
+	/// Index of the last (highest) set bit, or `None` if the bitboard is empty.
+	#[inline]
+	#[allow(deprecated)]
+	fn last(&self) -> Option<u32> {
+		if self.is_empty() { None } else { Some(self.msb()) }
+	}
 	/// Set first (lsb) one to zero and returns its index
 	fn pop_lsb(&mut self) -> u32;
 	/// Parallel bit extract (PEXT).
@@ -188,6 +296,21 @@ pub trait Bitboard : Clone + PartialEq
 	/// Inserts a compact bitfield into column `x`.
 	fn insert_col(&mut self,x: u8, col_bits: Self::Storage);
 
+	/// Coordinates `(x, y)` of the set bits, in the order of their indexes.
+	#[inline]
+	fn iter_coords(&self) -> Coords<'_, Self> {
+		Coords { board: self, bits: self.bits() }
+	}
+	/// Rows from `y = 0` upward, each as the compact bitfield of [`Bitboard::extract_row`].
+	#[inline]
+	fn iter_rows(&self) -> Rows<'_, Self> {
+		Rows { board: self, front: 0, back: self.height() }
+	}
+	/// Columns from `x = 0` rightward, each as the compact bitfield of [`Bitboard::extract_col`].
+	#[inline]
+	fn iter_cols(&self) -> Cols<'_, Self> {
+		Cols { board: self, front: 0, back: self.width() }
+	}
 
 }
 
@@ -201,50 +324,268 @@ impl<T> Pdep<T> for T::Storage
 		T::pdep(mask, self.clone())
 	}
 }
+/// Iteration over the indexes of the set bits, in increasing order or from both ends.
 pub trait BitIter: Bitboard+Sized
-//where <Self as Bitboard>::Storage: BitStorage
 {
+	/// Consumes the bitboard; see [`BitIter::bits`] to borrow it instead.
 	fn iter_bits(self) -> BitIterator<Self>;
+	/// Borrows the bitboard, without cloning its storage.
+	fn bits(&self) -> Bits<'_, Self>;
 }
-pub struct BitIterator<B: Bitboard>
-//where <B as Bitboard>::Storage: BitStorage
-{
+
+/// Scan state shared by [`BitIterator`] and [`Bits`].
+///
+/// The words at both ends are copied, and the next word is read from the storage once a copy is
+/// exhausted, so each word is loaded once. When both ends reach the same word they share the
+/// `front` copy. The scan stops at the ends of the storage; `remaining`, the number of set bits
+/// in the words, only gives the length.
+#[derive(Clone, Debug)]
+struct WordCursor {
+	front: u64,
+	front_index: usize,
+	back: u64,
+	back_index: usize,
+	remaining: usize,
+}
+
+impl WordCursor {
+	#[inline]
+	fn new<S: WordStorage>(storage: &S) -> Self {
+		let nb_words = storage.nb_words();
+		let last = nb_words.saturating_sub(1);
+		let front = if nb_words == 0 { 0 } else { storage.word(0) };
+		let back = if last == 0 { 0 } else { storage.word(last) };
+		let remaining = (0..nb_words).map(|i| storage.word(i).count_ones() as usize).sum();
+		WordCursor { front, front_index: 0, back, back_index: last, remaining }
+	}
+
+	#[inline]
+	fn next<S: WordStorage>(&mut self, storage: &S) -> Option<u32> {
+		while self.front == 0 {
+			if self.front_index >= self.back_index {
+				return None;
+			}
+			self.front_index += 1;
+			self.front = if self.front_index == self.back_index { self.back } else { storage.word(self.front_index) };
+		}
+		self.remaining -= 1;
+		let bit = self.front.trailing_zeros();
+		self.front &= self.front - 1;
+		Some(self.front_index as u32 * 64 + bit)
+	}
+
+	#[inline]
+	fn next_back<S: WordStorage>(&mut self, storage: &S) -> Option<u32> {
+		loop {
+			// Shared with the front
+			let word = if self.back_index == self.front_index { &mut self.front } else { &mut self.back };
+			if *word != 0 {
+				self.remaining -= 1;
+				let bit = 63 - word.leading_zeros();
+				*word ^= 1 << bit;
+				return Some(self.back_index as u32 * 64 + bit);
+			}
+			if self.back_index <= self.front_index {
+				return None;
+			}
+			self.back_index -= 1;
+			if self.back_index != self.front_index {
+				self.back = storage.word(self.back_index);
+			}
+		}
+	}
+}
+
+/// Owning iterator over the indexes of the set bits, returned by [`BitIter::iter_bits`].
+#[derive(Clone, Debug)]
+pub struct BitIterator<B: Bitboard> {
 	bb: B,
+	cursor: WordCursor,
 }
 
-impl<B: Bitboard> Iterator for BitIterator<B>
-//where <B as Bitboard>::Storage: BitStorage
-{
+impl<B: Bitboard> Iterator for BitIterator<B> {
 	type Item = u32;
 
 	#[inline]
 	fn next(&mut self) -> Option<Self::Item> {
-		if self.bb.any() {
-			Some(self.bb.pop_lsb())
-		} else {
-			None
-		}
+		self.cursor.next(self.bb.storage())
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.cursor.remaining, Some(self.cursor.remaining))
 	}
 }
-impl<B: Bitboard> BitIter for B
-//where <B as Bitboard>::Storage: BitStorage
-{
+impl<B: Bitboard> DoubleEndedIterator for BitIterator<B> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.cursor.next_back(self.bb.storage())
+	}
+}
+impl<B: Bitboard> ExactSizeIterator for BitIterator<B> {}
+impl<B: Bitboard> core::iter::FusedIterator for BitIterator<B> {}
+
+/// Borrowing iterator over the indexes of the set bits, returned by [`BitIter::bits`].
+///
+/// It walks the storage word by word, so iterating a sparse `LargeBitboard` costs one load per
+/// word plus one step per set bit, and nothing is allocated.
+pub struct Bits<'a, B: Bitboard> {
+	storage: &'a B::Storage,
+	cursor: WordCursor,
+}
+
+impl<B: Bitboard> Clone for Bits<'_, B> {
+	fn clone(&self) -> Self {
+		Bits { storage: self.storage, cursor: self.cursor.clone() }
+	}
+}
+
+impl<B: Bitboard> Iterator for Bits<'_, B> {
+	type Item = u32;
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.cursor.next(self.storage)
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.cursor.remaining, Some(self.cursor.remaining))
+	}
+}
+impl<B: Bitboard> DoubleEndedIterator for Bits<'_, B> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.cursor.next_back(self.storage)
+	}
+}
+impl<B: Bitboard> ExactSizeIterator for Bits<'_, B> {}
+impl<B: Bitboard> core::iter::FusedIterator for Bits<'_, B> {}
+
+impl<B: Bitboard> BitIter for B {
 	#[inline]
 	fn iter_bits(self) -> BitIterator<Self> {
-		BitIterator { bb: self }
+		let cursor = WordCursor::new(self.storage());
+		BitIterator { bb: self, cursor }
 	}
+
+	#[inline]
+	fn bits(&self) -> Bits<'_, Self> {
+		Bits { storage: self.storage(), cursor: WordCursor::new(self.storage()) }
+	}
+}
+
+/// Returns `(x, y)` coordinates corresponding to the linear index `i` of `b`.
+#[inline(always)]
+pub(crate) fn coords_from_index<B: Bitboard>(b: &B, i: usize) -> (u8, u8) {
+	if b.col_major() {
+		((i / b.height() as usize) as u8, (i % b.height() as usize) as u8)
+	} else {
+		((i % b.width() as usize) as u8, (i / b.width() as usize) as u8)
+	}
+}
+
+/// Iterator over the coordinates of the set bits, returned by [`Bitboard::iter_coords`].
+pub struct Coords<'a, B: Bitboard> {
+	board: &'a B,
+	bits: Bits<'a, B>,
 }
 
+impl<B: Bitboard> Clone for Coords<'_, B> {
+	fn clone(&self) -> Self {
+		Coords { board: self.board, bits: self.bits.clone() }
+	}
+}
+
+impl<B: Bitboard> Iterator for Coords<'_, B> {
+	type Item = (u8, u8);
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.bits.next().map(|i| coords_from_index(self.board, i as usize))
+	}
 
-pub fn fmt_bitboard_display<B: Bitboard>(b: &B, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.bits.size_hint()
+	}
+}
+impl<B: Bitboard> DoubleEndedIterator for Coords<'_, B> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.bits.next_back().map(|i| coords_from_index(self.board, i as usize))
+	}
+}
+impl<B: Bitboard> ExactSizeIterator for Coords<'_, B> {}
+impl<B: Bitboard> core::iter::FusedIterator for Coords<'_, B> {}
+
+macro_rules! impl_line_iterator {
+	($name:ident, $extract:ident, $doc:literal) => {
+		#[doc = $doc]
+		pub struct $name<'a, B: Bitboard> {
+			board: &'a B,
+			front: u8,
+			back: u8,
+		}
+
+		impl<B: Bitboard> Clone for $name<'_, B> {
+			fn clone(&self) -> Self {
+				$name { board: self.board, front: self.front, back: self.back }
+			}
+		}
+
+		impl<B: Bitboard> Iterator for $name<'_, B> {
+			type Item = B::Storage;
+
+			#[inline]
+			fn next(&mut self) -> Option<Self::Item> {
+				if self.front == self.back {
+					return None;
+				}
+				self.front += 1;
+				Some(self.board.$extract(self.front - 1))
+			}
+
+			#[inline]
+			fn size_hint(&self) -> (usize, Option<usize>) {
+				let len = (self.back - self.front) as usize;
+				(len, Some(len))
+			}
+		}
+		impl<B: Bitboard> DoubleEndedIterator for $name<'_, B> {
+			#[inline]
+			fn next_back(&mut self) -> Option<Self::Item> {
+				if self.front == self.back {
+					return None;
+				}
+				self.back -= 1;
+				Some(self.board.$extract(self.back))
+			}
+		}
+		impl<B: Bitboard> ExactSizeIterator for $name<'_, B> {}
+		impl<B: Bitboard> core::iter::FusedIterator for $name<'_, B> {}
+	};
+}
+impl_line_iterator!(Rows, extract_row, "Iterator over the rows of a bitboard, returned by [`Bitboard::iter_rows`].");
+impl_line_iterator!(Cols, extract_col, "Iterator over the columns of a bitboard, returned by [`Bitboard::iter_cols`].");
+
+
+pub fn fmt_bitboard_display<B: Bitboard>(b: &B, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+	fmt_grid(b.width(), b.height(), |x, y| if b.get(x, y) { '#' } else { '.' }, f)
+}
+
+/// Writes a `width` x `height` grid of the characters given by `cell`, with the row and column
+/// labels of [`fmt_bitboard_display`].
+pub(crate) fn fmt_grid(width: u8, height: u8, cell: impl Fn(u8, u8) -> char, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 	const MAX_W: u8 = 70;
 	const MAX_H: u8 = 70;
-	let w = b.width().min(MAX_W);
-	let h = b.height().min(MAX_H);
+	let w = width.min(MAX_W);
+	let h = height.min(MAX_H);
 
-	let line_index_width = ((h - 1).to_string().len()).max(2);
+	// At most 70 rows, so two digits
+	let line_index_width = 2;
 
-	if b.height() > MAX_H {
+	if height > MAX_H {
 		writeln!(f, "{:>width$}   ...", "", width = line_index_width)?;
 	}
 
@@ -252,20 +593,19 @@ pub fn fmt_bitboard_display<B: Bitboard>(b: &B, f: &mut std::fmt::Formatter<'_>)
 		write!(f, "{:>width$} | ", y, width = line_index_width)?;
 
 		for x in 0..w {
-			let c = if b.get(x, y) { '#' } else { '.' };
-			write!(f, "{}", c)?;
+			write!(f, "{}", cell(x, y))?;
 		}
 
-		if b.width() > MAX_W {
+		if width > MAX_W {
 			write!(f, "...")?;
 		}
 
 		writeln!(f)?;
 	}
 
-	let prefix = " ".repeat(line_index_width + 3);
+	let prefix = line_index_width + 3;
 
-	write!(f, "{}", prefix)?;
+	write!(f, "{:prefix$}", "")?;
 	for x in 0..w {
 		if x >= 10 {
 			write!(f, "{}", (x / 10) % 10)?;
@@ -273,13 +613,13 @@ pub fn fmt_bitboard_display<B: Bitboard>(b: &B, f: &mut std::fmt::Formatter<'_>)
 			write!(f, "{}", x)?;
 		}
 	}
-	if b.width() > MAX_W {
+	if width > MAX_W {
 		write!(f, "...")?;
 	}
 	writeln!(f)?;
 
-	if b.width() >= 10 {
-		write!(f, "{}", prefix)?;
+	if width >= 10 {
+		write!(f, "{:prefix$}", "")?;
 
 		for x in 0..w {
 			if x < 10 {
@@ -289,7 +629,7 @@ pub fn fmt_bitboard_display<B: Bitboard>(b: &B, f: &mut std::fmt::Formatter<'_>)
 			}
 		}
 
-		if b.width() > MAX_W {
+		if width > MAX_W {
 			write!(f, "...")?;
 		}
 
@@ -300,45 +640,33 @@ pub fn fmt_bitboard_display<B: Bitboard>(b: &B, f: &mut std::fmt::Formatter<'_>)
 }
 
 
-pub fn fmt_bitboard_debug<B: Bitboard>(b: &B, storage_bits:usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+pub fn fmt_bitboard_debug<B: Bitboard>(b: &B, storage_bits:usize, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 	let total = b.width() as usize * b.height() as usize;
-	//let storage_bits = std::mem::size_of_val(&b.storage()) * 8;
-
-	let mut bits = Vec::new();
-	for i in 0..storage_bits {
-		bits.push(b.get_at_index(i));
-	}
-
-	let valid = &bits[..total];
-	let invalid = &bits[total..];
+	let bit = |i: usize| if b.get_at_index(i) { '1' } else { '0' };
 
-	write!(f, "{}(0b", std::any::type_name::<B>())?;
+	write!(f, "{}(0b", core::any::type_name::<B>())?;
 
-
-	if invalid.iter().any(|b| *b) {
+	// Bits of the storage beyond the board, MSB → LSB
+	if (total..storage_bits).any(|i| b.get_at_index(i)) {
 		write!(f, "(")?;
-		for b in invalid.iter().rev() { // MSB → LSB
-			write!(f, "{}", if *b { '1' } else { '0' })?;
+		for i in (total..storage_bits).rev() {
+			write!(f, "{}", bit(i))?;
 		}
 		write!(f, ")")?;
 	}
 
-	let mut valid_rev = valid.to_vec();
-	valid_rev.reverse();
-
 	let group_size = if b.col_major() {
 		b.height()
 	} else {
 		b.width()
 	} as usize;
 
-	for chunk in valid_rev.chunks(group_size) {
-		if chunk.as_ptr() != valid_rev.as_ptr() {
+	// Groups of `group_size` bits from the MSB, the last one possibly shorter
+	for (pos, i) in (0..total).rev().enumerate() {
+		if pos != 0 && pos % group_size == 0 {
 			write!(f, "_")?;
 		}
-		for b in chunk {
-			write!(f, "{}", if *b { '1' } else { '0' })?;
-		}
+		write!(f, "{}", bit(i))?;
 	}
 
 	write!(f, ")")
@@ -0,0 +1,191 @@
+//! Parsing of the `#`/`.` grid format produced by [`fmt_bitboard_display`](crate::fmt_bitboard_display).
+//!
+//! Two forms are accepted:
+//! - the display output, with its row labels (`" 7 | ..#....."`) and column labels;
+//! - a compact literal, one row of `#` and `.` per line, leading indentation allowed.
+//!
+//! In both forms the first row is the top one (highest `y`). Blank lines are ignored.
+//! Boards larger than 70x70 are truncated by the display and cannot be parsed back.
+
+use alloc::{string::String, vec::Vec};
+
+/// Reason of a [`ParseError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+	/// The text does not contain any row.
+	Empty,
+	/// A cell is neither `#` nor `.`.
+	UnexpectedChar(char),
+	/// A row does not have the same number of cells as the first one.
+	RowLength { expected: usize, found: usize },
+	/// A row label is missing, is not a number, or is not the expected `y`.
+	RowLabel { expected: usize },
+	/// Labeled and unlabeled rows are mixed.
+	MixedFormat,
+	/// The grid does not fit in the bitboard.
+	TooLarge { width: usize, height: usize },
+	/// The grid does not have the dimensions of the bitboard.
+	Shape { expected: (u8, u8), found: (u8, u8) },
+}
+
+/// Error returned when parsing a grid, with the 1-based line and column where it occurred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+	pub line: usize,
+	pub column: usize,
+	pub kind: ParseErrorKind,
+}
+impl ParseError {
+	fn new(line: usize, column: usize, kind: ParseErrorKind) -> Self {
+		Self { line, column, kind }
+	}
+}
+impl core::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "line {}, column {}: ", self.line, self.column)?;
+		match &self.kind {
+			ParseErrorKind::Empty => write!(f, "no rows to parse"),
+			ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}, expected '#' or '.'", c),
+			ParseErrorKind::RowLength { expected, found } => write!(f, "row has {} cells, expected {}", found, expected),
+			ParseErrorKind::RowLabel { expected } => write!(f, "invalid row label, expected {}", expected),
+			ParseErrorKind::MixedFormat => write!(f, "labeled and unlabeled rows are mixed"),
+			ParseErrorKind::TooLarge { width, height } => write!(f, "grid of {}x{} is too large", width, height),
+			ParseErrorKind::Shape { expected, found } => write!(f, "grid is {}x{}, expected {}x{}", found.0, found.1, expected.0, expected.1),
+		}
+	}
+}
+impl core::error::Error for ParseError {}
+
+/// A parsed grid of cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+	width: u8,
+	height: u8,
+	/// Cells, top row first.
+	cells: Vec<bool>,
+	/// Line of the first row.
+	first_line: usize,
+}
+
+/// A row, as found in the text.
+struct Row {
+	line: usize,
+	/// Column of the first cell.
+	column: usize,
+	cells: Vec<bool>,
+	/// Row label and its column, for the display form.
+	label: Option<(Option<usize>, usize)>,
+}
+
+impl Grid {
+	/// Parses a grid in the display or compact form.
+	pub fn parse(s: &str) -> Result<Self, ParseError> {
+		let mut rows: Vec<Row> = Vec::new();
+		let mut labeled = None;
+		let mut in_column_labels = false;
+
+		for (i, line) in s.lines().enumerate() {
+			let line_no = i + 1;
+			let chars: Vec<char> = line.trim_end().chars().collect();
+			let Some(start) = chars.iter().position(|c| !c.is_whitespace()) else {
+				continue;
+			};
+			let bar = chars.iter().position(|&c| c == '|');
+
+			if bar.is_none() && labeled == Some(true) && chars.iter().all(|c| c.is_ascii_digit() || c.is_whitespace()) {
+				// Column labels, below the rows of the display form
+				in_column_labels = true;
+				continue;
+			}
+			if in_column_labels || labeled.is_some_and(|l| l != bar.is_some()) {
+				return Err(ParseError::new(line_no, start + 1, ParseErrorKind::MixedFormat));
+			}
+			labeled = Some(bar.is_some());
+
+			let (label, first) = match bar {
+				Some(bar) => {
+					let text: String = chars[..bar].iter().collect();
+					let label = (text.trim().parse::<usize>().ok(), start + 1);
+					// The display writes one space after the bar
+					let first = if chars.get(bar + 1) == Some(&' ') { bar + 2 } else { bar + 1 };
+					(Some(label), first)
+				}
+				None => (None, start),
+			};
+
+			let mut cells = Vec::with_capacity(chars.len() - first);
+			for (j, &c) in chars[first..].iter().enumerate() {
+				match c {
+					'#' => cells.push(true),
+					'.' => cells.push(false),
+					_ => return Err(ParseError::new(line_no, first + j + 1, ParseErrorKind::UnexpectedChar(c))),
+				}
+			}
+			if let Some(first_row) = rows.first() && first_row.cells.len() != cells.len() {
+				let column = first + cells.len().min(first_row.cells.len()) + 1;
+				return Err(ParseError::new(line_no, column, ParseErrorKind::RowLength { expected: first_row.cells.len(), found: cells.len() }));
+			}
+			rows.push(Row { line: line_no, column: first + 1, cells, label });
+		}
+
+		let Some(first_row) = rows.first() else {
+			return Err(ParseError::new(s.lines().count().max(1), 1, ParseErrorKind::Empty));
+		};
+		let (width, height) = (first_row.cells.len(), rows.len());
+		if width == 0 {
+			return Err(ParseError::new(first_row.line, first_row.column, ParseErrorKind::Empty));
+		}
+		if width > u8::MAX as usize || height > u8::MAX as usize {
+			return Err(ParseError::new(first_row.line, first_row.column, ParseErrorKind::TooLarge { width, height }));
+		}
+		for (r, row) in rows.iter().enumerate() {
+			let expected = height - 1 - r;
+			if let Some((label, column)) = row.label && label != Some(expected) {
+				return Err(ParseError::new(row.line, column, ParseErrorKind::RowLabel { expected }));
+			}
+		}
+
+		Ok(Self {
+			width: width as u8,
+			height: height as u8,
+			cells: rows.iter().flat_map(|row| row.cells.iter().copied()).collect(),
+			first_line: first_row.line,
+		})
+	}
+
+	/// Width of the grid in cells.
+	#[inline(always)]
+	pub fn width(&self) -> u8 {
+		self.width
+	}
+	/// Height of the grid in cells.
+	#[inline(always)]
+	pub fn height(&self) -> u8 {
+		self.height
+	}
+	/// Returns the cell at coordinates `(x, y)`, `(0, 0)` being the bottom-left cell.
+	#[inline(always)]
+	pub fn get(&self, x: u8, y: u8) -> bool {
+		let row = (self.height - 1 - y) as usize;
+		self.cells[row * self.width as usize + x as usize]
+	}
+	/// Coordinates of the `#` cells.
+	pub fn iter_set(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+		(0..self.height).flat_map(move |y| (0..self.width).map(move |x| (x, y))).filter(|&(x, y)| self.get(x, y))
+	}
+	/// Returns an error if the grid is not `width` x `height`.
+	pub fn expect_shape(&self, width: u8, height: u8) -> Result<(), ParseError> {
+		if (self.width, self.height) != (width, height) {
+			return Err(ParseError::new(self.first_line, 1, ParseErrorKind::Shape { expected: (width, height), found: (self.width, self.height) }));
+		}
+		Ok(())
+	}
+	/// Returns an error if the grid has more than `max_squares` cells.
+	pub fn expect_at_most(&self, max_squares: usize) -> Result<(), ParseError> {
+		let (width, height) = (self.width as usize, self.height as usize);
+		if width * height > max_squares {
+			return Err(ParseError::new(self.first_line, 1, ParseErrorKind::TooLarge { width, height }));
+		}
+		Ok(())
+	}
+}
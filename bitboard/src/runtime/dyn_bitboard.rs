@@ -0,0 +1,526 @@
+use crate::{Bitboard, fmt_bitboard_display, runtime::{BitboardShape, RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}, WordStorage};
+
+/// Backend of a [`DynBitboard`]: the narrowest runtime bitboard for its shape.
+///
+/// It is also used as the `Storage` of [`DynBitboard`]: compressed bitfields returned by
+/// `pext` or `extract_row`/`extract_col` are kept in a board of the same backend and shape.
+/// The backend can be read with [`as_small`](Self::as_small) and friends but not chosen, so
+/// that boards of the same shape always have the same backend, even after `storage_mut`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DynStorage(Inner);
+
+#[derive(Clone, PartialEq, Eq)]
+enum Inner {
+	Small(SmallBitboard),
+	Medium(MediumBitboard),
+	Large(LargeBitboard),
+}
+
+/// Runtime bitboard choosing the narrowest backend for its shape, the same way the
+/// proc macro picks its storage: [`SmallBitboard`] up to 64 squares,
+/// [`MediumBitboard`] up to 128 squares and [`LargeBitboard`] above.
+/// Boards converted with `From` are moved to that backend if they are on another one.
+///
+/// Binary operators panic when the operands do not have the same shape, even in release
+/// builds since the backends may differ; use [`RuntimeBitboard::try_and`] and friends
+/// to get a [`ShapeError`](super::ShapeError) instead.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DynBitboard {
+	inner: DynStorage,
+}
+
+/// Calls the same expression on the backend, whatever it is.
+macro_rules! dispatch {
+	($storage:expr, $b:ident => $e:expr) => {
+		match $storage {
+			DynStorage(Inner::Small($b)) => $e,
+			DynStorage(Inner::Medium($b)) => $e,
+			DynStorage(Inner::Large($b)) => $e,
+		}
+	};
+}
+
+/// Calls the same expression on the backend and wraps the result in the same backend.
+macro_rules! map {
+	($storage:expr, $b:ident => $e:expr) => {
+		match $storage {
+			DynStorage(Inner::Small($b)) => DynStorage(Inner::Small($e)),
+			DynStorage(Inner::Medium($b)) => DynStorage(Inner::Medium($e)),
+			DynStorage(Inner::Large($b)) => DynStorage(Inner::Large($e)),
+		}
+	};
+}
+
+/// Calls a `RuntimeBitboard` constructor on the backend selected by the shape.
+macro_rules! select {
+	($w:expr, $h:expr, $f:ident($($arg:expr),*)) => {
+		DynBitboard {
+			inner: match Backend::for_shape($w, $h) {
+				Backend::Small => DynStorage(Inner::Small(SmallBitboard::$f($($arg),*))),
+				Backend::Medium => DynStorage(Inner::Medium(MediumBitboard::$f($($arg),*))),
+				Backend::Large => DynStorage(Inner::Large(LargeBitboard::$f($($arg),*))),
+			}
+		}
+	};
+}
+
+/// Calls the same binary operation on two backends, `$mismatch` is evaluated when they differ.
+macro_rules! zip {
+	($lhs:expr, $rhs:expr, ($a:ident, $b:ident) => $e:expr, _ => $mismatch:expr) => {
+		match ($lhs, $rhs) {
+			(DynStorage(Inner::Small($a)), DynStorage(Inner::Small($b))) => $e,
+			(DynStorage(Inner::Medium($a)), DynStorage(Inner::Medium($b))) => $e,
+			(DynStorage(Inner::Large($a)), DynStorage(Inner::Large($b))) => $e,
+			_ => $mismatch,
+		}
+	};
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Backend {
+	Small,
+	Medium,
+	Large,
+}
+impl Backend {
+	#[inline(always)]
+	fn for_shape(w: u8, h: u8) -> Self {
+		match w as usize * h as usize {
+			0..=64 => Backend::Small,
+			65..=128 => Backend::Medium,
+			_ => Backend::Large,
+		}
+	}
+}
+
+impl DynStorage {
+	/// The backend, if it is a [`SmallBitboard`] (up to 64 squares).
+	#[inline(always)]
+	pub fn as_small(&self) -> Option<&SmallBitboard> {
+		if let Inner::Small(b) = &self.0 { Some(b) } else { None }
+	}
+	/// The backend, if it is a [`MediumBitboard`] (65 to 128 squares).
+	#[inline(always)]
+	pub fn as_medium(&self) -> Option<&MediumBitboard> {
+		if let Inner::Medium(b) = &self.0 { Some(b) } else { None }
+	}
+	/// The backend, if it is a [`LargeBitboard`] (more than 128 squares).
+	#[inline(always)]
+	pub fn as_large(&self) -> Option<&LargeBitboard> {
+		if let Inner::Large(b) = &self.0 { Some(b) } else { None }
+	}
+
+	#[inline(always)]
+	fn shape(&self) -> BitboardShape {
+		dispatch!(self, b => b.shape())
+	}
+	/// Builds a backend of shape `(w, h, col_major)` from any word storage.
+	fn from_words(w: u8, h: u8, col_major: bool, words: &impl WordStorage) -> Self {
+		let word = |i: usize| if i < words.nb_words() { words.word(i) } else { 0 };
+		match Backend::for_shape(w, h) {
+			Backend::Small => DynStorage(Inner::Small(SmallBitboard::new(w, h, col_major, word(0)))),
+			Backend::Medium => DynStorage(Inner::Medium(MediumBitboard::new(w, h, col_major, word(0) as u128 | (word(1) as u128) << 64))),
+			Backend::Large => {
+				let nb_words = (w as usize * h as usize).div_ceil(64);
+				DynStorage(Inner::Large(LargeBitboard::new(w, h, col_major, (0..nb_words).map(word).collect())))
+			}
+		}
+	}
+}
+impl WordStorage for DynStorage {
+	#[inline(always)]
+	fn nb_words(&self) -> usize {
+		dispatch!(self, b => b.storage().nb_words())
+	}
+	#[inline(always)]
+	fn word(&self, i: usize) -> u64 {
+		dispatch!(self, b => b.storage().word(i))
+	}
+}
+
+impl DynBitboard {
+	/// Creates an empty bitboard of `shape`, on the narrowest backend able to hold it.
+	#[inline(always)]
+	pub fn for_shape(shape: BitboardShape) -> Self {
+		Self::empty(shape.w, shape.h, shape.col_major)
+	}
+	/// Returns the backend.
+	#[inline(always)]
+	pub fn inner(&self) -> &DynStorage {
+		&self.inner
+	}
+	/// Consumes the bitboard and returns its backend.
+	#[inline(always)]
+	pub fn into_inner(self) -> DynStorage {
+		self.inner
+	}
+
+	#[inline(always)]
+	fn assert_same_shape(&self, other: &Self) {
+		if let Err(e) = self.check_shape(other) {
+			panic!("{}", e);
+		}
+	}
+}
+
+impl From<SmallBitboard> for DynBitboard {
+	#[inline(always)]
+	fn from(b: SmallBitboard) -> Self {
+		let inner = match Backend::for_shape(b.width(), b.height()) {
+			Backend::Small => DynStorage(Inner::Small(b)),
+			_ => DynStorage::from_words(b.width(), b.height(), b.col_major(), b.storage()),
+		};
+		Self { inner }
+	}
+}
+impl From<MediumBitboard> for DynBitboard {
+	#[inline(always)]
+	fn from(b: MediumBitboard) -> Self {
+		let inner = match Backend::for_shape(b.width(), b.height()) {
+			Backend::Medium => DynStorage(Inner::Medium(b)),
+			_ => DynStorage::from_words(b.width(), b.height(), b.col_major(), b.storage()),
+		};
+		Self { inner }
+	}
+}
+impl From<LargeBitboard> for DynBitboard {
+	#[inline(always)]
+	fn from(b: LargeBitboard) -> Self {
+		let inner = match Backend::for_shape(b.width(), b.height()) {
+			Backend::Large => DynStorage(Inner::Large(b)),
+			_ => DynStorage::from_words(b.width(), b.height(), b.col_major(), b.storage()),
+		};
+		Self { inner }
+	}
+}
+
+impl RuntimeBitboard for DynBitboard {
+	#[inline(always)]
+	fn shape(&self) -> BitboardShape {
+		self.inner.shape()
+	}
+	/// Creates a bitboard from the words of `bits`, converted to the backend selected by the shape.
+	#[inline(always)]
+	fn new(w: u8, h: u8, col_major: bool, bits: DynStorage) -> Self {
+		Self { inner: DynStorage::from_words(w, h, col_major, &bits) }
+	}
+	#[inline(always)]
+	fn full(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, full(w, h, col_major))
+	}
+	#[inline(always)]
+	fn empty(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, empty(w, h, col_major))
+	}
+	#[inline(always)]
+	fn borders(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, borders(w, h, col_major))
+	}
+	#[inline(always)]
+	fn west_border(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, west_border(w, h, col_major))
+	}
+	#[inline(always)]
+	fn east_border(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, east_border(w, h, col_major))
+	}
+	#[inline(always)]
+	fn south_border(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, south_border(w, h, col_major))
+	}
+	#[inline(always)]
+	fn north_border(w: u8, h: u8, col_major: bool) -> Self {
+		select!(w, h, north_border(w, h, col_major))
+	}
+	#[inline(always)]
+	fn row_mask(w: u8, h: u8, col_major: bool, y: u8) -> Self {
+		select!(w, h, row_mask(w, h, col_major, y))
+	}
+	#[inline(always)]
+	fn col_mask(w: u8, h: u8, col_major: bool, x: u8) -> Self {
+		select!(w, h, col_mask(w, h, col_major, x))
+	}
+
+	fn index_from_coords(w: u8, h: u8, col_major: bool, x: u8, y: u8) -> usize {
+		if col_major {
+			x as usize * h as usize + y as usize
+		} else {
+			y as usize * w as usize + x as usize
+		}
+	}
+	fn coords_from_index(w: u8, h: u8, col_major: bool, i: usize) -> (u8, u8) {
+		if col_major {
+			((i / h as usize) as u8, (i % h as usize) as u8)
+		} else {
+			((i % w as usize) as u8, (i / w as usize) as u8)
+		}
+	}
+	fn is_in_bounds(w: u8, h: u8, x: u8, y: u8) -> bool {
+		x < w && y < h
+	}
+	fn is_index_in_bounds(w: u8, h: u8, i: usize) -> bool {
+		i < w as usize * h as usize
+	}
+
+	#[inline(always)]
+	fn full_with_same_shape(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.full_with_same_shape()) }
+	}
+	#[inline(always)]
+	fn empty_with_same_shape(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.empty_with_same_shape()) }
+	}
+	#[inline(always)]
+	fn west_border_with_same_shape(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.west_border_with_same_shape()) }
+	}
+	#[inline(always)]
+	fn east_border_with_same_shape(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.east_border_with_same_shape()) }
+	}
+	#[inline(always)]
+	fn north_border_with_same_shape(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.north_border_with_same_shape()) }
+	}
+	#[inline(always)]
+	fn south_border_with_same_shape(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.south_border_with_same_shape()) }
+	}
+	#[inline(always)]
+	fn row_mask_with_same_shape(&self, y: u8) -> Self {
+		Self { inner: map!(&self.inner, b => b.row_mask_with_same_shape(y)) }
+	}
+	#[inline(always)]
+	fn col_mask_with_same_shape(&self, x: u8) -> Self {
+		Self { inner: map!(&self.inner, b => b.col_mask_with_same_shape(x)) }
+	}
+
+	#[inline(always)]
+	fn index_from_coords_with_same_shape(&self, x: u8, y: u8) -> usize {
+		Self::index_from_coords(self.width(), self.height(), self.col_major(), x, y)
+	}
+	#[inline(always)]
+	fn coords_from_index_with_same_shape(&self, i: usize) -> (u8, u8) {
+		Self::coords_from_index(self.width(), self.height(), self.col_major(), i)
+	}
+	#[inline(always)]
+	fn is_in_bounds_with_same_shape(&self, x: u8, y: u8) -> bool {
+		Self::is_in_bounds(self.width(), self.height(), x, y)
+	}
+	#[inline(always)]
+	fn is_index_in_bounds_with_same_shape(&self, i: usize) -> bool {
+		Self::is_index_in_bounds(self.width(), self.height(), i)
+	}
+}
+
+impl Bitboard for DynBitboard {
+	type Storage = DynStorage;
+	#[inline(always)]
+	fn width(&self) -> u8 { dispatch!(&self.inner, b => b.width()) }
+	#[inline(always)]
+	fn height(&self) -> u8 { dispatch!(&self.inner, b => b.height()) }
+	#[inline(always)]
+	fn col_major(&self) -> bool { dispatch!(&self.inner, b => b.col_major()) }
+
+	#[inline(always)]
+	fn is_empty(&self) -> bool {
+		dispatch!(&self.inner, b => b.is_empty())
+	}
+	#[inline(always)]
+	fn count(&self) -> u32 {
+		dispatch!(&self.inner, b => b.count())
+	}
+	#[inline(always)]
+	fn intersects(&self, other: &Self) -> bool {
+		self.assert_same_shape(other);
+		zip!(&self.inner, &other.inner, (a, b) => a.intersects(b), _ => unreachable!())
+	}
+	#[inline(always)]
+	fn storage(&self) -> &Self::Storage {
+		&self.inner
+	}
+	#[inline(always)]
+	fn storage_mut(&mut self) -> &mut Self::Storage {
+		&mut self.inner
+	}
+
+	#[inline(always)]
+	fn get_at_index(&self, idx: usize) -> bool {
+		dispatch!(&self.inner, b => b.get_at_index(idx))
+	}
+	#[inline(always)]
+	fn assign_at_index(&mut self, idx: usize, val: bool) {
+		dispatch!(&mut self.inner, b => b.assign_at_index(idx, val))
+	}
+	#[inline(always)]
+	fn set_at_index(&mut self, idx: usize) {
+		dispatch!(&mut self.inner, b => b.set_at_index(idx))
+	}
+	#[inline(always)]
+	fn reset_at_index(&mut self, idx: usize) {
+		dispatch!(&mut self.inner, b => b.reset_at_index(idx))
+	}
+	#[inline(always)]
+	fn toggle_at_index(&mut self, idx: usize) {
+		dispatch!(&mut self.inner, b => b.toggle_at_index(idx))
+	}
+	#[inline(always)]
+	fn get(&self, x: u8, y: u8) -> bool {
+		dispatch!(&self.inner, b => b.get(x, y))
+	}
+	#[inline(always)]
+	fn assign(&mut self, x: u8, y: u8, val: bool) {
+		dispatch!(&mut self.inner, b => b.assign(x, y, val))
+	}
+	#[inline(always)]
+	fn set(&mut self, x: u8, y: u8) {
+		dispatch!(&mut self.inner, b => b.set(x, y))
+	}
+	#[inline(always)]
+	fn reset(&mut self, x: u8, y: u8) {
+		dispatch!(&mut self.inner, b => b.reset(x, y))
+	}
+
+	#[inline(always)]
+	fn flipped(&self) -> Self {
+		Self { inner: map!(&self.inner, b => b.flipped()) }
+	}
+	#[inline]
+	#[allow(deprecated)]
+	fn lsb(&self) -> u32 {
+		dispatch!(&self.inner, b => b.lsb())
+	}
+	#[inline]
+	#[allow(deprecated)]
+	fn msb(&self) -> u32 {
+		dispatch!(&self.inner, b => b.msb())
+	}
+	#[inline]
+	fn pop_lsb(&mut self) -> u32 {
+		dispatch!(&mut self.inner, b => b.pop_lsb())
+	}
+	#[inline]
+	fn pext(&self, mask: &Self) -> Self::Storage {
+		self.assert_same_shape(mask);
+		let (w, h, col_major) = (self.width(), self.height(), self.col_major());
+		zip!(&self.inner, &mask.inner, (a, m) => DynStorage::from_words(w, h, col_major, &a.pext(m)), _ => unreachable!())
+	}
+	#[inline]
+	fn pdep(&self, compressed: Self::Storage) -> Self {
+		let word = |i: usize| if i < compressed.nb_words() { compressed.word(i) } else { 0 };
+		let inner = match &self.inner {
+			DynStorage(Inner::Small(m)) => DynStorage(Inner::Small(m.pdep(word(0)))),
+			DynStorage(Inner::Medium(m)) => DynStorage(Inner::Medium(m.pdep(word(0) as u128 | (word(1) as u128) << 64))),
+			DynStorage(Inner::Large(m)) => {
+				let nb_words = m.storage().len();
+				DynStorage(Inner::Large(m.pdep((0..nb_words).map(word).collect())))
+			}
+		};
+		Self { inner }
+	}
+
+	#[inline(always)]
+	fn extract_row(&self, y: u8) -> Self::Storage {
+		let mask = self.row_mask_with_same_shape(y);
+		self.pext(&mask)
+	}
+	#[inline(always)]
+	fn extract_col(&self, x: u8) -> Self::Storage {
+		let mask = self.col_mask_with_same_shape(x);
+		self.pext(&mask)
+	}
+	#[inline(always)]
+	fn insert_row(&mut self, y: u8, row_bits: Self::Storage) {
+		let mask = self.row_mask_with_same_shape(y);
+		let new_row = mask.pdep(row_bits);
+		*self &= !mask;
+		*self |= new_row;
+	}
+	#[inline(always)]
+	fn insert_col(&mut self, x: u8, col_bits: Self::Storage) {
+		let mask = self.col_mask_with_same_shape(x);
+		let new_col = mask.pdep(col_bits);
+		*self &= !mask;
+		*self |= new_col;
+	}
+}
+
+macro_rules! impl_dyn_binary_op {
+	($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+		impl core::ops::$op for DynBitboard {
+			type Output = Self;
+
+			#[inline(always)]
+			fn $fn(mut self, rhs: Self) -> Self {
+				core::ops::$op_assign::$fn_assign(&mut self, rhs);
+				self
+			}
+		}
+		impl core::ops::$op_assign for DynBitboard {
+			#[inline(always)]
+			fn $fn_assign(&mut self, rhs: Self) {
+				self.assert_same_shape(&rhs);
+				zip!(&mut self.inner, rhs.inner, (a, b) => core::ops::$op_assign::$fn_assign(a, b), _ => unreachable!())
+			}
+		}
+	};
+}
+impl_dyn_binary_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_dyn_binary_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_dyn_binary_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl core::ops::Not for DynBitboard {
+	type Output = Self;
+
+	#[inline(always)]
+	fn not(self) -> Self {
+		Self { inner: map!(self.inner, b => !b) }
+	}
+}
+
+impl core::ops::Shl<usize> for DynBitboard {
+	type Output = Self;
+
+	#[inline(always)]
+	fn shl(mut self, rhs: usize) -> Self {
+		self <<= rhs;
+		self
+	}
+}
+impl core::ops::Shr<usize> for DynBitboard {
+	type Output = Self;
+
+	#[inline(always)]
+	fn shr(mut self, rhs: usize) -> Self {
+		self >>= rhs;
+		self
+	}
+}
+impl core::ops::ShlAssign<usize> for DynBitboard {
+	#[inline(always)]
+	fn shl_assign(&mut self, rhs: usize) {
+		dispatch!(&mut self.inner, b => *b <<= rhs)
+	}
+}
+impl core::ops::ShrAssign<usize> for DynBitboard {
+	#[inline(always)]
+	fn shr_assign(&mut self, rhs: usize) {
+		dispatch!(&mut self.inner, b => *b >>= rhs)
+	}
+}
+
+impl core::fmt::Display for DynBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		fmt_bitboard_display(self, f)
+	}
+}
+impl core::fmt::Debug for DynBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		dispatch!(&self.inner, b => core::fmt::Debug::fmt(b, f))
+	}
+}
+impl core::fmt::Debug for DynStorage {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		dispatch!(self, b => core::fmt::Debug::fmt(b, f))
+	}
+}
@@ -1,61 +1,81 @@
-use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::RuntimeBitboard};
+use alloc::{boxed::Box, vec};
 
+use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::{BitboardShape, RuntimeBitboard, debug_assert_same_shape}};
 
-#[derive(Clone, PartialEq, Eq)]
+
+#[derive(PartialEq, Eq)]
 pub struct LargeBitboard {
-	w: u8,
-	h: u8,
-	col_major: bool,
+	shape: BitboardShape,
 	bits: Box<[u64]>,
 }
+impl Clone for LargeBitboard {
+	#[inline]
+	fn clone(&self) -> Self {
+		Self { shape: self.shape, bits: self.bits.clone() }
+	}
+	/// Copies `source` into the words of `self`, allocating only if their numbers differ.
+	#[inline]
+	fn clone_from(&mut self, source: &Self) {
+		self.shape = source.shape;
+		if self.bits.len() == source.bits.len() {
+			self.bits.copy_from_slice(&source.bits);
+		} else {
+			self.bits = source.bits.clone();
+		}
+	}
+}
 impl RuntimeBitboard for LargeBitboard {
+	#[inline(always)]
+	fn shape(&self) -> BitboardShape {
+		self.shape
+	}
 
 	#[inline(always)]
 	fn new(w: u8, h: u8, col_major: bool, bits:Box<[u64]>) -> Self {
-		Self { w, h, col_major, bits }
+		Self { shape: BitboardShape::new(w, h, col_major), bits }
 	}
 	#[inline(always)]
 	fn west_border_with_same_shape(&self) -> Self {
-		Self::west_border(self.w, self.h, self.col_major)
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn east_border_with_same_shape(&self) -> Self {
-		Self::east_border(self.w, self.h, self.col_major)
+		Self::east_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn north_border_with_same_shape(&self) -> Self {
-		Self::north_border(self.w, self.h, self.col_major)
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn south_border_with_same_shape(&self) -> Self {
-		Self::south_border(self.w, self.h, self.col_major)
+		Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn full_with_same_shape(&self) -> Self {
-		Self::full(self.w, self.h, self.col_major)
+		Self::full(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn empty_with_same_shape(&self) -> Self {
-		Self::empty(self.w, self.h, self.col_major)
+		Self::empty(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn index_from_coords_with_same_shape(&self, x: u8, y: u8) -> usize {
-		Self::index_from_coords(self.w, self.h, self.col_major, x, y)
+		Self::index_from_coords(self.shape.w, self.shape.h, self.shape.col_major, x, y)
 	}
 	
 	#[inline(always)]
 	fn coords_from_index_with_same_shape(&self, i: usize) -> (u8, u8) {
-		Self::coords_from_index(self.w, self.h, self.col_major, i)
+		Self::coords_from_index(self.shape.w, self.shape.h, self.shape.col_major, i)
 	}
 	
 	#[inline(always)]
 	fn is_in_bounds_with_same_shape(&self, x: u8, y: u8) -> bool {
-		Self::is_in_bounds(self.w, self.h, x, y)
+		Self::is_in_bounds(self.shape.w, self.shape.h, x, y)
 	}
 	
 	#[inline(always)]
 	fn is_index_in_bounds_with_same_shape(&self, i: usize) -> bool {
-		Self::is_index_in_bounds(self.w, self.h, i)
+		Self::is_index_in_bounds(self.shape.w, self.shape.h, i)
 	}
 	#[inline(always)]
 	fn borders(w: u8, h: u8, col_major: bool) -> Self {
@@ -63,6 +83,9 @@ impl RuntimeBitboard for LargeBitboard {
 		Self::north_border(w, h, col_major) | Self::south_border(w, h, col_major)
 	}
 	fn west_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::col_mask(w, h, col_major, 0);
+		}
 		let nb_bits = w as usize * h as usize;
 		let nb_words = nb_bits.div_ceil(64);
 
@@ -92,9 +115,7 @@ impl RuntimeBitboard for LargeBitboard {
 		let nb_words= nb_bits.div_ceil(64);
 
 		Self {
-			w,
-			h,
-			col_major,
+			shape: BitboardShape::new(w, h, col_major),
 			bits: vec![0u64; nb_words].into_boxed_slice(),
 		}
 	}
@@ -113,15 +134,19 @@ impl RuntimeBitboard for LargeBitboard {
 	}
 	#[inline(always)]
 	fn row_mask_with_same_shape(&self, y: u8) -> Self {
-		Self::row_mask(self.w, self.h, self.col_major, y)
+		Self::row_mask(self.shape.w, self.shape.h, self.shape.col_major, y)
 	}
 	#[inline(always)]
 	fn col_mask_with_same_shape(&self, x: u8) -> Self {
-		Self::col_mask(self.w, self.h, self.col_major, x)
+		Self::col_mask(self.shape.w, self.shape.h, self.shape.col_major, x)
 	}
 	fn row_mask(w: u8, h: u8, col_major: bool, y: u8) -> Self {
 		if col_major {
-			Self::west_border(w,h,col_major) << y
+			let mut row = Self::empty(w, h, col_major);
+			for x in 0..w {
+				row.set_at_index(x as usize * h as usize + y as usize);
+			}
+			row
 		} else {
 			let mut row = Self::empty(w, h, col_major);
 			row.bits[0]=1;
@@ -171,28 +196,19 @@ impl RuntimeBitboard for LargeBitboard {
 impl Bitboard for LargeBitboard {
 	type Storage=Box<[u64]>;
 	#[inline(always)]
-	fn width(&self) -> u8 { self.w }
+	fn width(&self) -> u8 { self.shape.w }
 	#[inline(always)]
-	fn height(&self) -> u8 { self.h }
+	fn height(&self) -> u8 { self.shape.h }
 	#[inline(always)]
-	fn col_major(&self) -> bool { self.col_major }
+	fn col_major(&self) -> bool { self.shape.col_major }
 
 	#[inline]
 	fn is_empty(&self) -> bool {
-		for a in self.bits.iter() {
-			if *a != 0 {
-				return false;
-			}
-		}
-		true
+		crate::simd::is_zero(&self.bits)
 	}
 	#[inline]
 	fn count(&self) -> u32 {
-		let mut counts=0;
-		for a in self.bits.iter() {
-			counts+=a.count_ones();
-		}
-		counts
+		crate::simd::count_ones(&self.bits)
 	}
 	#[inline]
 	fn intersects(&self, other: &Self) -> bool {
@@ -270,7 +286,7 @@ impl Bitboard for LargeBitboard {
 		for b in a.iter_mut() {
 			*b = !(*b);
 		}
-		Self{ w: self.w, h: self.h, col_major: self.col_major, bits: a }
+		Self { shape: self.shape, bits: a }
 	}
 	#[inline]
 	fn lsb(&self) -> u32 {
@@ -302,64 +318,16 @@ impl Bitboard for LargeBitboard {
 	}
 
 	fn pext(&self, mask: &Self) -> Self::Storage {
-		let mut out = Vec::new();
-		let mut current: u64 = 0;
-		let mut bitpos = 0;
-
-		for (a, m) in self.bits.iter().zip(mask.bits.iter()) {
-			let mut mm = *m;
-			while mm != 0 {
-				let lsb = mm.trailing_zeros();
-				let bit = (a >> lsb) & 1;
-
-				current |= bit << bitpos;
-				bitpos += 1;
-
-				if bitpos == 64 {
-					out.push(current);
-					current = 0;
-					bitpos = 0;
-				}
-
-				mm &= mm - 1;
-			}
-		}
-
-		if bitpos > 0 {
-			out.push(current);
-		}
-
+		let nb_bits = crate::simd::count_ones(&mask.bits) as usize;
+		let mut out = vec![0u64; nb_bits.div_ceil(64)];
+		crate::bmi2::pext_words(&self.bits, &mask.bits, &mut out);
 		out.into_boxed_slice()
 	}
 
 	fn pdep(&self, compressed: Self::Storage) -> Self {
 		let mut out = vec![0u64; self.bits.len()];
-
-		let mut src_word_index = 0;
-		let mut src_bit_index = 0;
-
-		for (out_word, mask_word) in out.iter_mut().zip(self.bits.iter()) {
-			let mut mm = *mask_word;
-
-			while mm != 0 {
-				let dst_bit = mm.trailing_zeros();
-
-				let src_word = compressed[src_word_index];
-				let src_bit = (src_word >> src_bit_index) & 1;
-
-				*out_word |= src_bit << dst_bit;
-
-				src_bit_index += 1;
-				if src_bit_index == 64 {
-					src_bit_index = 0;
-					src_word_index += 1;
-				}
-
-				mm &= mm - 1;
-			}
-		}
-
-		Self::new(self.w, self.h, self.col_major, out.into_boxed_slice())
+		crate::bmi2::pdep_words(&compressed, &self.bits, &mut out);
+		Self::new(self.shape.w, self.shape.h, self.shape.col_major, out.into_boxed_slice())
 	}
 
 	#[inline(always)]
@@ -395,100 +363,109 @@ impl Bitboard for LargeBitboard {
 	}
 
 }
-impl std::ops::BitAnd for LargeBitboard {
+impl core::ops::BitAnd for LargeBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitand(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self &= rhs;
 		self
 	}
 }
 
-impl std::ops::BitOr for LargeBitboard {
+impl core::ops::BitOr for LargeBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitor(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self |= rhs;
 		self
 	}
 }
 
-impl std::ops::BitXor for LargeBitboard {
+impl core::ops::BitXor for LargeBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitxor(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self ^= rhs;
 		self
 	}
 }
 
 
-impl std::ops::BitAndAssign<&Self> for LargeBitboard {
+impl core::ops::BitAndAssign<&Self> for LargeBitboard {
 	#[inline(always)]
 	fn bitand_assign(&mut self, rhs: &Self) {
-		for (a, b) in self.bits.iter_mut().zip(rhs.bits.iter()) {
-			*a &= *b;
-		}
+		debug_assert_same_shape(&self.shape, &rhs.shape);
+		crate::simd::and_assign(&mut self.bits, &rhs.bits);
 	}
 }
 
-impl std::ops::BitOrAssign<&Self> for LargeBitboard {
+impl core::ops::BitOrAssign<&Self> for LargeBitboard {
 	#[inline(always)]
 	fn bitor_assign(&mut self, rhs: &Self) {
-		for (a, b) in self.bits.iter_mut().zip(rhs.bits.iter()) {
-			*a |= *b;
-		}
+		debug_assert_same_shape(&self.shape, &rhs.shape);
+		crate::simd::or_assign(&mut self.bits, &rhs.bits);
 	}
 }
 
-impl std::ops::BitXorAssign<&Self> for LargeBitboard {
+impl core::ops::BitXorAssign<&Self> for LargeBitboard {
 	#[inline(always)]
 	fn bitxor_assign(&mut self, rhs: &Self) {
-		for (a, b) in self.bits.iter_mut().zip(rhs.bits.iter()) {
-			*a ^= *b;
-		}
-		//maybe one day
-		//std::simd::Simd::<u64,8>::from_slice(&self.bits);
+		debug_assert_same_shape(&self.shape, &rhs.shape);
+		crate::simd::xor_assign(&mut self.bits, &rhs.bits);
 	}
 }
-impl std::ops::BitAndAssign for LargeBitboard {
+impl core::ops::BitAndAssign for LargeBitboard {
 	#[inline(always)]
 	fn bitand_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		*self &= &rhs;
 	}
 }
 
-impl std::ops::BitOrAssign for LargeBitboard {
+impl core::ops::BitOrAssign for LargeBitboard {
 	#[inline(always)]
 	fn bitor_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		*self |= &rhs;
 	}
 }
 
-impl std::ops::BitXorAssign for LargeBitboard {
+impl core::ops::BitXorAssign for LargeBitboard {
 	#[inline(always)]
 	fn bitxor_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		*self ^= &rhs;
 	}
 }
 
-impl std::ops::Not for LargeBitboard {
+impl LargeBitboard {
+	/// Complements every word, including the bits past the last square.
+	#[inline(always)]
+	fn not_assign(&mut self) {
+		crate::simd::not_assign(&mut self.bits);
+	}
+}
+
+impl core::ops::Not for LargeBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn not(mut self) -> Self {
-		for a in self.bits.iter_mut() {
-			*a = !*a;
-		}
+		self.not_assign();
 		self
 	}
 
 }
 
-impl std::ops::Shl<usize> for LargeBitboard {
+super::impl_borrowed_ops!([] LargeBitboard);
+
+impl core::ops::Shl<usize> for LargeBitboard {
 	type Output = Self;
 	fn shl(mut self, rhs: usize) -> Self {
 		self <<= rhs;
@@ -496,7 +473,7 @@ impl std::ops::Shl<usize> for LargeBitboard {
 	}
 }
 
-impl std::ops::Shr<usize> for LargeBitboard {
+impl core::ops::Shr<usize> for LargeBitboard {
 	type Output = Self;
 	fn shr(mut self, rhs: usize) -> Self {
 		self >>= rhs;
@@ -504,74 +481,20 @@ impl std::ops::Shr<usize> for LargeBitboard {
 	}
 }
 
-impl std::ops::ShlAssign<usize> for LargeBitboard {
+impl core::ops::ShlAssign<usize> for LargeBitboard {
 	#[inline(always)]
 	fn shl_assign(&mut self, rhs: usize) {
-		let word_shift = rhs / 64;
-		let bit_shift  = rhs % 64;
-
-		let len = self.bits.len();
-
-		if word_shift >= len {
-			for w in self.bits.iter_mut() {
-				*w = 0;
-			}
-			return;
-		}
-
-		if word_shift > 0 {
-			for i in (word_shift..len).rev() {
-				self.bits[i] = self.bits[i - word_shift];
-			}
-			for i in 0..word_shift {
-				self.bits[i] = 0;
-			}
-		}
-
-		if bit_shift > 0 {
-			for i in (1..len).rev() {
-				self.bits[i] = (self.bits[i] << bit_shift)
-					| (self.bits[i - 1] >> (64 - bit_shift));
-			}
-			self.bits[0] <<= bit_shift;
-		}
+		crate::simd::shl_assign(&mut self.bits, rhs);
 	}
 }
 
-impl std::ops::ShrAssign<usize> for LargeBitboard {
+impl core::ops::ShrAssign<usize> for LargeBitboard {
 	#[inline(always)]
 	fn shr_assign(&mut self, rhs: usize) {
-		let word_shift = rhs / 64;
-		let bit_shift  = rhs % 64;
-
-		let len = self.bits.len();
-
-		if word_shift >= len {
-			for w in self.bits.iter_mut() {
-				*w = 0;
-			}
-			return;
-		}
-
-		if word_shift > 0 {
-			for i in 0..len - word_shift {
-				self.bits[i] = self.bits[i + word_shift];
-			}
-			for i in len - word_shift..len {
-				self.bits[i] = 0;
-			}
-		}
-
-		if bit_shift > 0 {
-			for i in 0..len - 1 {
-				self.bits[i] = (self.bits[i] >> bit_shift)
-					| (self.bits[i + 1] << (64 - bit_shift));
-			}
-			self.bits[len - 1] >>= bit_shift;
-		}
+		crate::simd::shr_assign(&mut self.bits, rhs);
 	}
 }
-impl std::ops::Shl<u8> for LargeBitboard {
+impl core::ops::Shl<u8> for LargeBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -581,7 +504,7 @@ impl std::ops::Shl<u8> for LargeBitboard {
 	}
 }
 
-impl std::ops::Shr<u8> for LargeBitboard {
+impl core::ops::Shr<u8> for LargeBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -590,14 +513,14 @@ impl std::ops::Shr<u8> for LargeBitboard {
 		self
 	}
 }
-impl std::ops::ShlAssign<u8> for LargeBitboard {
+impl core::ops::ShlAssign<u8> for LargeBitboard {
 	#[inline(always)]
 	fn shl_assign(&mut self, rhs: u8) {
 		*self <<= rhs as usize;
 	}
 }
 
-impl std::ops::ShrAssign<u8> for LargeBitboard {
+impl core::ops::ShrAssign<u8> for LargeBitboard {
 	#[inline(always)]
 	fn shr_assign(&mut self, rhs: u8) {
 		*self >>= rhs as usize;
@@ -605,7 +528,7 @@ impl std::ops::ShrAssign<u8> for LargeBitboard {
 }
 
 
-impl std::ops::SubAssign<usize> for LargeBitboard {
+impl core::ops::SubAssign<usize> for LargeBitboard {
 	#[inline(always)]
 	fn sub_assign(&mut self, rhs: usize) {
 		let mut carry = rhs as u64;
@@ -621,7 +544,7 @@ impl std::ops::SubAssign<usize> for LargeBitboard {
 		}
 	}
 }
-impl std::ops::Sub<usize> for LargeBitboard {
+impl core::ops::Sub<usize> for LargeBitboard {
 	type Output = Self;
 
 	fn sub(mut self, rhs: usize) -> Self::Output {
@@ -631,14 +554,14 @@ impl std::ops::Sub<usize> for LargeBitboard {
 }
 
 
-impl std::fmt::Display for LargeBitboard {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for LargeBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		fmt_bitboard_display(self, f)
 	}
 }
 
-impl std::fmt::Debug for LargeBitboard {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for LargeBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		fmt_bitboard_debug(self, self.bits.len() * 64, f)
 	}
 }
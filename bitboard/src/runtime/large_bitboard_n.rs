@@ -0,0 +1,442 @@
+use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::{BitboardShape, RuntimeBitboard, debug_assert_same_shape}};
+
+/// A runtime bitboard of up to `WORDS * 64` squares, stored inline in `[u64; WORDS]`.
+///
+/// The stack-allocated counterpart of [`LargeBitboard`](crate::runtime::large_bitboard::LargeBitboard):
+/// it is `Copy`, never allocates, and is available without `alloc`. Unlike `LargeBitboard`,
+/// `!` and `<<` clear the bits past the last square, so they stay zero.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct LargeBitboardN<const WORDS: usize> {
+	shape: BitboardShape,
+	bits: [u64; WORDS],
+}
+impl<const WORDS: usize> RuntimeBitboard for LargeBitboardN<WORDS> {
+	const MAX_SQUARES: usize = WORDS * 64;
+
+	#[inline(always)]
+	fn shape(&self) -> BitboardShape {
+		self.shape
+	}
+
+	#[inline(always)]
+	fn new(w: u8, h: u8, col_major: bool, bits: [u64; WORDS]) -> Self {
+		assert!(w as usize * h as usize <= Self::MAX_SQUARES);
+		Self { shape: BitboardShape::new(w, h, col_major), bits }
+	}
+	#[inline(always)]
+	fn west_border_with_same_shape(&self) -> Self {
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major)
+	}
+	#[inline(always)]
+	fn east_border_with_same_shape(&self) -> Self {
+		Self::east_border(self.shape.w, self.shape.h, self.shape.col_major)
+	}
+	#[inline(always)]
+	fn north_border_with_same_shape(&self) -> Self {
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major)
+	}
+	#[inline(always)]
+	fn south_border_with_same_shape(&self) -> Self {
+		Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
+	}
+	#[inline(always)]
+	fn full_with_same_shape(&self) -> Self {
+		Self::full(self.shape.w, self.shape.h, self.shape.col_major)
+	}
+	#[inline(always)]
+	fn empty_with_same_shape(&self) -> Self {
+		Self::empty(self.shape.w, self.shape.h, self.shape.col_major)
+	}
+	#[inline(always)]
+	fn index_from_coords_with_same_shape(&self, x: u8, y: u8) -> usize {
+		Self::index_from_coords(self.shape.w, self.shape.h, self.shape.col_major, x, y)
+	}
+
+	#[inline(always)]
+	fn coords_from_index_with_same_shape(&self, i: usize) -> (u8, u8) {
+		Self::coords_from_index(self.shape.w, self.shape.h, self.shape.col_major, i)
+	}
+
+	#[inline(always)]
+	fn is_in_bounds_with_same_shape(&self, x: u8, y: u8) -> bool {
+		Self::is_in_bounds(self.shape.w, self.shape.h, x, y)
+	}
+
+	#[inline(always)]
+	fn is_index_in_bounds_with_same_shape(&self, i: usize) -> bool {
+		Self::is_index_in_bounds(self.shape.w, self.shape.h, i)
+	}
+	#[inline(always)]
+	fn borders(w: u8, h: u8, col_major: bool) -> Self {
+		Self::west_border(w, h, col_major) | Self::east_border(w, h, col_major) |
+		Self::north_border(w, h, col_major) | Self::south_border(w, h, col_major)
+	}
+	#[inline(always)]
+	fn west_border(w: u8, h: u8, col_major: bool) -> Self {
+		Self::col_mask(w, h, col_major, 0)
+	}
+	#[inline(always)]
+	fn east_border(w: u8, h: u8, col_major: bool) -> Self {
+		Self::col_mask(w, h, col_major, w - 1)
+	}
+	#[inline(always)]
+	fn north_border(w: u8, h: u8, col_major: bool) -> Self {
+		Self::row_mask(w, h, col_major, h - 1)
+	}
+	#[inline(always)]
+	fn south_border(w: u8, h: u8, col_major: bool) -> Self {
+		Self::row_mask(w, h, col_major, 0)
+	}
+
+	#[inline(always)]
+	fn empty(w: u8, h: u8, col_major: bool) -> Self {
+		Self::new(w, h, col_major, [0; WORDS])
+	}
+	fn full(w: u8, h: u8, col_major: bool) -> Self {
+		let nb_bits = w as usize * h as usize;
+		let mut bits = [0; WORDS];
+		for (i, word) in bits.iter_mut().enumerate() {
+			let rem = nb_bits.saturating_sub(i * 64);
+			*word = if rem >= 64 { u64::MAX } else { (1u64 << rem) - 1 };
+		}
+		Self::new(w, h, col_major, bits)
+	}
+	#[inline(always)]
+	fn row_mask_with_same_shape(&self, y: u8) -> Self {
+		Self::row_mask(self.shape.w, self.shape.h, self.shape.col_major, y)
+	}
+	#[inline(always)]
+	fn col_mask_with_same_shape(&self, x: u8) -> Self {
+		Self::col_mask(self.shape.w, self.shape.h, self.shape.col_major, x)
+	}
+	fn row_mask(w: u8, h: u8, col_major: bool, y: u8) -> Self {
+		let mut row = Self::empty(w, h, col_major);
+		for x in 0..w {
+			row.set_at_index(Self::index_from_coords(w, h, col_major, x, y));
+		}
+		row
+	}
+	fn col_mask(w: u8, h: u8, col_major: bool, x: u8) -> Self {
+		let mut col = Self::empty(w, h, col_major);
+		for y in 0..h {
+			col.set_at_index(Self::index_from_coords(w, h, col_major, x, y));
+		}
+		col
+	}
+	fn index_from_coords(w: u8, h: u8, col_major: bool, x: u8, y: u8) -> usize {
+		if col_major {
+			x as usize * h as usize + y as usize
+		} else {
+			y as usize * w as usize + x as usize
+		}
+	}
+
+	fn coords_from_index(w: u8, h: u8, col_major: bool, i: usize) -> (u8, u8) {
+		if col_major {
+			((i / h as usize) as u8, (i % h as usize) as u8)
+		} else {
+			((i % w as usize) as u8, (i / w as usize) as u8)
+		}
+	}
+
+	fn is_in_bounds(w: u8, h: u8, x: u8, y: u8) -> bool {
+		x < w && y < h
+	}
+
+	fn is_index_in_bounds(w: u8, h: u8, i: usize) -> bool {
+		i < w as usize * h as usize
+	}
+}
+
+impl<const WORDS: usize> Bitboard for LargeBitboardN<WORDS> {
+	type Storage = [u64; WORDS];
+	#[inline(always)]
+	fn width(&self) -> u8 { self.shape.w }
+	#[inline(always)]
+	fn height(&self) -> u8 { self.shape.h }
+	#[inline(always)]
+	fn col_major(&self) -> bool { self.shape.col_major }
+
+	#[inline]
+	fn is_empty(&self) -> bool {
+		crate::simd::is_zero(&self.bits)
+	}
+	#[inline]
+	fn count(&self) -> u32 {
+		crate::simd::count_ones(&self.bits)
+	}
+	#[inline]
+	fn intersects(&self, other: &Self) -> bool {
+		self.bits.iter().zip(other.bits.iter()).any(|(a, b)| a & b != 0)
+	}
+	#[inline]
+	fn storage(&self) -> &Self::Storage {
+		&self.bits
+	}
+	#[inline]
+	fn storage_mut(&mut self) -> &mut Self::Storage {
+		&mut self.bits
+	}
+
+	#[inline]
+	fn get_at_index(&self, idx: usize) -> bool {
+		(self.bits[idx / 64] >> (idx % 64)) & 1 == 1
+	}
+
+	#[inline]
+	fn assign_at_index(&mut self, idx: usize, val: bool) {
+		if val {
+			self.set_at_index(idx);
+		} else {
+			self.reset_at_index(idx);
+		}
+	}
+	#[inline(always)]
+	fn set_at_index(&mut self, idx: usize) {
+		self.bits[idx / 64] |= 1 << (idx % 64);
+	}
+	#[inline(always)]
+	fn reset_at_index(&mut self, idx: usize) {
+		self.bits[idx / 64] &= !(1 << (idx % 64));
+	}
+	#[inline(always)]
+	fn toggle_at_index(&mut self, idx: usize) {
+		self.bits[idx / 64] ^= 1 << (idx % 64);
+	}
+	#[inline(always)]
+	fn get(&self, x: u8, y: u8) -> bool {
+		self.get_at_index(self.index_from_coords_with_same_shape(x, y))
+	}
+
+	#[inline(always)]
+	fn assign(&mut self, x: u8, y: u8, val: bool) {
+		self.assign_at_index(self.index_from_coords_with_same_shape(x, y), val)
+	}
+	#[inline(always)]
+	fn set(&mut self, x: u8, y: u8) {
+		self.set_at_index(self.index_from_coords_with_same_shape(x, y))
+	}
+	#[inline(always)]
+	fn reset(&mut self, x: u8, y: u8) {
+		self.reset_at_index(self.index_from_coords_with_same_shape(x, y))
+	}
+	#[inline(always)]
+	fn flipped(&self) -> Self {
+		!*self
+	}
+	#[inline]
+	fn lsb(&self) -> u32 {
+		for (i, &segment) in self.bits.iter().enumerate() {
+			if segment != 0 {
+				return (i as u32 * 64) + segment.trailing_zeros();
+			}
+		}
+		WORDS as u32 * 64
+	}
+	#[inline]
+	fn msb(&self) -> u32 {
+		for (i, &segment) in self.bits.iter().enumerate().rev() {
+			if segment != 0 {
+				return (i as u32 * 64) + segment.ilog2();
+			}
+		}
+		panic!("msb called on empty bitboard");
+	}
+	#[inline]
+	fn pop_lsb(&mut self) -> u32 {
+		for (word_index, word) in self.bits.iter_mut().enumerate() {
+			if *word != 0 {
+				let lsb = word.trailing_zeros();
+				*word &= *word - 1;
+				return (word_index as u32) * 64 + lsb;
+			}
+		}
+		u32::MAX
+	}
+
+	#[inline]
+	fn pext(&self, mask: &Self) -> Self::Storage {
+		let mut out = [0; WORDS];
+		crate::bmi2::pext_words(&self.bits, &mask.bits, &mut out);
+		out
+	}
+
+	#[inline]
+	fn pdep(&self, compressed: Self::Storage) -> Self {
+		let mut out = [0; WORDS];
+		crate::bmi2::pdep_words(&compressed, &self.bits, &mut out);
+		Self { shape: self.shape, bits: out }
+	}
+
+	#[inline(always)]
+	fn extract_row(&self, y: u8) -> Self::Storage {
+		self.pext(&self.row_mask_with_same_shape(y))
+	}
+	#[inline(always)]
+	fn extract_col(&self, x: u8) -> Self::Storage {
+		self.pext(&self.col_mask_with_same_shape(x))
+	}
+	#[inline(always)]
+	fn insert_row(&mut self, y: u8, row_bits: Self::Storage) {
+		let mask = self.row_mask_with_same_shape(y);
+		*self = (*self & !mask) | mask.pdep(row_bits);
+	}
+	#[inline(always)]
+	fn insert_col(&mut self, x: u8, col_bits: Self::Storage) {
+		let mask = self.col_mask_with_same_shape(x);
+		*self = (*self & !mask) | mask.pdep(col_bits);
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitAnd for LargeBitboardN<WORDS> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn bitand(mut self, rhs: Self) -> Self {
+		self &= &rhs;
+		self
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitOr for LargeBitboardN<WORDS> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn bitor(mut self, rhs: Self) -> Self {
+		self |= &rhs;
+		self
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitXor for LargeBitboardN<WORDS> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn bitxor(mut self, rhs: Self) -> Self {
+		self ^= &rhs;
+		self
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitAndAssign<&Self> for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn bitand_assign(&mut self, rhs: &Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
+		crate::simd::and_assign(&mut self.bits, &rhs.bits);
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitOrAssign<&Self> for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn bitor_assign(&mut self, rhs: &Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
+		crate::simd::or_assign(&mut self.bits, &rhs.bits);
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitXorAssign<&Self> for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn bitxor_assign(&mut self, rhs: &Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
+		crate::simd::xor_assign(&mut self.bits, &rhs.bits);
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitAndAssign for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn bitand_assign(&mut self, rhs: Self) {
+		*self &= &rhs;
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitOrAssign for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn bitor_assign(&mut self, rhs: Self) {
+		*self |= &rhs;
+	}
+}
+
+impl<const WORDS: usize> core::ops::BitXorAssign for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn bitxor_assign(&mut self, rhs: Self) {
+		*self ^= &rhs;
+	}
+}
+
+impl<const WORDS: usize> LargeBitboardN<WORDS> {
+	/// Clears the bits past the last square, which `!` and `<<` would set.
+	#[inline(always)]
+	fn clear_past_board(&mut self) {
+		let nb_squares = self.shape.w as usize * self.shape.h as usize;
+		let (full_words, rest) = (nb_squares / 64, nb_squares % 64);
+		if rest != 0 {
+			self.bits[full_words] &= (1 << rest) - 1;
+		}
+		self.bits[nb_squares.div_ceil(64)..].fill(0);
+	}
+	/// Complements the squares of the board.
+	#[inline(always)]
+	fn not_assign(&mut self) {
+		crate::simd::not_assign(&mut self.bits);
+		self.clear_past_board();
+	}
+}
+
+impl<const WORDS: usize> core::ops::Not for LargeBitboardN<WORDS> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn not(mut self) -> Self {
+		self.not_assign();
+		self
+	}
+}
+
+impl<const WORDS: usize> core::ops::Shl<usize> for LargeBitboardN<WORDS> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn shl(mut self, rhs: usize) -> Self {
+		self <<= rhs;
+		self
+	}
+}
+
+impl<const WORDS: usize> core::ops::Shr<usize> for LargeBitboardN<WORDS> {
+	type Output = Self;
+
+	#[inline(always)]
+	fn shr(mut self, rhs: usize) -> Self {
+		self >>= rhs;
+		self
+	}
+}
+
+impl<const WORDS: usize> core::ops::ShlAssign<usize> for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn shl_assign(&mut self, rhs: usize) {
+		crate::simd::shl_assign(&mut self.bits, rhs);
+		self.clear_past_board();
+	}
+}
+
+impl<const WORDS: usize> core::ops::ShrAssign<usize> for LargeBitboardN<WORDS> {
+	#[inline(always)]
+	fn shr_assign(&mut self, rhs: usize) {
+		crate::simd::shr_assign(&mut self.bits, rhs);
+	}
+}
+
+super::impl_borrowed_ops!([const WORDS: usize] LargeBitboardN<WORDS>);
+
+impl<const WORDS: usize> core::fmt::Display for LargeBitboardN<WORDS> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		fmt_bitboard_display(self, f)
+	}
+}
+
+impl<const WORDS: usize> core::fmt::Debug for LargeBitboardN<WORDS> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		fmt_bitboard_debug(self, WORDS * 64, f)
+	}
+}
@@ -1,12 +1,13 @@
-use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::RuntimeBitboard};
+use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::{BitboardShape, RuntimeBitboard, debug_assert_same_shape}};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 #[derive(Clone, PartialEq, Eq, Copy)]
 pub struct MediumBitboard {
-	w: u8,
-	h: u8,
-	col_major: bool,
+	shape: BitboardShape,
 	bits: u128,
 }
+#[cfg(feature = "alloc")]
 impl MediumBitboard {
 	pub fn all_subsets(&self) -> Vec<Self>
 	{
@@ -17,7 +18,7 @@ impl MediumBitboard {
 
 		loop {
 			subsets.push(subset);
-			subset = Self::new(self.w, self.h, self.col_major, subset.bits.wrapping_sub(self.bits) & self.bits);
+			subset = Self::new(self.shape.w, self.shape.h, self.shape.col_major, subset.bits.wrapping_sub(self.bits) & self.bits);
 			if subset == zero {
 				break;
 			}
@@ -30,45 +31,51 @@ impl MediumBitboard {
 	#[inline(always)]
 	pub fn new(w: u8, h: u8, col_major: bool, bits: u128) -> Self {
 		assert!(w*h<=128);
-		Self { w, h, col_major, bits }
+		Self { shape: BitboardShape::new(w, h, col_major), bits }
 	}
 	#[inline(always)]
 	pub fn west_border_with_same_shape(&self) -> Self {
-		Self::west_border(self.w, self.h, self.col_major)
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn east_border_with_same_shape(&self) -> Self {
-		Self::east_border(self.w, self.h, self.col_major)
+		Self::east_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn north_border_with_same_shape(&self) -> Self {
-		Self::north_border(self.w, self.h, self.col_major)
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn south_border_with_same_shape(&self) -> Self {
-		Self::south_border(self.w, self.h, self.col_major)
+		Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn full_with_same_shape(&self) -> Self {
-		Self::full(self.w, self.h, self.col_major)
+		Self::full(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn empty_with_same_shape(&self) -> Self {
-		Self::empty(self.w, self.h, self.col_major)
+		Self::empty(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 }
 
 impl Default for MediumBitboard {
 	#[inline(always)]
 	fn default() -> Self {
-		Self { w: 8, h: 8, col_major: false, bits: 0 }
+		Self { shape: BitboardShape::new(8, 8, false), bits: 0 }
 	}
 }
 impl RuntimeBitboard for MediumBitboard {
+	const MAX_SQUARES: usize = 128;
+
+	#[inline(always)]
+	fn shape(&self) -> BitboardShape {
+		self.shape
+	}
 	#[inline(always)]
 	fn new(w: u8, h: u8, col_major: bool, bits: u128) -> Self {
 		assert!(w*h<=128);
-		Self { w, h, col_major, bits }
+		Self { shape: BitboardShape::new(w, h, col_major), bits }
 	}
 	#[inline(always)]
 	fn full(w: u8, h: u8, col_major: bool) -> Self {
@@ -94,6 +101,9 @@ impl RuntimeBitboard for MediumBitboard {
 	}
 	#[inline(always)]
 	fn west_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::col_mask(w, h, col_major, 0);
+		}
 		let mut bits = 0;
 		for y in 0..h as usize {
 			let idx = y * w as usize;
@@ -104,6 +114,9 @@ impl RuntimeBitboard for MediumBitboard {
 
 	#[inline(always)]
 	fn east_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::col_mask(w, h, col_major, w - 1);
+		}
 		let mut bits = 0u128;
 		let mut v = 1u128 << (w - 1);
 
@@ -116,6 +129,9 @@ impl RuntimeBitboard for MediumBitboard {
 	}
 	#[inline(always)]
 	fn south_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::row_mask(w, h, col_major, 0);
+		}
 		let bits = if w == 128 {
 			u128::MAX
 		} else {
@@ -127,6 +143,9 @@ impl RuntimeBitboard for MediumBitboard {
 
 	#[inline(always)]
 	fn north_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::row_mask(w, h, col_major, h - 1);
+		}
 		let mut bits = if w == 128 {
 			u128::MAX
 		} else {
@@ -140,15 +159,19 @@ impl RuntimeBitboard for MediumBitboard {
 	#[inline(always)]
 	fn row_mask(w: u8, h: u8, col_major: bool, y: u8) -> Self {
 		if col_major {
-			Self::west_border(w,h,col_major) << y
+			let mut bits = 0u128;
+			for x in 0..w as usize {
+				bits |= 1 << (x * h as usize + y as usize);
+			}
+			Self { shape: BitboardShape::new(w, h, col_major), bits }
 		} else {
-			Self{ w, h, col_major, bits: ((1u128 << w) - 1) << (y * w) }
+			Self { shape: BitboardShape::new(w, h, col_major), bits: ((1u128 << w) - 1) << (y * w) }
 		}
 	}
 	#[inline(always)]
 	fn col_mask(w: u8, h: u8, col_major: bool, x: u8) -> Self {
 		if col_major {
-			Self{ w, h, col_major, bits: ((1u128 << h) - 1) << (x * h) }
+			Self { shape: BitboardShape::new(w, h, col_major), bits: ((1u128 << h) - 1) << (x * h) }
 		} else {
 			Self::west_border(w,h,col_major) << x
 		}
@@ -178,71 +201,71 @@ impl RuntimeBitboard for MediumBitboard {
 	}
 	#[inline(always)]
 	fn full_with_same_shape(&self) -> Self {
-		Self::full(self.w, self.h, self.col_major)
+		Self::full(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	#[inline(always)]
 	fn empty_with_same_shape(&self) -> Self {
-		Self::empty(self.w, self.h, self.col_major)
+		Self::empty(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	#[inline(always)]
 	fn west_border_with_same_shape(&self) -> Self {
-		Self::west_border(self.w, self.h, self.col_major)
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	#[inline(always)]
 	fn east_border_with_same_shape(&self) -> Self {
-		Self::east_border(self.w, self.h, self.col_major)
+		Self::east_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	#[inline(always)]
 	fn north_border_with_same_shape(&self) -> Self {
-		Self::north_border(self.w, self.h, self.col_major)
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	#[inline(always)]
 	fn south_border_with_same_shape(&self) -> Self {
-		Self::south_border(self.w, self.h, self.col_major)
+		Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	#[inline(always)]
 	fn row_mask_with_same_shape(&self, y: u8) -> Self {
-		Self::row_mask(self.w, self.h, self.col_major, y)
+		Self::row_mask(self.shape.w, self.shape.h, self.shape.col_major, y)
 	}
 	#[inline(always)]
 	fn col_mask_with_same_shape(&self, x: u8) -> Self {
-		Self::col_mask(self.w, self.h, self.col_major, x)
+		Self::col_mask(self.shape.w, self.shape.h, self.shape.col_major, x)
 	}
 
 	#[inline(always)]
 	fn index_from_coords_with_same_shape(&self, x: u8, y: u8) -> usize {
-		Self::index_from_coords(self.w, self.h, self.col_major, x, y)
+		Self::index_from_coords(self.shape.w, self.shape.h, self.shape.col_major, x, y)
 	}
 	
 	#[inline(always)]
 	fn coords_from_index_with_same_shape(&self, i: usize) -> (u8, u8) {
-		Self::coords_from_index(self.w, self.h, self.col_major, i)
+		Self::coords_from_index(self.shape.w, self.shape.h, self.shape.col_major, i)
 	}
 	
 	#[inline(always)]
 	fn is_in_bounds_with_same_shape(&self, x: u8, y: u8) -> bool {
-		Self::is_in_bounds(self.w, self.h, x, y)
+		Self::is_in_bounds(self.shape.w, self.shape.h, x, y)
 	}
 	
 	#[inline(always)]
 	fn is_index_in_bounds_with_same_shape(&self, i: usize) -> bool {
-		Self::is_index_in_bounds(self.w, self.h, i)
+		Self::is_index_in_bounds(self.shape.w, self.shape.h, i)
 	}
 }
 impl Bitboard for MediumBitboard {
 	type Storage=u128;
 	#[inline(always)]
-	fn width(&self) -> u8 { self.w }
+	fn width(&self) -> u8 { self.shape.w }
 	#[inline(always)]
-	fn height(&self) -> u8 { self.h }
+	fn height(&self) -> u8 { self.shape.h }
 	#[inline(always)]
-	fn col_major(&self) -> bool { self.col_major }
+	fn col_major(&self) -> bool { self.shape.col_major }
 
 
 	#[inline(always)]
@@ -314,7 +337,7 @@ impl Bitboard for MediumBitboard {
 	
 	#[inline(always)]
 	fn flipped(&self) -> Self {
-		Self{ w: self.w, h: self.h, col_major: self.col_major, bits: !self.bits }
+		Self { shape: self.shape, bits: !self.bits }
 	}
 	#[inline]
 	fn lsb(&self) -> u32 {
@@ -326,97 +349,18 @@ impl Bitboard for MediumBitboard {
 	}
 	#[inline]
 	fn pop_lsb(&mut self) -> u32 {
-		let low = self.bits as u64;
-
-		if low != 0 {
-			let idx = low.trailing_zeros();
-			self.bits &= self.bits - 1;
-			return idx;
-		}
-
-		let high = (self.bits >> 64) as u64;
-		let idx = high.trailing_zeros();
+		let idx = self.bits.trailing_zeros();
 		self.bits &= self.bits - 1;
-		idx + 64
+		idx
 	}
 
 	#[inline]
 	fn pext(&self, mask: &Self) -> Self::Storage {
-		#[cfg(target_feature = "bmi2")]
-		unsafe {
-			let lo  = self.bits as u64;
-			let hi  = (self.bits >> 64) as u64;
-
-			let mlo = mask.bits as u64;
-			let mhi = (mask.bits >> 64) as u64;
-
-			let lo_res = std::arch::x86_64::_pext_u64(lo, mlo);
-			let hi_res = std::arch::x86_64::_pext_u64(hi, mhi);
-
-			let shift = mlo.count_ones();
-
-			((hi_res as u128) << shift) | (lo_res as u128)
-		}
-		#[cfg(not(target_feature = "bmi2"))]
-		{
-			let mut res: u128 = 0;
-			let mut bit: u128 = 1;
-			let mut m = mask.bits;
-			let mut v = self.bits;
-
-			while m != 0 {
-				if m & 1 != 0 {
-					if v & 1 != 0 {
-						res |= bit;
-					}
-					bit <<= 1;
-				}
-				m >>= 1;
-				v >>= 1;
-			}
-			res
-		}
+		crate::bmi2::pext_u128(self.bits, mask.bits)
 	}
 	#[inline]
 	fn pdep(&self, compressed: Self::Storage) -> Self {
-		#[cfg(target_feature = "bmi2")]
-		unsafe {
-			let src = compressed;
-
-			let lo_mask = self.bits as u64;
-			let hi_mask = (self.bits >> 64) as u64;
-
-			let lo_count = lo_mask.count_ones();
-
-			let src_lo = src as u64;
-			let src_hi = (src >> lo_count) as u64;
-
-			let lo_res = std::arch::x86_64::_pdep_u64(src_lo, lo_mask);
-			let hi_res = std::arch::x86_64::_pdep_u64(src_hi, hi_mask);
-
-			Self::new(self.w, self.h, self.col_major, (lo_res as u128) | ((hi_res as u128) << 64))
-		}
-		#[cfg(not(target_feature = "bmi2"))]
-		{
-			
-			// fallback
-			let mut res: u128 = 0;
-			let mut bit: u128 = 1;
-			let mut m = compressed;
-			let mut v = self.bits;
-
-			while m != 0 {
-				if m & 1 != 0 {
-					if v & 1 != 0 {
-						res |= bit;
-					}
-					v >>= 1;
-				}
-				bit <<= 1;
-				m >>= 1;
-			}
-			Self::new(self.w, self.h, self.col_major, res)
-		}
+		Self::new(self.shape.w, self.shape.h, self.shape.col_major, crate::bmi2::pdep_u128(compressed, self.bits))
 	}
 
 	#[inline(always)]
@@ -450,82 +394,91 @@ impl Bitboard for MediumBitboard {
 	}
 	
 }
-impl std::ops::BitAnd for MediumBitboard {
+impl core::ops::BitAnd for MediumBitboard {
 	type Output = Self;
 	#[inline(always)]
 	fn bitand(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits &= rhs.bits;
 		self
 	}
 }
 
-impl std::ops::BitOr for MediumBitboard {
+impl core::ops::BitOr for MediumBitboard {
 	type Output = Self;
 	#[inline(always)]
 	fn bitor(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits |= rhs.bits;
 		self
 	}
 }
 
-impl std::ops::BitXor for MediumBitboard {
+impl core::ops::BitXor for MediumBitboard {
 	type Output = Self;
 	#[inline(always)]
 	fn bitxor(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits ^= rhs.bits;
 		self
 	}
 }
-impl std::ops::BitAnd<&Self> for MediumBitboard {
+impl core::ops::BitAnd<&Self> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitand(mut self, rhs: &Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits &= rhs.bits;
 		self
 	}
 }
-impl std::ops::BitOr<&Self> for MediumBitboard {
+impl core::ops::BitOr<&Self> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitor(mut self, rhs: &Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits |= rhs.bits;
 		self
 	}
 }
-impl std::ops::BitXor<&Self> for MediumBitboard {
+impl core::ops::BitXor<&Self> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitxor(mut self, rhs: &Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits ^= rhs.bits;
 		self
 	}
 }
 
-impl std::ops::BitAndAssign for MediumBitboard {
+impl core::ops::BitAndAssign for MediumBitboard {
 	#[inline(always)]
 	fn bitand_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits &= rhs.bits;
 	}
 }
 
-impl std::ops::BitOrAssign for MediumBitboard {
+impl core::ops::BitOrAssign for MediumBitboard {
 	#[inline(always)]
 	fn bitor_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits |= rhs.bits;
 	}
 }
 
-impl std::ops::BitXorAssign for MediumBitboard {
+impl core::ops::BitXorAssign for MediumBitboard {
 	#[inline(always)]
 	fn bitxor_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits ^= rhs.bits;
 	}
 }
 
-impl std::ops::Not for MediumBitboard {
+impl core::ops::Not for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -535,7 +488,7 @@ impl std::ops::Not for MediumBitboard {
 	}
 }
 
-impl std::ops::Shl<usize> for MediumBitboard {
+impl core::ops::Shl<usize> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -545,7 +498,7 @@ impl std::ops::Shl<usize> for MediumBitboard {
 	}
 }
 
-impl std::ops::Shr<usize> for MediumBitboard {
+impl core::ops::Shr<usize> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -554,19 +507,19 @@ impl std::ops::Shr<usize> for MediumBitboard {
 		self
 	}
 }
-impl std::ops::ShlAssign<usize> for MediumBitboard {
+impl core::ops::ShlAssign<usize> for MediumBitboard {
 	#[inline(always)]
 	fn shl_assign(&mut self, rhs: usize) {
 		self.bits <<= rhs;
 	}
 }
-impl std::ops::ShrAssign<usize> for MediumBitboard {
+impl core::ops::ShrAssign<usize> for MediumBitboard {
 	#[inline(always)]
 	fn shr_assign(&mut self, rhs: usize) {
 		self.bits >>= rhs;
 	}
 }
-impl std::ops::Shl<u8> for MediumBitboard {
+impl core::ops::Shl<u8> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -576,7 +529,7 @@ impl std::ops::Shl<u8> for MediumBitboard {
 	}
 }
 
-impl std::ops::Shr<u8> for MediumBitboard {
+impl core::ops::Shr<u8> for MediumBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -586,15 +539,15 @@ impl std::ops::Shr<u8> for MediumBitboard {
 	}
 }
 
-impl std::fmt::Display for MediumBitboard {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MediumBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		fmt_bitboard_display(self, f)
 	}
 }
 
-impl std::fmt::Debug for MediumBitboard {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		fmt_bitboard_debug(self, std::mem::size_of_val(self.storage()) * 8, f)
+impl core::fmt::Debug for MediumBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		fmt_bitboard_debug(self, core::mem::size_of_val(self.storage()) * 8, f)
 	}
 }
 
@@ -1,11 +1,18 @@
 use crate::Bitboard;
+#[cfg(feature = "alloc")]
+use crate::parse::{Grid, ParseError};
 
 
 pub mod small_bitboard;
 pub mod medium_bitboard;
+#[cfg(feature = "alloc")]
 pub mod large_bitboard;
+#[cfg(feature = "alloc")]
+pub mod dyn_bitboard;
+pub mod large_bitboard_n;
 
-#[derive(Clone, Debug, )]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitboardShape {
 	pub w: u8,
 	pub h: u8,
@@ -15,10 +22,58 @@ impl BitboardShape {
 	pub fn new(w: u8, h: u8, col_major:bool) -> Self {
 		Self { w, h, col_major }
 	}
+	/// Total number of squares of the shape.
+	#[inline(always)]
+	pub fn nb_squares(&self) -> usize {
+		self.w as usize * self.h as usize
+	}
+}
+impl core::fmt::Display for BitboardShape {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "{}x{}{}", self.w, self.h, if self.col_major { " (column-major)" } else { "" })
+	}
+}
+
+/// Error returned when combining runtime bitboards that do not have the same shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShapeError {
+	/// Shape of the left operand.
+	pub expected: BitboardShape,
+	/// Shape of the right operand.
+	pub found: BitboardShape,
+}
+impl core::fmt::Display for ShapeError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "bitboard shape mismatch: expected {}, found {}", self.expected, self.found)
+	}
+}
+impl core::error::Error for ShapeError {}
+
+/// Panics in debug builds if the operands of a binary operation do not have the same shape.
+#[inline(always)]
+#[track_caller]
+pub(crate) fn debug_assert_same_shape(expected: &BitboardShape, found: &BitboardShape) {
+	debug_assert!(expected == found, "{}", ShapeError { expected: *expected, found: *found });
 }
+
 pub trait RuntimeBitboard : Bitboard {
+	/// Maximum number of squares the storage can hold.
+	const MAX_SQUARES: usize = usize::MAX;
+
 	fn new(w: u8, h: u8, col_major: bool, bits: Self::Storage) -> Self;
 
+	/// Parses a `#`/`.` grid (see [`crate::parse`]), inferring the width and height.
+	#[cfg(feature = "alloc")]
+	fn parse(s: &str, col_major: bool) -> Result<Self, ParseError> {
+		let grid = Grid::parse(s)?;
+		grid.expect_at_most(Self::MAX_SQUARES)?;
+		let mut b = Self::empty(grid.width(), grid.height(), col_major);
+		for (x, y) in grid.iter_set() {
+			b.set(x, y);
+		}
+		Ok(b)
+	}
+
 	fn full(w: u8, h: u8, col_major: bool) -> Self;
 	fn empty(w: u8, h: u8, col_major: bool) -> Self;
 
@@ -60,4 +115,562 @@ pub trait RuntimeBitboard : Bitboard {
 	/// Check index is inside the bitboard
 	fn is_index_in_bounds_with_same_shape(&self, i: usize) -> bool;
 
-}
\ No newline at end of file
+	/// Shape (width, height and bit order) of the bitboard.
+	fn shape(&self) -> BitboardShape;
+	/// Returns a [`ShapeError`] if `other` does not have the same shape as `self`.
+	#[inline(always)]
+	fn check_shape(&self, other: &Self) -> Result<(), ShapeError> {
+		let (expected, found) = (self.shape(), other.shape());
+		if expected == found {
+			Ok(())
+		} else {
+			Err(ShapeError { expected, found })
+		}
+	}
+	/// Bitwise AND, or a [`ShapeError`] if `other` does not have the same shape.
+	#[inline]
+	fn try_and(self, other: Self) -> Result<Self, ShapeError> {
+		self.check_shape(&other)?;
+		Ok(self & other)
+	}
+	/// Bitwise OR, or a [`ShapeError`] if `other` does not have the same shape.
+	#[inline]
+	fn try_or(self, other: Self) -> Result<Self, ShapeError> {
+		self.check_shape(&other)?;
+		Ok(self | other)
+	}
+	/// Bitwise XOR, or a [`ShapeError`] if `other` does not have the same shape.
+	#[inline]
+	fn try_xor(self, other: Self) -> Result<Self, ShapeError> {
+		self.check_shape(&other)?;
+		Ok(self ^ other)
+	}
+	/// `self & !other`, or a [`ShapeError`] if `other` does not have the same shape.
+	#[inline]
+	fn try_andnot(self, other: Self) -> Result<Self, ShapeError> {
+		self.check_shape(&other)?;
+		Ok(self & !other)
+	}
+
+	/// Offset to add/subtract to an index to move to the next column
+	#[inline(always)]
+	fn h_offset(&self) -> usize {
+		if self.col_major() { self.height() as usize } else { 1 }
+	}
+	/// Offset to add/subtract to an index to move to the next row
+	#[inline(always)]
+	fn v_offset(&self) -> usize {
+		if self.col_major() { 1 } else { self.width() as usize }
+	}
+
+	/// A Mask to prevent wrapping during north shifts.
+	#[inline(always)]
+	fn no_wrap_n_mask(&self) -> Self {
+		self.full_with_same_shape() & !self.north_border_with_same_shape()
+	}
+	/// A Mask to prevent wrapping during south shifts.
+	#[inline(always)]
+	fn no_wrap_s_mask(&self) -> Self {
+		self.full_with_same_shape() & !self.south_border_with_same_shape()
+	}
+	/// A Mask to prevent wrapping during east shifts.
+	#[inline(always)]
+	fn no_wrap_e_mask(&self) -> Self {
+		self.full_with_same_shape() & !self.east_border_with_same_shape()
+	}
+	/// A Mask to prevent wrapping during west shifts.
+	#[inline(always)]
+	fn no_wrap_w_mask(&self) -> Self {
+		self.full_with_same_shape() & !self.west_border_with_same_shape()
+	}
+	/// A Mask to prevent wrapping during north-east shifts.
+	#[inline(always)]
+	fn no_wrap_ne_mask(&self) -> Self {
+		self.no_wrap_n_mask() & self.no_wrap_e_mask()
+	}
+	/// A Mask to prevent wrapping during north-west shifts.
+	#[inline(always)]
+	fn no_wrap_nw_mask(&self) -> Self {
+		self.no_wrap_n_mask() & self.no_wrap_w_mask()
+	}
+	/// A Mask to prevent wrapping during south-east shifts.
+	#[inline(always)]
+	fn no_wrap_se_mask(&self) -> Self {
+		self.no_wrap_s_mask() & self.no_wrap_e_mask()
+	}
+	/// A Mask to prevent wrapping during south-west shifts.
+	#[inline(always)]
+	fn no_wrap_sw_mask(&self) -> Self {
+		self.no_wrap_s_mask() & self.no_wrap_w_mask()
+	}
+
+	/// Shift bitboard one square north (up).
+	#[inline(always)]
+	fn shift_n(&mut self) {
+		let offset = self.v_offset() as isize;
+		shift_masked(self, self.no_wrap_n_mask(), offset);
+	}
+	/// Shift bitboard one square south (down).
+	#[inline(always)]
+	fn shift_s(&mut self) {
+		let offset = -(self.v_offset() as isize);
+		shift_masked(self, self.no_wrap_s_mask(), offset);
+	}
+	/// Shift bitboard one square east (right).
+	#[inline(always)]
+	fn shift_e(&mut self) {
+		let offset = self.h_offset() as isize;
+		shift_masked(self, self.no_wrap_e_mask(), offset);
+	}
+	/// Shift bitboard one square west (left).
+	#[inline(always)]
+	fn shift_w(&mut self) {
+		let offset = -(self.h_offset() as isize);
+		shift_masked(self, self.no_wrap_w_mask(), offset);
+	}
+	/// Shift bitboard one square north-east.
+	#[inline(always)]
+	fn shift_ne(&mut self) {
+		let offset = self.v_offset() as isize + self.h_offset() as isize;
+		shift_masked(self, self.no_wrap_ne_mask(), offset);
+	}
+	/// Shift bitboard one square north-west.
+	#[inline(always)]
+	fn shift_nw(&mut self) {
+		let offset = self.v_offset() as isize - self.h_offset() as isize;
+		shift_masked(self, self.no_wrap_nw_mask(), offset);
+	}
+	/// Shift bitboard one square south-east.
+	#[inline(always)]
+	fn shift_se(&mut self) {
+		let offset = self.h_offset() as isize - self.v_offset() as isize;
+		shift_masked(self, self.no_wrap_se_mask(), offset);
+	}
+	/// Shift bitboard one square south-west.
+	#[inline(always)]
+	fn shift_sw(&mut self) {
+		let offset = -(self.v_offset() as isize + self.h_offset() as isize);
+		shift_masked(self, self.no_wrap_sw_mask(), offset);
+	}
+
+	/// Shifted bitboard one square north (up).
+	#[inline(always)]
+	fn shifted_n(&self) -> Self { let mut res = self.clone(); res.shift_n(); res }
+	/// Shifted bitboard one square south (down).
+	#[inline(always)]
+	fn shifted_s(&self) -> Self { let mut res = self.clone(); res.shift_s(); res }
+	/// Shifted bitboard one square east (right).
+	#[inline(always)]
+	fn shifted_e(&self) -> Self { let mut res = self.clone(); res.shift_e(); res }
+	/// Shifted bitboard one square west (left).
+	#[inline(always)]
+	fn shifted_w(&self) -> Self { let mut res = self.clone(); res.shift_w(); res }
+	/// Shifted bitboard one square north-east.
+	#[inline(always)]
+	fn shifted_ne(&self) -> Self { let mut res = self.clone(); res.shift_ne(); res }
+	/// Shifted bitboard one square north-west.
+	#[inline(always)]
+	fn shifted_nw(&self) -> Self { let mut res = self.clone(); res.shift_nw(); res }
+	/// Shifted bitboard one square south-east.
+	#[inline(always)]
+	fn shifted_se(&self) -> Self { let mut res = self.clone(); res.shift_se(); res }
+	/// Shifted bitboard one square south-west.
+	#[inline(always)]
+	fn shifted_sw(&self) -> Self { let mut res = self.clone(); res.shift_sw(); res }
+
+	/// Shift bitboard by `dx` columns and `dy` rows; squares leaving the board are dropped.
+	fn shift(&mut self, dx: i16, dy: i16) {
+		let (w, h) = (self.width() as u16, self.height() as u16);
+		if dx.unsigned_abs() >= w || dy.unsigned_abs() >= h {
+			*self = self.empty_with_same_shape();
+			return;
+		}
+		let mut keep = self.full_with_same_shape();
+		for i in 0..dx.unsigned_abs() {
+			let x = if dx > 0 { w - 1 - i } else { i };
+			keep &= !self.col_mask_with_same_shape(x as u8);
+		}
+		for i in 0..dy.unsigned_abs() {
+			let y = if dy > 0 { h - 1 - i } else { i };
+			keep &= !self.row_mask_with_same_shape(y as u8);
+		}
+		let offset = dx as isize * self.h_offset() as isize + dy as isize * self.v_offset() as isize;
+		shift_masked(self, keep, offset);
+	}
+	/// Shifted bitboard by `dx` columns and `dy` rows.
+	#[inline(always)]
+	fn shifted(&self, dx: i16, dy: i16) -> Self {
+		let mut res = self.clone();
+		res.shift(dx, dy);
+		res
+	}
+
+	/// Return the dilated board
+	fn dilated(&self) -> Self {
+		let mut res = self.clone();
+		res |= self.shifted_e();
+		res |= self.shifted_ne();
+		res |= self.shifted_n();
+		res |= self.shifted_nw();
+		res |= self.shifted_w();
+		res |= self.shifted_sw();
+		res |= self.shifted_s();
+		res |= self.shifted_se();
+		res
+	}
+	/// Return the eroded board
+	fn eroded(&self) -> Self {
+		let mut res = self.clone();
+		res &= self.shifted_e();
+		res &= self.shifted_ne();
+		res &= self.shifted_n();
+		res &= self.shifted_nw();
+		res &= self.shifted_w();
+		res &= self.shifted_sw();
+		res &= self.shifted_s();
+		res &= self.shifted_se();
+		res
+	}
+	/// Return all neighbors of any stone in the bitboard
+	fn neighbors_of_any(&self) -> Self {
+		self.dilated() & !self.clone()
+	}
+
+	/// Squares `s` such that `s - k * (dx, dy)` is set for every bit `k` of `mask`.
+	///
+	/// Shared implementation of the `detect_pattern_*` functions.
+	fn detect_pattern(&self, dx: i16, dy: i16, mut mask: u64) -> Self {
+		let mut res = self.full_with_same_shape();
+		while mask != 0 {
+			let k = mask.trailing_zeros() as i16;
+			mask &= mask - 1;
+			res &= self.shifted(k * dx, k * dy);
+		}
+		res
+	}
+	/// Squares ending the horizontal pattern `mask` (bit `k` set: `k` squares to the west is set).
+	#[inline]
+	fn detect_pattern_h(&self, mask: u64) -> Self {
+		self.detect_pattern(1, 0, mask)
+	}
+	/// Squares ending the vertical pattern `mask` (bit `k` set: `k` squares to the south is set).
+	#[inline]
+	fn detect_pattern_v(&self, mask: u64) -> Self {
+		self.detect_pattern(0, 1, mask)
+	}
+	/// Squares ending the ascending diagonal pattern `mask` (bit `k` set: `k` squares to the south-west is set).
+	#[inline]
+	fn detect_pattern_diag_inc(&self, mask: u64) -> Self {
+		self.detect_pattern(1, 1, mask)
+	}
+	/// Squares ending the descending diagonal pattern `mask` (bit `k` set: `k` squares to the north-west is set).
+	#[inline]
+	fn detect_pattern_diag_dec(&self, mask: u64) -> Self {
+		self.detect_pattern(1, -1, mask)
+	}
+
+	/// Squares starting a run of `n` set squares in direction `(dx, dy)`.
+	///
+	/// Runs are built by doubling: after each step, a set bit means the run starting
+	/// there has length `built`, and one last shift completes the remaining squares.
+	fn aligned_starts(&self, n: usize, dx: i16, dy: i16) -> Self {
+		if n == 0 {
+			return self.full_with_same_shape();
+		}
+		if n > u8::MAX as usize {
+			return self.empty_with_same_shape();
+		}
+		let mut temp = self.clone();
+		let mut built: i16 = 1;
+		while built * 2 <= n as i16 {
+			temp &= temp.shifted(-dx * built, -dy * built);
+			built *= 2;
+		}
+		let remaining = n as i16 - built;
+		if remaining > 0 {
+			temp &= temp.shifted(-dx * remaining, -dy * remaining);
+		}
+		temp
+	}
+
+	/// Returns `true` if `n` set squares are aligned in any direction.
+	#[inline]
+	fn has_n_aligned(&self, n: u8) -> bool {
+		if n == 0 { return true; }
+		if n == 1 { return self.any(); }
+
+		self.has_n_aligned_horizontal(n) ||
+			self.has_n_aligned_vertical(n) ||
+			self.has_n_aligned_diag_dec(n) ||
+			self.has_n_aligned_diag_inc(n)
+	}
+	/// Returns `true` if `n` set squares are aligned horizontally.
+	#[inline]
+	fn has_n_aligned_horizontal(&self, n: u8) -> bool {
+		n == 0 || self.aligned_starts(n as usize, 1, 0).any()
+	}
+	/// Returns `true` if `n` set squares are aligned vertically.
+	#[inline]
+	fn has_n_aligned_vertical(&self, n: u8) -> bool {
+		n == 0 || self.aligned_starts(n as usize, 0, 1).any()
+	}
+	/// Returns `true` if `n` set squares are aligned on a descending diagonal.
+	#[inline]
+	fn has_n_aligned_diag_dec(&self, n: u8) -> bool {
+		n == 0 || self.aligned_starts(n as usize, 1, -1).any()
+	}
+	/// Returns `true` if `n` set squares are aligned on an ascending diagonal.
+	#[inline]
+	fn has_n_aligned_diag_inc(&self, n: u8) -> bool {
+		n == 0 || self.aligned_starts(n as usize, 1, 1).any()
+	}
+
+	/// Returns `true` if `N` set squares are aligned in any direction.
+	#[inline]
+	fn has_aligned<const N: usize>(&self) -> bool {
+		N <= u8::MAX as usize && self.has_n_aligned(N as u8)
+	}
+	/// Returns `true` if `N` set squares are aligned horizontally.
+	#[inline]
+	fn has_aligned_horizontal<const N: usize>(&self) -> bool {
+		N <= u8::MAX as usize && self.has_n_aligned_horizontal(N as u8)
+	}
+	/// Returns `true` if `N` set squares are aligned vertically.
+	#[inline]
+	fn has_aligned_vertical<const N: usize>(&self) -> bool {
+		N <= u8::MAX as usize && self.has_n_aligned_vertical(N as u8)
+	}
+	/// Returns `true` if `N` set squares are aligned on a descending diagonal.
+	#[inline]
+	fn has_aligned_diag_dec<const N: usize>(&self) -> bool {
+		N <= u8::MAX as usize && self.has_n_aligned_diag_dec(N as u8)
+	}
+	/// Returns `true` if `N` set squares are aligned on an ascending diagonal.
+	#[inline]
+	fn has_aligned_diag_inc<const N: usize>(&self) -> bool {
+		N <= u8::MAX as usize && self.has_n_aligned_diag_inc(N as u8)
+	}
+
+	/// Number of runs of `N` aligned set squares in all directions (overlapping runs are counted).
+	#[inline]
+	fn count_aligned<const N: usize>(&self) -> u32 {
+		self.count_aligned_horizontal::<N>()
+			+ self.count_aligned_vertical::<N>()
+			+ self.count_aligned_diag_dec::<N>()
+			+ self.count_aligned_diag_inc::<N>()
+	}
+	/// Number of horizontal runs of `N` aligned set squares.
+	#[inline]
+	fn count_aligned_horizontal<const N: usize>(&self) -> u32 {
+		self.aligned_starts(N, 1, 0).count()
+	}
+	/// Number of vertical runs of `N` aligned set squares.
+	#[inline]
+	fn count_aligned_vertical<const N: usize>(&self) -> u32 {
+		self.aligned_starts(N, 0, 1).count()
+	}
+	/// Number of descending diagonal runs of `N` aligned set squares.
+	#[inline]
+	fn count_aligned_diag_dec<const N: usize>(&self) -> u32 {
+		self.aligned_starts(N, 1, -1).count()
+	}
+	/// Number of ascending diagonal runs of `N` aligned set squares.
+	#[inline]
+	fn count_aligned_diag_inc<const N: usize>(&self) -> u32 {
+		self.aligned_starts(N, 1, 1).count()
+	}
+}
+
+/// Keeps the bits of `b` inside `mask`, then moves them by `offset` indexes.
+#[inline(always)]
+fn shift_masked<B: RuntimeBitboard>(b: &mut B, mask: B, offset: isize) {
+	*b &= mask;
+	if offset >= 0 {
+		*b <<= offset as usize;
+	} else {
+		*b >>= (-offset) as usize;
+	}
+}
+
+/// `FromIterator` needs a shape, so runtime bitboards only `extend` an existing board.
+macro_rules! impl_collections_runtime {
+	(@impl [$($g:tt)*] $t:ty) => {
+		impl<'a, $($g)*> IntoIterator for &'a $t {
+			type Item = u32;
+			type IntoIter = crate::Bits<'a, $t>;
+
+			#[inline]
+			fn into_iter(self) -> Self::IntoIter {
+				crate::BitIter::bits(self)
+			}
+		}
+		impl<$($g)*> Extend<u32> for $t {
+			/// Sets the bits at the given indexes.
+			#[inline]
+			fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+				for i in iter {
+					self.set_at_index(i as usize);
+				}
+			}
+		}
+		impl<$($g)*> Extend<(u8, u8)> for $t {
+			/// Sets the bits at the given coordinates.
+			#[inline]
+			fn extend<I: IntoIterator<Item = (u8, u8)>>(&mut self, iter: I) {
+				for (x, y) in iter {
+					self.set(x, y);
+				}
+			}
+		}
+	};
+	($($t:ty),*) => {
+		$(impl_collections_runtime!(@impl [] $t);)*
+	};
+}
+impl_collections_runtime!(small_bitboard::SmallBitboard, medium_bitboard::MediumBitboard);
+impl_collections_runtime!(@impl [const WORDS: usize] large_bitboard_n::LargeBitboardN<WORDS>);
+#[cfg(feature = "alloc")]
+impl_collections_runtime!(large_bitboard::LargeBitboard, dyn_bitboard::DynBitboard);
+
+/// Borrowed-operand operators (`&a & &b`, `a & &b`, `!&a`, `&a << n`) and the `*_into` functions
+/// writing into an existing board, for the runtime bitboards stored in `u64` words.
+///
+/// The type needs the `&Self` assign operators, and a `clone_from` reusing the words it has.
+macro_rules! impl_borrowed_ops {
+	([$($g:tt)*] $t:ty) => {
+		impl<$($g)*> $t {
+			/// `out = a & b`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn and_into(a: &Self, b: &Self, out: &mut Self) {
+				out.clone_from(a);
+				*out &= b;
+			}
+			/// `out = a | b`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn or_into(a: &Self, b: &Self, out: &mut Self) {
+				out.clone_from(a);
+				*out |= b;
+			}
+			/// `out = a ^ b`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn xor_into(a: &Self, b: &Self, out: &mut Self) {
+				out.clone_from(a);
+				*out ^= b;
+			}
+			/// `out = a & !b`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn andnot_into(a: &Self, b: &Self, out: &mut Self) {
+				out.clone_from(b);
+				out.not_assign();
+				*out &= a;
+			}
+			/// `out = !a`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn not_into(a: &Self, out: &mut Self) {
+				out.clone_from(a);
+				out.not_assign();
+			}
+			/// `out = a << rhs`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn shl_into(a: &Self, rhs: usize, out: &mut Self) {
+				out.clone_from(a);
+				*out <<= rhs;
+			}
+			/// `out = a >> rhs`, without allocating when `out` already has the shape of `a`.
+			#[inline]
+			pub fn shr_into(a: &Self, rhs: usize, out: &mut Self) {
+				out.clone_from(a);
+				*out >>= rhs;
+			}
+		}
+
+		impl<$($g)*> core::ops::BitAnd<&$t> for &$t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn bitand(self, rhs: &$t) -> $t {
+				let mut res = self.clone();
+				res &= rhs;
+				res
+			}
+		}
+		impl<$($g)*> core::ops::BitOr<&$t> for &$t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn bitor(self, rhs: &$t) -> $t {
+				let mut res = self.clone();
+				res |= rhs;
+				res
+			}
+		}
+		impl<$($g)*> core::ops::BitXor<&$t> for &$t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn bitxor(self, rhs: &$t) -> $t {
+				let mut res = self.clone();
+				res ^= rhs;
+				res
+			}
+		}
+		impl<$($g)*> core::ops::BitAnd<&$t> for $t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn bitand(mut self, rhs: &$t) -> $t {
+				self &= rhs;
+				self
+			}
+		}
+		impl<$($g)*> core::ops::BitOr<&$t> for $t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn bitor(mut self, rhs: &$t) -> $t {
+				self |= rhs;
+				self
+			}
+		}
+		impl<$($g)*> core::ops::BitXor<&$t> for $t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn bitxor(mut self, rhs: &$t) -> $t {
+				self ^= rhs;
+				self
+			}
+		}
+		impl<$($g)*> core::ops::Not for &$t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn not(self) -> $t {
+				let mut res = self.clone();
+				res.not_assign();
+				res
+			}
+		}
+		impl<$($g)*> core::ops::Shl<usize> for &$t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn shl(self, rhs: usize) -> $t {
+				let mut res = self.clone();
+				res <<= rhs;
+				res
+			}
+		}
+		impl<$($g)*> core::ops::Shr<usize> for &$t {
+			type Output = $t;
+
+			#[inline(always)]
+			fn shr(self, rhs: usize) -> $t {
+				let mut res = self.clone();
+				res >>= rhs;
+				res
+			}
+		}
+	};
+}
+pub(crate) use impl_borrowed_ops;
\ No newline at end of file
@@ -1,11 +1,12 @@
-use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::RuntimeBitboard};
+use crate::{Bitboard, fmt_bitboard_debug, fmt_bitboard_display, runtime::{BitboardShape, RuntimeBitboard, debug_assert_same_shape}};
+#[cfg(feature = "alloc")]
+use {alloc::vec::Vec, crate::sliding::SlidingTable};
 #[derive(Clone, PartialEq, Eq, Copy)]
 pub struct SmallBitboard {
-	w: u8,
-	h: u8,
-	col_major: bool,
+	shape: BitboardShape,
 	bits: u64,
 }
+#[cfg(feature = "alloc")]
 impl SmallBitboard {
 	pub fn all_subsets(&self) -> Vec<Self>
 	{
@@ -16,7 +17,7 @@ impl SmallBitboard {
 
 		loop {
 			subsets.push(subset);
-			subset = Self::new(self.w, self.h, self.col_major, subset.bits.wrapping_sub(self.bits) & self.bits);
+			subset = Self::new(self.shape.w, self.shape.h, self.shape.col_major, subset.bits.wrapping_sub(self.bits) & self.bits);
 			if subset == zero {
 				break;
 			}
@@ -28,46 +29,52 @@ impl SmallBitboard {
 impl SmallBitboard {
 	#[inline(always)]
 	pub fn borders_with_same_shape(&self) -> Self {
-		Self::west_border(self.w, self.h, self.col_major) | Self::east_border(self.w, self.h, self.col_major) |
-		Self::north_border(self.w, self.h, self.col_major) | Self::south_border(self.w, self.h, self.col_major)
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major) | Self::east_border(self.shape.w, self.shape.h, self.shape.col_major) |
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major) | Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn west_border_with_same_shape(&self) -> Self {
-		Self::west_border(self.w, self.h, self.col_major)
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn east_border_with_same_shape(&self) -> Self {
-		Self::east_border(self.w, self.h, self.col_major)
+		Self::east_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn north_border_with_same_shape(&self) -> Self {
-		Self::north_border(self.w, self.h, self.col_major)
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn south_border_with_same_shape(&self) -> Self {
-		Self::south_border(self.w, self.h, self.col_major)
+		Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn full_with_same_shape(&self) -> Self {
-		Self::full(self.w, self.h, self.col_major)
+		Self::full(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	pub fn empty_with_same_shape(&self) -> Self {
-		Self::empty(self.w, self.h, self.col_major)
+		Self::empty(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 }
 impl Default for SmallBitboard {
 	#[inline(always)]
 	fn default() -> Self {
-		Self { w: 8, h: 8, col_major: false, bits: 0 }
+		Self { shape: BitboardShape::new(8, 8, false), bits: 0 }
 	}
 }
 impl RuntimeBitboard for SmallBitboard {
+	const MAX_SQUARES: usize = 64;
+
+	#[inline(always)]
+	fn shape(&self) -> BitboardShape {
+		self.shape
+	}
 	#[inline(always)]
 	fn new(w: u8, h: u8, col_major: bool, bits: u64) -> Self {
 		assert!(w*h<=64);
-		Self { w, h, col_major, bits }
+		Self { shape: BitboardShape::new(w, h, col_major), bits }
 	}
 	#[inline(always)]
 	fn borders(w: u8, h: u8, col_major: bool) -> Self {
@@ -76,6 +83,9 @@ impl RuntimeBitboard for SmallBitboard {
 	}
 	#[inline(always)]
 	fn west_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::col_mask(w, h, col_major, 0);
+		}
 		let mut bits = 0u64;
 		let mut v = 1u64;
 		for _ in 0..h {
@@ -88,6 +98,9 @@ impl RuntimeBitboard for SmallBitboard {
 
 	#[inline(always)]
 	fn east_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::col_mask(w, h, col_major, w - 1);
+		}
 		let mut bits = 0u64;
 		let mut v = 1u64 << (w - 1);
 
@@ -100,6 +113,9 @@ impl RuntimeBitboard for SmallBitboard {
 	}
 	#[inline(always)]
 	fn south_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::row_mask(w, h, col_major, 0);
+		}
 		let bits = if w == 64 {
 			u64::MAX
 		} else {
@@ -111,6 +127,9 @@ impl RuntimeBitboard for SmallBitboard {
 
 	#[inline(always)]
 	fn north_border(w: u8, h: u8, col_major: bool) -> Self {
+		if col_major {
+			return Self::row_mask(w, h, col_major, h - 1);
+		}
 		let mut bits = if w == 64 {
 			u64::MAX
 		} else {
@@ -164,77 +183,81 @@ impl RuntimeBitboard for SmallBitboard {
 	}
 	
 	fn full_with_same_shape(&self) -> Self {
-		Self::full(self.w, self.h, self.col_major)
+		Self::full(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	fn empty_with_same_shape(&self) -> Self {
-		Self::empty(self.w, self.h, self.col_major)
+		Self::empty(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	fn west_border_with_same_shape(&self) -> Self {
-		Self::west_border(self.w, self.h, self.col_major)
+		Self::west_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	fn east_border_with_same_shape(&self) -> Self {
-		Self::east_border(self.w, self.h, self.col_major)
+		Self::east_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	fn north_border_with_same_shape(&self) -> Self {
-		Self::north_border(self.w, self.h, self.col_major)
+		Self::north_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 
 	fn south_border_with_same_shape(&self) -> Self {
-		Self::south_border(self.w, self.h, self.col_major)
+		Self::south_border(self.shape.w, self.shape.h, self.shape.col_major)
 	}
 	#[inline(always)]
 	fn row_mask_with_same_shape(&self, y: u8) -> Self {
-		Self::row_mask(self.w, self.h, self.col_major, y)
+		Self::row_mask(self.shape.w, self.shape.h, self.shape.col_major, y)
 	}
 	#[inline(always)]
 	fn col_mask_with_same_shape(&self, x: u8) -> Self {
-		Self::col_mask(self.w, self.h, self.col_major, x)
+		Self::col_mask(self.shape.w, self.shape.h, self.shape.col_major, x)
 	}
 	
 	fn row_mask(w: u8, h: u8, col_major: bool, y: u8) -> Self {
 		if col_major {
-			Self::west_border(w,h,col_major) << y
+			let mut bits = 0u64;
+			for x in 0..w as usize {
+				bits |= 1 << (x * h as usize + y as usize);
+			}
+			Self { shape: BitboardShape::new(w, h, col_major), bits }
 		} else {
-			Self{ w, h, col_major, bits: ((1u64 << w) - 1) << (y * w) }
+			Self { shape: BitboardShape::new(w, h, col_major), bits: ((1u64 << w) - 1) << (y * w) }
 		}
 	}
 	fn col_mask(w: u8, h: u8, col_major: bool, x: u8) -> Self {
 		if col_major {
-			Self{ w, h, col_major, bits: ((1u64 << h) - 1) << (x * h) }
+			Self { shape: BitboardShape::new(w, h, col_major), bits: ((1u64 << h) - 1) << (x * h) }
 		} else {
 			Self::west_border(w,h,col_major) << x
 		}
 	}
 	
 	fn index_from_coords_with_same_shape(&self, x: u8, y: u8) -> usize {
-		Self::index_from_coords(self.w, self.h, self.col_major, x, y)
+		Self::index_from_coords(self.shape.w, self.shape.h, self.shape.col_major, x, y)
 	}
 	
 	fn coords_from_index_with_same_shape(&self, i: usize) -> (u8, u8) {
-		Self::coords_from_index(self.w, self.h, self.col_major, i)
+		Self::coords_from_index(self.shape.w, self.shape.h, self.shape.col_major, i)
 	}
 	
 	fn is_in_bounds_with_same_shape(&self, x: u8, y: u8) -> bool {
-		Self::is_in_bounds(self.w, self.h, x, y)
+		Self::is_in_bounds(self.shape.w, self.shape.h, x, y)
 	}
 	
 	fn is_index_in_bounds_with_same_shape(&self, i: usize) -> bool {
-		Self::is_index_in_bounds(self.w, self.h, i)
+		Self::is_index_in_bounds(self.shape.w, self.shape.h, i)
 	}
 	
 }
 impl Bitboard for SmallBitboard {
 	type Storage = u64;
 	#[inline(always)]
-	fn width(&self) -> u8 { self.w }
+	fn width(&self) -> u8 { self.shape.w }
 	#[inline(always)]
-	fn height(&self) -> u8 { self.h }
+	fn height(&self) -> u8 { self.shape.h }
 	#[inline(always)]
-	fn col_major(&self) -> bool { self.col_major }
+	fn col_major(&self) -> bool { self.shape.col_major }
 
 
 	#[inline(always)]
@@ -307,7 +330,7 @@ impl Bitboard for SmallBitboard {
 	
 	#[inline(always)]
 	fn flipped(&self) -> Self {
-		Self{ w: self.w, h: self.h, col_major: self.col_major, bits: !self.bits }
+		Self { shape: self.shape, bits: !self.bits }
 	}
 	#[inline]
 	fn lsb(&self) -> u32 {
@@ -325,63 +348,11 @@ impl Bitboard for SmallBitboard {
 	}
 	#[inline]
 	fn pext(&self, mask: &Self) -> Self::Storage {
-		#[cfg(debug_assertions)]
-		eprintln!("pext");
-		#[cfg(target_feature = "bmi2")]
-		unsafe {
-			std::arch::x86_64::_pext_u64(self.bits, mask.bits)
-		}
-		#[cfg(not(target_feature = "bmi2"))]
-		{
-			#[cfg(debug_assertions)]
-			eprintln!("pext fallback");
-			let mut res: u64 = 0;
-			let mut bit: u64 = 1;
-			let mut m = mask.bits;
-			let mut v = self.bits;
-
-			while m != 0 {
-				if m & 1 != 0 {
-					if v & 1 != 0 {
-						res |= bit;
-					}
-					bit <<= 1;
-				}
-				m >>= 1;
-				v >>= 1;
-			}
-			res
-		}
+		crate::bmi2::pext_u64(self.bits, mask.bits)
 	}
 	#[inline]
 	fn pdep(&self, compressed: Self::Storage) -> Self {
-		#[cfg(target_feature = "bmi2")]
-		unsafe {
-			//#[cfg(debug_assertions)]
-			//eprintln!("pdep_bmi2");
-			Self{w:self.w, h:self.h, col_major: self.col_major, bits:std::arch::x86_64::_pdep_u64(compressed, self.bits)}
-		}
-		#[cfg(not(target_feature = "bmi2"))]
-		{
-			#[cfg(debug_assertions)]
-			eprintln!("pdep fallback");
-			let mut res: u64 = 0;
-			let mut bit: u64 = 1;
-			let mut m = self.bits;
-			let mut v = compressed;
-
-			while m != 0 {
-				if m & 1 != 0 {
-					if v & 1 != 0 {
-						res |= bit;
-					}
-					v >>= 1;
-				}
-				bit <<= 1;
-				m >>= 1;
-			}
-			Self{w:self.w, h:self.h, col_major: self.col_major, bits: res}
-		}
+		Self { shape: self.shape, bits: crate::bmi2::pdep_u64(compressed, self.bits) }
 	}
 
 	#[inline(always)]
@@ -414,84 +385,93 @@ impl Bitboard for SmallBitboard {
 		*self = cleared | new_row;
 	}
 }
-impl std::ops::BitAnd for SmallBitboard {
+impl core::ops::BitAnd for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitand(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits &= rhs.bits;
 		self
 	}
 }
 
-impl std::ops::BitOr for SmallBitboard {
+impl core::ops::BitOr for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitor(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits |= rhs.bits;
 		self
 	}
 }
 
-impl std::ops::BitXor for SmallBitboard {
+impl core::ops::BitXor for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitxor(mut self, rhs: Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits ^= rhs.bits;
 		self
 	}
 }
-impl std::ops::BitAnd<&Self> for SmallBitboard {
+impl core::ops::BitAnd<&Self> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitand(mut self, rhs: &Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits &= rhs.bits;
 		self
 	}
 }
-impl std::ops::BitOr<&Self> for SmallBitboard {
+impl core::ops::BitOr<&Self> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitor(mut self, rhs: &Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits |= rhs.bits;
 		self
 	}
 }
-impl std::ops::BitXor<&Self> for SmallBitboard {
+impl core::ops::BitXor<&Self> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
 	fn bitxor(mut self, rhs: &Self) -> Self {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits ^= rhs.bits;
 		self
 	}
 }
 
-impl std::ops::BitAndAssign for SmallBitboard {
+impl core::ops::BitAndAssign for SmallBitboard {
 	#[inline(always)]
 	fn bitand_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits &= rhs.bits;
 	}
 }
 
-impl std::ops::BitOrAssign for SmallBitboard {
+impl core::ops::BitOrAssign for SmallBitboard {
 	#[inline(always)]
 	fn bitor_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits |= rhs.bits;
 	}
 }
 
-impl std::ops::BitXorAssign for SmallBitboard {
+impl core::ops::BitXorAssign for SmallBitboard {
 	#[inline(always)]
 	fn bitxor_assign(&mut self, rhs: Self) {
+		debug_assert_same_shape(&self.shape, &rhs.shape);
 		self.bits ^= rhs.bits;
 	}
 }
-impl std::ops::Not for SmallBitboard {
+impl core::ops::Not for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -501,7 +481,7 @@ impl std::ops::Not for SmallBitboard {
 	}
 }
 
-impl std::ops::Shl<usize> for SmallBitboard {
+impl core::ops::Shl<usize> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -511,7 +491,7 @@ impl std::ops::Shl<usize> for SmallBitboard {
 	}
 }
 
-impl std::ops::Shr<usize> for SmallBitboard {
+impl core::ops::Shr<usize> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -521,19 +501,19 @@ impl std::ops::Shr<usize> for SmallBitboard {
 	}
 }
 
-impl std::ops::ShlAssign<usize> for SmallBitboard {
+impl core::ops::ShlAssign<usize> for SmallBitboard {
 	#[inline(always)]
 	fn shl_assign(&mut self, rhs: usize) {
 		self.bits <<= rhs;
 	}
 }
-impl std::ops::ShrAssign<usize> for SmallBitboard {
+impl core::ops::ShrAssign<usize> for SmallBitboard {
 	#[inline(always)]
 	fn shr_assign(&mut self, rhs: usize) {
 		self.bits >>= rhs;
 	}
 }
-impl std::ops::Shl<u8> for SmallBitboard {
+impl core::ops::Shl<u8> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -543,7 +523,7 @@ impl std::ops::Shl<u8> for SmallBitboard {
 	}
 }
 
-impl std::ops::Shr<u8> for SmallBitboard {
+impl core::ops::Shr<u8> for SmallBitboard {
 	type Output = Self;
 
 	#[inline(always)]
@@ -553,17 +533,18 @@ impl std::ops::Shr<u8> for SmallBitboard {
 	}
 }
 
-impl std::fmt::Display for SmallBitboard {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SmallBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		fmt_bitboard_display(self, f)
 	}
 }
 
-impl std::fmt::Debug for SmallBitboard {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		fmt_bitboard_debug(self, std::mem::size_of_val(self.storage()) * 8, f)
+impl core::fmt::Debug for SmallBitboard {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		fmt_bitboard_debug(self, core::mem::size_of_val(self.storage()) * 8, f)
 	}
 }
+#[cfg(feature = "alloc")]
 impl SmallBitboard {
 	pub fn generate_sliding_moves(offsets: &[(i8, i8)], w: u8, h: u8, col_major: bool)
 		-> Vec<Self>
@@ -656,6 +637,7 @@ impl SmallBitboard {
 	}
 
 }
+#[cfg(feature = "alloc")]
 impl SmallBitboard {
 	pub fn generate_jump_moves(offsets: &[(i8, i8)], w: u8, h: u8, col_major: bool)
 		-> Vec<Self>
@@ -683,36 +665,10 @@ impl SmallBitboard {
 
 		moves
 	}
-	/*
-	pub fn generate_sliding_table<S: Sliding>(&self) -> SlidingTable<S::BB>
-	where
-		S: Sliding,
-		S::BB: RuntimeBitboard + Clone
-	{
-		let board_size = self.height() as usize * self.width() as usize;
-		let mut table = SlidingTable {
-			mask: vec![S::BB::empty(self.width(), self.height(), self.col_major()); board_size],
-			attacks: vec![Vec::new(); board_size],
-		};
-		let mut sq=0;
-		while sq < board_size {
-			let mask = S::mask(sq as u8);
-			let subsets = mask.all_subsets();
-			let mut atk_table = vec![S::BB::empty(self.width(), self.height(), self.col_major()); 1 << mask.count()];
-
-			for subset in subsets {
-				let idx = subset.pext(&mask);
-				atk_table[idx.to_usize()] = S::attacks(sq as u8, &subset);
-			}
-
-			table.mask[sq] = mask;
-			table.attacks[sq] = atk_table;
-			sq+=1;
-		}
-
-		table
+	/// Builds a sliding attack table for `directions` on boards shaped like `self`.
+	pub fn generate_sliding_table(&self, directions: &[(i8, i8)]) -> SlidingTable<Self> {
+		SlidingTable::new(&self.empty_with_same_shape(), directions)
 	}
-	*/
 
 }
 
@@ -752,7 +708,7 @@ mod tests {
 		let rook_dirs = vec![(1,0), (0,-1), (-1,0), (0,1)];
 		let moves = SmallBitboard::generate_sliding_moves(&rook_dirs, 8, 8, false);
 		println!("{}", moves[27]);
-		let moves = SmallBitboard::generate_sliding_moves(&KNIGHT_OFFSETS, 8, 8, false);
+		let moves = SmallBitboard::generate_sliding_moves(KNIGHT_OFFSETS, 8, 8, false);
 		println!("{}", moves[27]);
 		let moves= SmallBitboard::generate_jump_moves(&[(1,1),(-1,1)],8,8, false);
 		println!("{}", moves[27]);
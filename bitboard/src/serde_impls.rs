@@ -0,0 +1,153 @@
+//! `serde` support, enabled with the `serde` feature.
+//!
+//! Compile-time bitboards are serialized as their storage: an integer, or a tuple of
+//! 64-bit words for array storage. Runtime bitboards are serialized as a `shape` and
+//! their `bits`. Deserialization rejects bits set outside of the board.
+
+pub use serde;
+
+use alloc::{boxed::Box, format};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::{self, Error as _, SeqAccess, Visitor}, ser::SerializeTuple};
+
+use crate::{Bitboard, runtime::{BitboardShape, RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+
+/// Serializes the words of an array storage as a tuple.
+pub fn serialize_words<S: Serializer, const N: usize>(words: &[u64; N], serializer: S) -> Result<S::Ok, S::Error> {
+	let mut tuple = serializer.serialize_tuple(N)?;
+	for word in words {
+		tuple.serialize_element(word)?;
+	}
+	tuple.end()
+}
+
+/// Deserializes the words of an array storage, serialized with [`serialize_words`].
+pub fn deserialize_words<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u64; N], D::Error> {
+	struct WordsVisitor<const N: usize>;
+	impl<'de, const N: usize> Visitor<'de> for WordsVisitor<N> {
+		type Value = [u64; N];
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			write!(f, "a tuple of {} words", N)
+		}
+		fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+			let mut words = [0u64; N];
+			for (i, word) in words.iter_mut().enumerate() {
+				*word = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+			}
+			Ok(words)
+		}
+	}
+	deserializer.deserialize_tuple(N, WordsVisitor::<N>)
+}
+
+/// Implements `Serialize`/`Deserialize` for a `#[bitboard]` type, called by the proc macro.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_serde {
+	(int $t:ident) => {
+		impl $crate::__private::serde::Serialize for $t {
+			fn serialize<S: $crate::__private::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				$crate::__private::serde::Serialize::serialize(&self.0, serializer)
+			}
+		}
+		impl<'de> $crate::__private::serde::Deserialize<'de> for $t {
+			fn deserialize<D: $crate::__private::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let bits = <<Self as $crate::Bitboard>::Storage as $crate::__private::serde::Deserialize>::deserialize(deserializer)?;
+				if bits & !Self::FULL.0 != 0 {
+					return Err(<D::Error as $crate::__private::serde::de::Error>::custom(
+						format_args!("bits set outside of the {}x{} board", Self::WIDTH, Self::HEIGHT)
+					));
+				}
+				Ok(Self(bits))
+			}
+		}
+	};
+	(array $t:ident) => {
+		impl $crate::__private::serde::Serialize for $t {
+			fn serialize<S: $crate::__private::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				$crate::__private::serialize_words(&self.0, serializer)
+			}
+		}
+		impl<'de> $crate::__private::serde::Deserialize<'de> for $t {
+			fn deserialize<D: $crate::__private::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let bits = $crate::__private::deserialize_words(deserializer)?;
+				if bits.iter().zip(Self::FULL.0.iter()).any(|(w, f)| w & !f != 0) {
+					return Err(<D::Error as $crate::__private::serde::de::Error>::custom(
+						format_args!("bits set outside of the {}x{} board", Self::WIDTH, Self::HEIGHT)
+					));
+				}
+				Ok(Self(bits))
+			}
+		}
+	};
+}
+
+/// Serialized form of the runtime bitboards.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Bitboard")]
+struct Repr<T> {
+	shape: BitboardShape,
+	bits: T,
+}
+
+/// Checks that a deserialized `shape` holds at most `max_squares` squares.
+fn check_shape<E: de::Error>(shape: &BitboardShape, max_squares: usize, backend: &str) -> Result<(), E> {
+	if shape.w == 0 || shape.h == 0 {
+		return Err(E::custom(format_args!("invalid bitboard shape {}", shape)));
+	}
+	if shape.nb_squares() > max_squares {
+		return Err(E::custom(format_args!("shape {} does not fit in a {}", shape, backend)));
+	}
+	Ok(())
+}
+
+/// Checks that a deserialized bitboard has no bit set outside of its shape.
+fn check_bits<B: RuntimeBitboard, E: de::Error>(b: B) -> Result<B, E> {
+	if (b.clone() & !b.full_with_same_shape()).any() {
+		return Err(E::custom(format_args!("bits set outside of the {} board", b.shape())));
+	}
+	Ok(b)
+}
+
+impl Serialize for SmallBitboard {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		Repr { shape: self.shape(), bits: *self.storage() }.serialize(serializer)
+	}
+}
+impl<'de> Deserialize<'de> for SmallBitboard {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let Repr { shape, bits } = Repr::<u64>::deserialize(deserializer)?;
+		check_shape(&shape, 64, "SmallBitboard")?;
+		check_bits(Self::new(shape.w, shape.h, shape.col_major, bits))
+	}
+}
+
+impl Serialize for MediumBitboard {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		Repr { shape: self.shape(), bits: *self.storage() }.serialize(serializer)
+	}
+}
+impl<'de> Deserialize<'de> for MediumBitboard {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let Repr { shape, bits } = Repr::<u128>::deserialize(deserializer)?;
+		check_shape(&shape, 128, "MediumBitboard")?;
+		check_bits(<Self as RuntimeBitboard>::new(shape.w, shape.h, shape.col_major, bits))
+	}
+}
+
+impl Serialize for LargeBitboard {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		Repr { shape: self.shape(), bits: self.storage() }.serialize(serializer)
+	}
+}
+impl<'de> Deserialize<'de> for LargeBitboard {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let Repr { shape, bits } = Repr::<Box<[u64]>>::deserialize(deserializer)?;
+		check_shape(&shape, usize::MAX, "LargeBitboard")?;
+		let nb_words = shape.nb_squares().div_ceil(64);
+		if bits.len() != nb_words {
+			return Err(D::Error::invalid_length(bits.len(), &format!("{} words for a {} board", nb_words, shape).as_str()));
+		}
+		check_bits(Self::new(shape.w, shape.h, shape.col_major, bits))
+	}
+}
@@ -0,0 +1,367 @@
+//! Word kernels of the array storage, with an optional SIMD path.
+//!
+//! The `#[bitboard]` types stored in `[u64; N]` and [`LargeBitboard`](crate::runtime::large_bitboard::LargeBitboard)
+//! run their boolean operators, shifts, `count` and `is_empty` through these functions.
+//! With the `simd` feature on `x86_64`, they use AVX2 when the CPU has it (detected at run time)
+//! and SSE2 otherwise. Without it, or on other architectures, they are the [`scalar`] loops.
+//! The `const fn` of the macro types always use scalar code.
+//!
+//! The binary operations work on the common prefix of `a` and `b`, which have the same length
+//! for bitboards of the same shape.
+
+/// Word by word loops, the fallback of every kernel.
+pub mod scalar {
+	/// `a &= b`.
+	#[inline]
+	pub fn and_assign(a: &mut [u64], b: &[u64]) {
+		for (a, b) in a.iter_mut().zip(b) {
+			*a &= *b;
+		}
+	}
+	/// `a |= b`.
+	#[inline]
+	pub fn or_assign(a: &mut [u64], b: &[u64]) {
+		for (a, b) in a.iter_mut().zip(b) {
+			*a |= *b;
+		}
+	}
+	/// `a ^= b`.
+	#[inline]
+	pub fn xor_assign(a: &mut [u64], b: &[u64]) {
+		for (a, b) in a.iter_mut().zip(b) {
+			*a ^= *b;
+		}
+	}
+	/// `a = !a`.
+	#[inline]
+	pub fn not_assign(a: &mut [u64]) {
+		for a in a.iter_mut() {
+			*a = !*a;
+		}
+	}
+	/// Shifts the words, least significant first, `rhs` bits towards the most significant end.
+	#[inline]
+	pub fn shl_assign(a: &mut [u64], rhs: usize) {
+		let len = a.len();
+		let (word_shift, bit_shift) = (rhs / 64, (rhs % 64) as u32);
+		if word_shift >= len {
+			a.fill(0);
+			return;
+		}
+		for i in (word_shift..len).rev() {
+			a[i] = if bit_shift == 0 {
+				a[i - word_shift]
+			} else if i > word_shift {
+				(a[i - word_shift] << bit_shift) | (a[i - word_shift - 1] >> (64 - bit_shift))
+			} else {
+				a[i - word_shift] << bit_shift
+			};
+		}
+		a[..word_shift].fill(0);
+	}
+	/// Shifts the words, least significant first, `rhs` bits towards the least significant end.
+	#[inline]
+	pub fn shr_assign(a: &mut [u64], rhs: usize) {
+		let len = a.len();
+		let (word_shift, bit_shift) = (rhs / 64, (rhs % 64) as u32);
+		if word_shift >= len {
+			a.fill(0);
+			return;
+		}
+		for i in 0..len - word_shift {
+			a[i] = if bit_shift == 0 {
+				a[i + word_shift]
+			} else if i + word_shift + 1 < len {
+				(a[i + word_shift] >> bit_shift) | (a[i + word_shift + 1] << (64 - bit_shift))
+			} else {
+				a[i + word_shift] >> bit_shift
+			};
+		}
+		a[len - word_shift..].fill(0);
+	}
+	/// Number of bits set.
+	#[inline]
+	pub fn count_ones(a: &[u64]) -> u32 {
+		a.iter().map(|a| a.count_ones()).sum()
+	}
+	/// Are all the words zero.
+	#[inline]
+	pub fn is_zero(a: &[u64]) -> bool {
+		a.iter().all(|&a| a == 0)
+	}
+}
+
+/// SSE2 and AVX2 versions of the kernels, `LANES` words at a time, the rest with [`scalar`].
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+	macro_rules! kernels {
+		($module:ident, $feature:literal, $vec:ty, $lanes:literal,
+			$load:ident, $store:ident, $and:ident, $or:ident, $xor:ident, $sll:ident, $srl:ident,
+			$add:ident, $sub:ident, $sad:ident, $set1:ident, $zero:ident) => {
+			pub(super) mod $module {
+				use core::arch::x86_64::*;
+
+				use super::super::scalar;
+
+				const LANES: usize = $lanes;
+
+				#[target_feature(enable = $feature)]
+				unsafe fn load(words: &[u64], i: usize) -> $vec {
+					debug_assert!(i + LANES <= words.len());
+					unsafe { $load(words.as_ptr().add(i).cast()) }
+				}
+				#[target_feature(enable = $feature)]
+				unsafe fn store(words: &mut [u64], i: usize, v: $vec) {
+					debug_assert!(i + LANES <= words.len());
+					unsafe { $store(words.as_mut_ptr().add(i).cast(), v) }
+				}
+				/// Shift count of the `sll`/`srl` instructions, 64 or more clearing the lanes.
+				#[target_feature(enable = $feature)]
+				fn count(n: u32) -> __m128i {
+					_mm_cvtsi64_si128(n as i64)
+				}
+
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn and_assign(a: &mut [u64], b: &[u64]) {
+					let n = a.len().min(b.len());
+					let mut i = 0;
+					while i + LANES <= n {
+						unsafe {
+							let v = $and(load(a, i), load(b, i));
+							store(a, i, v);
+						}
+						i += LANES;
+					}
+					scalar::and_assign(&mut a[i..], &b[i..]);
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn or_assign(a: &mut [u64], b: &[u64]) {
+					let n = a.len().min(b.len());
+					let mut i = 0;
+					while i + LANES <= n {
+						unsafe {
+							let v = $or(load(a, i), load(b, i));
+							store(a, i, v);
+						}
+						i += LANES;
+					}
+					scalar::or_assign(&mut a[i..], &b[i..]);
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn xor_assign(a: &mut [u64], b: &[u64]) {
+					let n = a.len().min(b.len());
+					let mut i = 0;
+					while i + LANES <= n {
+						unsafe {
+							let v = $xor(load(a, i), load(b, i));
+							store(a, i, v);
+						}
+						i += LANES;
+					}
+					scalar::xor_assign(&mut a[i..], &b[i..]);
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn not_assign(a: &mut [u64]) {
+					let ones = $set1(-1);
+					let mut i = 0;
+					while i + LANES <= a.len() {
+						unsafe {
+							let v = $xor(load(a, i), ones);
+							store(a, i, v);
+						}
+						i += LANES;
+					}
+					scalar::not_assign(&mut a[i..]);
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn shl_assign(a: &mut [u64], rhs: usize) {
+					let len = a.len();
+					let (word_shift, bit_shift) = (rhs / 64, (rhs % 64) as u32);
+					if word_shift >= len {
+						a.fill(0);
+						return;
+					}
+					// Word `i` gets `a[i - word_shift] << bit_shift | a[i - word_shift - 1] >> (64 - bit_shift)`,
+					// from the top so that the sources are not overwritten yet
+					let (left, right) = (count(bit_shift), count(64 - bit_shift));
+					let mut end = len;
+					while end >= word_shift + 1 + LANES {
+						let i = end - LANES;
+						unsafe {
+							let v = $or($sll(load(a, i - word_shift), left), $srl(load(a, i - word_shift - 1), right));
+							store(a, i, v);
+						}
+						end = i;
+					}
+					// The last words, as in the scalar version
+					for i in (word_shift..end).rev() {
+						a[i] = if bit_shift == 0 {
+							a[i - word_shift]
+						} else if i > word_shift {
+							(a[i - word_shift] << bit_shift) | (a[i - word_shift - 1] >> (64 - bit_shift))
+						} else {
+							a[i - word_shift] << bit_shift
+						};
+					}
+					a[..word_shift].fill(0);
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn shr_assign(a: &mut [u64], rhs: usize) {
+					let len = a.len();
+					let (word_shift, bit_shift) = (rhs / 64, (rhs % 64) as u32);
+					if word_shift >= len {
+						a.fill(0);
+						return;
+					}
+					// Word `i` gets `a[i + word_shift] >> bit_shift | a[i + word_shift + 1] << (64 - bit_shift)`,
+					// from the bottom so that the sources are not overwritten yet
+					let (right, left) = (count(bit_shift), count(64 - bit_shift));
+					let mut i = 0;
+					while i + word_shift + 1 + LANES <= len {
+						unsafe {
+							let v = $or($srl(load(a, i + word_shift), right), $sll(load(a, i + word_shift + 1), left));
+							store(a, i, v);
+						}
+						i += LANES;
+					}
+					for i in i..len - word_shift {
+						a[i] = if bit_shift == 0 {
+							a[i + word_shift]
+						} else if i + word_shift + 1 < len {
+							(a[i + word_shift] >> bit_shift) | (a[i + word_shift + 1] << (64 - bit_shift))
+						} else {
+							a[i + word_shift] >> bit_shift
+						};
+					}
+					a[len - word_shift..].fill(0);
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn count_ones(a: &[u64]) -> u32 {
+					// Bit counts of the 2-bit, 4-bit then 8-bit fields, and the sums of the bytes of each word
+					let (m1, m2, m4) = ($set1(0x5555_5555_5555_5555), $set1(0x3333_3333_3333_3333), $set1(0x0F0F_0F0F_0F0F_0F0F));
+					let (c1, c2, c4) = (count(1), count(2), count(4));
+					let mut sums = $zero();
+					let mut i = 0;
+					while i + LANES <= a.len() {
+						let mut v = unsafe { load(a, i) };
+						v = $sub(v, $and($srl(v, c1), m1));
+						v = $add($and(v, m2), $and($srl(v, c2), m2));
+						v = $and($add(v, $srl(v, c4)), m4);
+						sums = $add(sums, $sad(v, $zero()));
+						i += LANES;
+					}
+					let mut lanes = [0u64; LANES];
+					unsafe { store(&mut lanes, 0, sums) };
+					lanes.iter().sum::<u64>() as u32 + scalar::count_ones(&a[i..])
+				}
+				#[target_feature(enable = $feature)]
+				pub(crate) unsafe fn is_zero(a: &[u64]) -> bool {
+					let mut acc = $zero();
+					let mut i = 0;
+					while i + LANES <= a.len() {
+						acc = $or(acc, unsafe { load(a, i) });
+						i += LANES;
+					}
+					let mut lanes = [0u64; LANES];
+					unsafe { store(&mut lanes, 0, acc) };
+					scalar::is_zero(&lanes) && scalar::is_zero(&a[i..])
+				}
+			}
+		};
+	}
+
+	kernels!(sse2, "sse2", __m128i, 2,
+		_mm_loadu_si128, _mm_storeu_si128, _mm_and_si128, _mm_or_si128, _mm_xor_si128, _mm_sll_epi64, _mm_srl_epi64,
+		_mm_add_epi64, _mm_sub_epi64, _mm_sad_epu8, _mm_set1_epi64x, _mm_setzero_si128);
+	kernels!(avx2, "avx2", __m256i, 4,
+		_mm256_loadu_si256, _mm256_storeu_si256, _mm256_and_si256, _mm256_or_si256, _mm256_xor_si256, _mm256_sll_epi64, _mm256_srl_epi64,
+		_mm256_add_epi64, _mm256_sub_epi64, _mm256_sad_epu8, _mm256_set1_epi64x, _mm256_setzero_si256);
+}
+
+/// Defines the public kernels: AVX2 or SSE2 with the `simd` feature on `x86_64`, scalar otherwise.
+macro_rules! dispatch {
+	($($(#[$attr:meta])* fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+		$(
+			$(#[$attr])*
+			#[inline]
+			pub fn $name($($arg: $ty),*) $(-> $ret)? {
+				#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+				{
+					// SSE2 is part of x86_64
+					if std::is_x86_feature_detected!("avx2") {
+						unsafe { x86::avx2::$name($($arg),*) }
+					} else {
+						unsafe { x86::sse2::$name($($arg),*) }
+					}
+				}
+				#[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+				{
+					scalar::$name($($arg),*)
+				}
+			}
+		)*
+	};
+}
+
+dispatch! {
+	/// `a &= b`.
+	fn and_assign(a: &mut [u64], b: &[u64]);
+	/// `a |= b`.
+	fn or_assign(a: &mut [u64], b: &[u64]);
+	/// `a ^= b`.
+	fn xor_assign(a: &mut [u64], b: &[u64]);
+	/// `a = !a`.
+	fn not_assign(a: &mut [u64]);
+	/// Shifts the words, least significant first, `rhs` bits towards the most significant end.
+	fn shl_assign(a: &mut [u64], rhs: usize);
+	/// Shifts the words, least significant first, `rhs` bits towards the least significant end.
+	fn shr_assign(a: &mut [u64], rhs: usize);
+	/// Number of bits set.
+	fn count_ones(a: &[u64]) -> u32;
+	/// Are all the words zero.
+	fn is_zero(a: &[u64]) -> bool;
+}
+
+#[cfg(all(test, feature = "simd", target_arch = "x86_64"))]
+mod tests {
+	use super::{scalar, x86};
+
+	/// Both SIMD widths against the scalar kernels, whatever the dispatch picks on this CPU.
+	#[test]
+	fn test_sse2_avx2() {
+		let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+		let mut next = move || {
+			seed ^= seed << 13;
+			seed ^= seed >> 7;
+			seed ^= seed << 17;
+			seed
+		};
+		let avx2 = std::is_x86_feature_detected!("avx2");
+		for len in 0..11 {
+			let a: Vec<u64> = (0..len).map(|_| next()).collect();
+			let b: Vec<u64> = (0..len).map(|_| next()).collect();
+			let mut expected = a.clone();
+			scalar::xor_assign(&mut expected, &b);
+			let mut res = a.clone();
+			unsafe { x86::sse2::xor_assign(&mut res, &b) };
+			assert_eq!(res, expected);
+			assert_eq!(unsafe { x86::sse2::count_ones(&a) }, scalar::count_ones(&a));
+			assert_eq!(unsafe { x86::sse2::is_zero(&a) }, scalar::is_zero(&a));
+			if avx2 {
+				assert_eq!(unsafe { x86::avx2::count_ones(&a) }, scalar::count_ones(&a));
+			}
+			for rhs in 0..len * 64 + 1 {
+				let mut expected = a.clone();
+				scalar::shl_assign(&mut expected, rhs);
+				let mut res = a.clone();
+				unsafe { x86::sse2::shl_assign(&mut res, rhs) };
+				assert_eq!(res, expected, "{len} words << {rhs}");
+				let mut expected = a.clone();
+				scalar::shr_assign(&mut expected, rhs);
+				let mut res = a.clone();
+				unsafe { x86::sse2::shr_assign(&mut res, rhs) };
+				assert_eq!(res, expected, "{len} words >> {rhs}");
+			}
+		}
+	}
+}
@@ -0,0 +1,411 @@
+use crate::{Bitboard, WordStorage, bmi2};
+use crate::runtime::{RuntimeBitboard, large_bitboard_n::LargeBitboardN, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard};
+#[cfg(feature = "alloc")]
+use {alloc::{borrow::Cow, vec::Vec}, crate::{coords_from_index, runtime::{dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard}}};
+
+/// Orthogonal (rook-like) sliding directions.
+pub const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+/// Diagonal (bishop-like) sliding directions.
+pub const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
+
+/// Per-square lookup data of a [`SlidingTable`].
+#[derive(Clone, Debug)]
+pub struct SlidingEntry<B> {
+	/// Relevant occupancy mask (the rays without their last square).
+	mask: B,
+	/// Start of this square's attacks in the flat attack array.
+	offset: usize,
+	/// Magic multiplier (unused when indexing with PEXT).
+	magic: u64,
+	/// Right shift applied after the magic multiplication.
+	shift: u8,
+	/// Rotation applied to word `i` (times `i`) when folding multi-word storage.
+	rot: u8,
+}
+
+impl<B> SlidingEntry<B> {
+	/// Entry of a table built at compile time by `tables(rook_sliding, bishop_sliding)`.
+	pub const fn new(mask: B, offset: usize, magic: u64, shift: u8, rot: u8) -> Self {
+		Self { mask, offset, magic, shift, rot }
+	}
+}
+
+/// Precomputed sliding attack table (rook-like, bishop-like or any set of directions).
+///
+/// Attacks of every square are stored in a single flat array. Each square owns a
+/// contiguous slice starting at its offset, indexed by its blockers:
+/// - with PEXT, the index is the PEXT of the occupancy by the relevant mask;
+/// - otherwise, the masked occupancy is folded into a `u64` and hashed with a
+///   magic multiplication found when the table is built.
+///
+/// Tables are generated at compile time by `#[bitboard(tables(rook_sliding, bishop_sliding))]`,
+/// indexed with PEXT when the target has BMI2, or at run time by [`SlidingTable::new`] (with the
+/// `alloc` feature), indexed with PEXT when [`bmi2::uses_hardware`].
+///
+/// The table size grows as `2^bits` per square, `bits` being the number of
+/// relevant squares, so it is only practical for small boards (up to ~10x10 for rooks).
+#[derive(Clone, Debug)]
+pub struct SlidingTable<B: Bitboard + 'static> {
+	#[cfg(feature = "alloc")]
+	entries: Cow<'static, [SlidingEntry<B>]>,
+	#[cfg(not(feature = "alloc"))]
+	entries: &'static [SlidingEntry<B>],
+	#[cfg(feature = "alloc")]
+	attacks: Cow<'static, [B]>,
+	#[cfg(not(feature = "alloc"))]
+	attacks: &'static [B],
+	/// Indexing with PEXT rather than magics.
+	pext: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<B> SlidingTable<B>
+where
+	B: Bitboard + 'static,
+	B::Storage: WordStorage,
+{
+	/// Builds a rook-like (orthogonal) sliding table for boards shaped like `empty`.
+	pub fn rook(empty: &B) -> Self {
+		Self::new(empty, &ROOK_DIRECTIONS)
+	}
+	/// Builds a bishop-like (diagonal) sliding table for boards shaped like `empty`.
+	pub fn bishop(empty: &B) -> Self {
+		Self::new(empty, &BISHOP_DIRECTIONS)
+	}
+	/// Builds a sliding table for the given `directions`.
+	///
+	/// `empty` must be an empty bitboard: it gives the shape of the table and is used as
+	/// the starting point of every generated mask and attack set.
+	pub fn new(empty: &B, directions: &[(i8, i8)]) -> Self {
+		let nb_squares = empty.width() as usize * empty.height() as usize;
+		let mut entries = Vec::with_capacity(nb_squares);
+		let mut attacks = Vec::new();
+		let mut rng = MagicRng(0x9E37_79B9_7F4A_7C15);
+		let pext = bmi2::uses_hardware();
+
+		for sq in 0..nb_squares {
+			let mask = Self::relevant_mask(empty, sq, directions);
+			let mask_indices: Vec<usize> = (0..nb_squares).filter(|&i| mask.get_at_index(i)).collect();
+			let bits = mask_indices.len();
+			assert!(bits <= 32, "sliding table: {} relevant squares is too many for square {}", bits, sq);
+
+			let size = 1usize << bits;
+			let mut occupancies = Vec::with_capacity(size);
+			let mut sq_attacks = Vec::with_capacity(size);
+			for subset in 0..size {
+				let mut occ = empty.clone();
+				for (bit, &i) in mask_indices.iter().enumerate() {
+					if subset & (1 << bit) != 0 {
+						occ.set_at_index(i);
+					}
+				}
+				sq_attacks.push(Self::attacks_slow(empty, sq, &occ, directions));
+				occupancies.push(occ);
+			}
+
+			let mut entry = SlidingEntry { mask, offset: attacks.len(), magic: 0, shift: (64 - bits) as u8, rot: 0 };
+			if !pext {
+				entry.rot = Self::find_rotation(&entry.mask, sq);
+				(entry.magic, entry.shift) = Self::find_magic(&entry, sq, &occupancies, &sq_attacks, &mut rng);
+			}
+
+			let slot_size = 1usize << (64 - entry.shift as usize);
+			attacks.resize(attacks.len() + slot_size, empty.clone());
+			for (occ, atk) in occupancies.iter().zip(sq_attacks) {
+				let idx = entry.offset + Self::index(pext, &entry, occ.storage());
+				attacks[idx] = atk;
+			}
+			entries.push(entry);
+		}
+
+		Self { entries: Cow::Owned(entries), attacks: Cow::Owned(attacks), pext }
+	}
+
+}
+
+impl<B: Bitboard + 'static> SlidingTable<B> {
+	/// Table from its entries and flat attack array, e.g. built at compile time by
+	/// `tables(rook_sliding, bishop_sliding)`; `pext` tells how the attacks are indexed.
+	pub const fn from_static(entries: &'static [SlidingEntry<B>], attacks: &'static [B], pext: bool) -> Self {
+		#[cfg(feature = "alloc")]
+		let (entries, attacks) = (Cow::Borrowed(entries), Cow::Borrowed(attacks));
+		Self { entries, attacks, pext }
+	}
+
+	/// Returns the sliding attacks from `square` given the board `occupancy`.
+	///
+	/// Attacks stop on (and include) the first occupied square of each direction.
+	#[inline(always)]
+	pub fn attacks(&self, square: usize, occupancy: &B) -> &B {
+		let entry = &self.entries[square];
+		&self.attacks[entry.offset + Self::index(self.pext, entry, occupancy.storage())]
+	}
+	/// Returns the relevant occupancy mask of `square`.
+	#[inline(always)]
+	pub fn mask(&self, square: usize) -> &B {
+		&self.entries[square].mask
+	}
+	/// Total number of attack sets stored in the flat array.
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		self.attacks.len()
+	}
+	/// Returns `true` if the table holds no attack set (board without squares).
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.attacks.is_empty()
+	}
+
+	#[inline(always)]
+	fn index(pext: bool, entry: &SlidingEntry<B>, occupancy: &B::Storage) -> usize {
+		if pext {
+			let mask = entry.mask.storage();
+			let mut idx = 0usize;
+			let mut shift = 0;
+			for i in 0..mask.nb_words() {
+				let m = mask.word(i);
+				if m != 0 {
+					idx |= (bmi2::pext_u64(occupancy.word(i), m) as usize) << shift;
+					shift += m.count_ones();
+				}
+			}
+			idx
+		} else {
+			let key = fold(occupancy, entry.mask.storage(), entry.rot);
+			key.wrapping_mul(entry.magic).checked_shr(entry.shift as u32).unwrap_or(0) as usize
+		}
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<B> SlidingTable<B>
+where
+	B: Bitboard + 'static,
+	B::Storage: WordStorage,
+{
+	/// Finds a word rotation making the folded mask words disjoint, so the fold is injective.
+	fn find_rotation(mask: &B, square: usize) -> u8 {
+		let storage = mask.storage();
+		let total: u32 = (0..storage.nb_words()).map(|i| storage.word(i).count_ones()).sum();
+		(0..64u8)
+			.find(|&rot| fold(storage, storage, rot).count_ones() == total)
+			.unwrap_or_else(|| panic!("sliding table: cannot fold the mask of square {} into 64 bits", square))
+	}
+
+	/// Searches a magic for the square, returning it with its shift.
+	///
+	/// When no perfect magic shows up quickly, the square gets a larger slot
+	/// (one more index bit), which makes the search much easier, up to [`MAGIC_EXTRA_BITS`] more.
+	fn find_magic(entry: &SlidingEntry<B>, square: usize, occupancies: &[B], attacks: &[B], rng: &mut MagicRng) -> (u64, u8) {
+		let keys: Vec<u64> = occupancies.iter()
+			.map(|occ| fold(occ.storage(), entry.mask.storage(), entry.rot))
+			.collect();
+		if keys.len() == 1 {
+			return (0, 64);
+		}
+		let mask_key = fold(entry.mask.storage(), entry.mask.storage(), entry.rot);
+		let mut shift = entry.shift;
+		let mut used: Vec<Option<usize>> = Vec::new();
+		loop {
+			used.clear();
+			used.resize(1 << (64 - shift), None);
+			for _ in 0..MAGIC_TRIES {
+				let magic = rng.sparse();
+				if keys.len() >= 64 && (mask_key.wrapping_mul(magic) >> 56).count_ones() < 6 {
+					continue;
+				}
+				used.iter_mut().for_each(|u| *u = None);
+				let ok = keys.iter().enumerate().all(|(i, key)| {
+					let idx = (key.wrapping_mul(magic) >> shift) as usize;
+					match used[idx] {
+						None => { used[idx] = Some(i); true }
+						Some(j) => attacks[j] == attacks[i],
+					}
+				});
+				if ok {
+					return (magic, shift);
+				}
+			}
+			assert!(entry.shift - shift < MAGIC_EXTRA_BITS, "sliding table: no magic found for square {}", square);
+			shift -= 1;
+		}
+	}
+
+	fn relevant_mask(empty: &B, square: usize, directions: &[(i8, i8)]) -> B {
+		let mut mask = empty.clone();
+		let (x0, y0) = coords_from_index(empty, square);
+		for &(dx, dy) in directions {
+			let mut x = x0 as i16 + dx as i16;
+			let mut y = y0 as i16 + dy as i16;
+			// the last square of a ray never blocks anything
+			while is_in_bounds(empty, x + dx as i16, y + dy as i16) {
+				mask.set(x as u8, y as u8);
+				x += dx as i16;
+				y += dy as i16;
+			}
+		}
+		mask
+	}
+
+	fn attacks_slow(empty: &B, square: usize, occupancy: &B, directions: &[(i8, i8)]) -> B {
+		let mut attacks = empty.clone();
+		let (x0, y0) = coords_from_index(empty, square);
+		for &(dx, dy) in directions {
+			let mut x = x0 as i16 + dx as i16;
+			let mut y = y0 as i16 + dy as i16;
+			while is_in_bounds(empty, x, y) {
+				attacks.set(x as u8, y as u8);
+				if occupancy.get(x as u8, y as u8) {
+					break;
+				}
+				x += dx as i16;
+				y += dy as i16;
+			}
+		}
+		attacks
+	}
+}
+
+/// Table-free sliding attacks, from Kogge-Stone occluded fills.
+///
+/// A fill moves every generator along a direction in `log2` rounds of shifts, doubling the
+/// distance at each round, so that it costs the same for one slider or for all of them, on any
+/// board size. Implemented by the `#[bitboard]` types without wrap-around and by the runtime
+/// bitboards; see [`sliding_attacks`] and [`attacks_of_all`].
+pub trait OccludedFill: Bitboard {
+	/// Squares one step of `direction` away from a square of the board,
+	/// e.g. all but the west column for `(1, 0)`.
+	fn step_targets(&self, direction: (i8, i8)) -> Self;
+
+	/// Moves the squares of `self` along `direction` through the `empty` squares.
+	///
+	/// The result holds the generators and every square reached, not the blockers.
+	fn occluded_fill(&self, empty: &Self, direction: (i8, i8)) -> Self {
+		let offset = step_offset(self, direction);
+		let len = ray_len(self, direction);
+		let mut generators = self.clone();
+		// Empty squares entered from a neighbour: shifting them never wraps
+		let mut propagators = empty.clone() & self.step_targets(direction);
+		let mut k = 1;
+		while k < len {
+			generators |= propagators.clone() & shifted_storage(&generators, offset * k as isize);
+			propagators &= shifted_storage(&propagators, offset * k as isize);
+			k *= 2;
+		}
+		generators
+	}
+
+	/// Squares attacked along `direction` by the squares of `self`, up to and including the
+	/// first square that is not `empty`.
+	#[inline]
+	fn sliding_attacks_towards(&self, empty: &Self, direction: (i8, i8)) -> Self {
+		let fill = self.occluded_fill(empty, direction);
+		shifted_storage(&fill, step_offset(self, direction)) & self.step_targets(direction)
+	}
+}
+
+/// Squares attacked from `from` along `directions`, up to and including the first occupied
+/// square of each direction, without tables.
+#[inline]
+pub fn sliding_attacks<B: OccludedFill>(from: usize, occupancy: &B, directions: &[(i8, i8)]) -> B {
+	let empty = occupancy.clone() ^ occupancy.clone();
+	let mut slider = empty.clone();
+	slider.set_at_index(from);
+	attacks_of_all(&slider, occupancy, directions)
+}
+
+/// Squares attacked by any of the `sliders` along `directions`, without tables.
+#[inline]
+pub fn attacks_of_all<B: OccludedFill>(sliders: &B, occupancy: &B, directions: &[(i8, i8)]) -> B {
+	let empty = occupancy.flipped();
+	directions.iter().fold(sliders.clone() ^ sliders.clone(), |attacks, &direction| {
+		attacks | sliders.sliding_attacks_towards(&empty, direction)
+	})
+}
+
+/// Offset of one step of `direction` in the storage of `b`.
+#[inline(always)]
+fn step_offset<B: Bitboard>(b: &B, (dx, dy): (i8, i8)) -> isize {
+	let (h_offset, v_offset) = if b.col_major() { (b.height() as isize, 1) } else { (1, b.width() as isize) };
+	dx as isize * h_offset + dy as isize * v_offset
+}
+
+/// Number of squares of the longest ray along `direction`.
+#[inline(always)]
+fn ray_len<B: Bitboard>(b: &B, (dx, dy): (i8, i8)) -> usize {
+	let along_x = if dx == 0 { usize::MAX } else { (b.width() as usize).div_ceil(dx.unsigned_abs() as usize) };
+	let along_y = if dy == 0 { usize::MAX } else { (b.height() as usize).div_ceil(dy.unsigned_abs() as usize) };
+	along_x.min(along_y)
+}
+
+/// Storage of `b` shifted by a signed offset, left when positive. Bits leaving the board
+/// are not cleared.
+#[inline(always)]
+fn shifted_storage<B: Bitboard>(b: &B, offset: isize) -> B {
+	let nb_squares = b.width() as usize * b.height() as usize;
+	if offset.unsigned_abs() >= nb_squares {
+		b.clone() ^ b.clone()
+	} else if offset >= 0 {
+		b.clone() << offset as usize
+	} else {
+		b.clone() >> offset.unsigned_abs()
+	}
+}
+
+macro_rules! impl_occluded_fill_runtime {
+	(@impl [$($g:tt)*] $t:ty) => {
+		impl<$($g)*> OccludedFill for $t {
+			#[inline]
+			fn step_targets(&self, (dx, dy): (i8, i8)) -> Self {
+				self.full_with_same_shape().shifted(dx as i16, dy as i16)
+			}
+		}
+	};
+	($($t:ty),*) => {
+		$(impl_occluded_fill_runtime!(@impl [] $t);)*
+	};
+}
+impl_occluded_fill_runtime!(SmallBitboard, MediumBitboard);
+impl_occluded_fill_runtime!(@impl [const WORDS: usize] LargeBitboardN<WORDS>);
+#[cfg(feature = "alloc")]
+impl_occluded_fill_runtime!(LargeBitboard, DynBitboard);
+
+/// Magic candidates tried before growing the slot of a square.
+#[cfg(feature = "alloc")]
+const MAGIC_TRIES: u32 = 10_000;
+/// Index bits a slot can get beyond the number of relevant squares before the search gives up.
+#[cfg(feature = "alloc")]
+const MAGIC_EXTRA_BITS: u8 = 4;
+
+/// Folds `bits & mask` into a single word, rotating word `i` by `rot * i`.
+#[inline(always)]
+fn fold<S: WordStorage>(bits: &S, mask: &S, rot: u8) -> u64 {
+	let mut key = 0u64;
+	for i in 0..mask.nb_words() {
+		key ^= (bits.word(i) & mask.word(i)).rotate_left(rot as u32 * i as u32);
+	}
+	key
+}
+
+#[cfg(feature = "alloc")]
+#[inline(always)]
+fn is_in_bounds<B: Bitboard>(b: &B, x: i16, y: i16) -> bool {
+	x >= 0 && y >= 0 && x < b.width() as i16 && y < b.height() as i16
+}
+
+#[cfg(feature = "alloc")]
+/// Deterministic xorshift generator used to search magics.
+struct MagicRng(u64);
+#[cfg(feature = "alloc")]
+impl MagicRng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+	/// Candidates with few bits set make better magics.
+	fn sparse(&mut self) -> u64 {
+		self.next() & self.next() & self.next()
+	}
+}
@@ -0,0 +1,310 @@
+//! Symmetries of the square (the dihedral group D4): flips, rotations and transposes.
+//!
+//! [`Symmetric`] is implemented by the square-topology `#[bitboard]` types and by the runtime
+//! bitboards. The transforms that exchange the width and the height return
+//! [`Symmetric::Transposed`]: `Self` for square macro boards and runtime boards, and a companion
+//! type generated by `#[bitboard]` (`<Name>Transposed`) for non-square macro boards.
+//!
+//! [`Canonical::canonical`] picks one representative among the symmetric images of a board, or
+//! of a tuple or array of layers, e.g. to deduplicate positions in a transposition table.
+
+use core::cmp::Ordering;
+
+use crate::{BitIter, Bitboard};
+use crate::runtime::{RuntimeBitboard, large_bitboard_n::LargeBitboardN, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard};
+#[cfg(feature = "alloc")]
+use crate::runtime::{dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard};
+
+/// One of the eight symmetries of the square.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+	Identity,
+	/// Quarter turn clockwise.
+	Rotate90,
+	Rotate180,
+	/// Quarter turn counter-clockwise.
+	Rotate270,
+	/// Mirror across the vertical axis: `x` becomes `WIDTH - 1 - x`.
+	FlipHorizontal,
+	/// Mirror across the horizontal axis: `y` becomes `HEIGHT - 1 - y`.
+	FlipVertical,
+	/// Mirror across the diagonal through `(0, 0)`: `(x, y)` becomes `(y, x)`.
+	Transpose,
+	/// Mirror across the other diagonal.
+	AntiTranspose,
+}
+
+impl Symmetry {
+	/// The eight symmetries, in the order of [`Symmetric::symmetries`].
+	pub const ALL: [Symmetry; 8] = [
+		Symmetry::Identity, Symmetry::Rotate90, Symmetry::Rotate180, Symmetry::Rotate270,
+		Symmetry::FlipHorizontal, Symmetry::FlipVertical, Symmetry::Transpose, Symmetry::AntiTranspose,
+	];
+
+	/// Returns `true` if the symmetry exchanges the width and the height.
+	#[inline]
+	pub const fn swaps_axes(self) -> bool {
+		matches!(self, Symmetry::Rotate90 | Symmetry::Rotate270 | Symmetry::Transpose | Symmetry::AntiTranspose)
+	}
+
+	/// The symmetry undoing `self`.
+	#[inline]
+	pub const fn inverse(self) -> Self {
+		match self {
+			Symmetry::Rotate90 => Symmetry::Rotate270,
+			Symmetry::Rotate270 => Symmetry::Rotate90,
+			s => s,
+		}
+	}
+
+	/// Where the square `(x, y)` of a `width` x `height` board goes.
+	#[inline]
+	pub const fn apply(self, width: u8, height: u8, x: u8, y: u8) -> (u8, u8) {
+		let (xr, yr) = (width - 1 - x, height - 1 - y);
+		match self {
+			Symmetry::Identity => (x, y),
+			Symmetry::Rotate90 => (y, xr),
+			Symmetry::Rotate180 => (xr, yr),
+			Symmetry::Rotate270 => (yr, x),
+			Symmetry::FlipHorizontal => (xr, y),
+			Symmetry::FlipVertical => (x, yr),
+			Symmetry::Transpose => (y, x),
+			Symmetry::AntiTranspose => (yr, xr),
+		}
+	}
+}
+
+/// Flips, rotations and transposes of a bitboard.
+pub trait Symmetric: Bitboard {
+	/// The board with the width and the height exchanged.
+	type Transposed: Symmetric<Transposed = Self>;
+
+	/// Mirror across the vertical axis.
+	fn flip_horizontal(&self) -> Self;
+	/// Mirror across the horizontal axis.
+	fn flip_vertical(&self) -> Self;
+	/// Mirror across the diagonal through `(0, 0)`.
+	fn transpose(&self) -> Self::Transposed;
+
+	/// Half turn.
+	#[inline]
+	fn rotate180(&self) -> Self {
+		self.flip_horizontal().flip_vertical()
+	}
+	/// Quarter turn clockwise.
+	#[inline]
+	fn rotate90(&self) -> Self::Transposed {
+		self.transpose().flip_vertical()
+	}
+	/// Quarter turn counter-clockwise.
+	#[inline]
+	fn rotate270(&self) -> Self::Transposed {
+		self.transpose().flip_horizontal()
+	}
+	/// Mirror across the diagonal through `(WIDTH - 1, 0)`.
+	#[inline]
+	fn anti_transpose(&self) -> Self::Transposed {
+		self.rotate180().transpose()
+	}
+
+	/// Returns the image of `self` by `symmetry`.
+	fn transformed(&self, symmetry: Symmetry) -> Self
+	where Self: Symmetric<Transposed = Self>
+	{
+		match symmetry {
+			Symmetry::Identity => self.clone(),
+			Symmetry::Rotate90 => self.rotate90(),
+			Symmetry::Rotate180 => self.rotate180(),
+			Symmetry::Rotate270 => self.rotate270(),
+			Symmetry::FlipHorizontal => self.flip_horizontal(),
+			Symmetry::FlipVertical => self.flip_vertical(),
+			Symmetry::Transpose => self.transpose(),
+			Symmetry::AntiTranspose => self.anti_transpose(),
+		}
+	}
+
+	/// Returns the image of `self` by `symmetry`, or `None` when the symmetry does not keep the shape
+	/// of the board (it exchanges the width and the height of a non-square board).
+	#[inline]
+	fn try_transformed(&self, symmetry: Symmetry) -> Option<Self> {
+		match symmetry {
+			Symmetry::Identity => Some(self.clone()),
+			Symmetry::Rotate180 => Some(self.rotate180()),
+			Symmetry::FlipHorizontal => Some(self.flip_horizontal()),
+			Symmetry::FlipVertical => Some(self.flip_vertical()),
+			_ => None,
+		}
+	}
+
+	/// Iterates over the eight images of `self`, in the order of [`Symmetry::ALL`].
+	///
+	/// Non-square macro boards only get their four same-shape images through
+	/// [`flip_horizontal`](Self::flip_horizontal), [`flip_vertical`](Self::flip_vertical) and
+	/// [`rotate180`](Self::rotate180); the other four are of type [`Self::Transposed`].
+	fn symmetries(&self) -> Symmetries<Self>
+	where Self: Symmetric<Transposed = Self>
+	{
+		Symmetries { board: self.clone(), next: 0 }
+	}
+}
+
+/// Iterator over the eight symmetric images of a bitboard, returned by [`Symmetric::symmetries`].
+pub struct Symmetries<B> {
+	board: B,
+	next: usize,
+}
+
+impl<B: Symmetric<Transposed = B>> Iterator for Symmetries<B> {
+	type Item = B;
+
+	fn next(&mut self) -> Option<B> {
+		let symmetry = *Symmetry::ALL.get(self.next)?;
+		self.next += 1;
+		Some(self.board.transformed(symmetry))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = Symmetry::ALL.len() - self.next;
+		(len, Some(len))
+	}
+}
+impl<B: Symmetric<Transposed = B>> ExactSizeIterator for Symmetries<B> {}
+
+/// Orders two bitboards of the same shape as the unsigned integers formed by their storage.
+///
+/// Only the highest differing bit is looked at, so this works the same for integer and word
+/// array storage.
+#[inline]
+pub fn cmp_storage<B: Bitboard>(a: &B, b: &B) -> Ordering {
+	match (a.clone() ^ b.clone()).last() {
+		None => Ordering::Equal,
+		Some(i) if a.get_at_index(i as usize) => Ordering::Greater,
+		Some(_) => Ordering::Less,
+	}
+}
+
+/// Canonical form under the symmetries of the board.
+///
+/// Implemented by every [`Symmetric`] bitboard, and by tuples and arrays of them for positions
+/// stored as several layers (e.g. black and white stones), all layers being transformed together.
+pub trait Canonical: Sized {
+	/// Image of `self` by `symmetry`, `None` when the symmetry does not keep the shape.
+	fn canonical_image(&self, symmetry: Symmetry) -> Option<Self>;
+	/// Order of the representatives: [`cmp_storage`], layer after layer.
+	fn canonical_cmp(&self, other: &Self) -> Ordering;
+
+	/// Returns the smallest image of `self` by the symmetries keeping the shape of the board (all
+	/// eight on a square board, four otherwise), and the symmetry giving it.
+	///
+	/// Symmetric positions have several such symmetries; the first one in [`Symmetry::ALL`] is
+	/// returned. A square `(x, y)` of `self` is at `symmetry.apply(w, h, x, y)` in the canonical
+	/// board, and a square of the canonical board maps back through `symmetry.inverse()`.
+	fn canonical(&self) -> (Self, Symmetry) {
+		let mut best = None::<(Self, Symmetry)>;
+		for symmetry in Symmetry::ALL {
+			if let Some(image) = self.canonical_image(symmetry)
+				&& best.as_ref().is_none_or(|(b, _)| image.canonical_cmp(b) == Ordering::Less)
+			{
+				best = Some((image, symmetry));
+			}
+		}
+		best.expect("the identity keeps the shape")
+	}
+}
+
+impl<B: Symmetric> Canonical for B {
+	#[inline]
+	fn canonical_image(&self, symmetry: Symmetry) -> Option<Self> {
+		self.try_transformed(symmetry)
+	}
+	#[inline]
+	fn canonical_cmp(&self, other: &Self) -> Ordering {
+		cmp_storage(self, other)
+	}
+}
+
+macro_rules! impl_canonical_tuple {
+	($($t:ident $i:tt),*) => {
+		impl<$($t: Canonical),*> Canonical for ($($t,)*) {
+			fn canonical_image(&self, symmetry: Symmetry) -> Option<Self> {
+				Some(($(self.$i.canonical_image(symmetry)?,)*))
+			}
+			fn canonical_cmp(&self, other: &Self) -> Ordering {
+				Ordering::Equal$(.then_with(|| self.$i.canonical_cmp(&other.$i)))*
+			}
+		}
+	};
+}
+impl_canonical_tuple!(A 0, B 1);
+impl_canonical_tuple!(A 0, B 1, C 2);
+impl_canonical_tuple!(A 0, B 1, C 2, D 3);
+
+impl<B: Canonical, const N: usize> Canonical for [B; N] {
+	fn canonical_image(&self, symmetry: Symmetry) -> Option<Self> {
+		let images: [Option<B>; N] = core::array::from_fn(|i| self[i].canonical_image(symmetry));
+		images.iter().all(Option::is_some).then(|| images.map(Option::unwrap))
+	}
+	fn canonical_cmp(&self, other: &Self) -> Ordering {
+		self.iter().zip(other).map(|(a, b)| a.canonical_cmp(b)).find(|o| o.is_ne()).unwrap_or(Ordering::Equal)
+	}
+}
+
+/// Image of a runtime bitboard by `symmetry`, square by square.
+fn transformed_runtime<B: RuntimeBitboard>(b: &B, symmetry: Symmetry) -> B {
+	let (w, h) = (b.width(), b.height());
+	let mut res = if symmetry.swaps_axes() { B::empty(h, w, b.col_major()) } else { b.empty_with_same_shape() };
+	for index in b.bits() {
+		let (x, y) = b.coords_from_index_with_same_shape(index as usize);
+		let (x, y) = symmetry.apply(w, h, x, y);
+		res.set(x, y);
+	}
+	res
+}
+
+macro_rules! impl_symmetric_runtime {
+	(@impl [$($g:tt)*] $t:ty) => {
+		impl<$($g)*> Symmetric for $t {
+			type Transposed = Self;
+
+			#[inline]
+			fn flip_horizontal(&self) -> Self {
+				transformed_runtime(self, Symmetry::FlipHorizontal)
+			}
+			#[inline]
+			fn flip_vertical(&self) -> Self {
+				transformed_runtime(self, Symmetry::FlipVertical)
+			}
+			#[inline]
+			fn transpose(&self) -> Self {
+				transformed_runtime(self, Symmetry::Transpose)
+			}
+			#[inline]
+			fn rotate180(&self) -> Self {
+				transformed_runtime(self, Symmetry::Rotate180)
+			}
+			#[inline]
+			fn rotate90(&self) -> Self {
+				transformed_runtime(self, Symmetry::Rotate90)
+			}
+			#[inline]
+			fn rotate270(&self) -> Self {
+				transformed_runtime(self, Symmetry::Rotate270)
+			}
+			#[inline]
+			fn anti_transpose(&self) -> Self {
+				transformed_runtime(self, Symmetry::AntiTranspose)
+			}
+			#[inline]
+			fn try_transformed(&self, symmetry: Symmetry) -> Option<Self> {
+				(!symmetry.swaps_axes() || self.width() == self.height()).then(|| transformed_runtime(self, symmetry))
+			}
+		}
+	};
+	($($t:ty),*) => {
+		$(impl_symmetric_runtime!(@impl [] $t);)*
+	};
+}
+impl_symmetric_runtime!(SmallBitboard, MediumBitboard);
+impl_symmetric_runtime!(@impl [const WORDS: usize] LargeBitboardN<WORDS>);
+#[cfg(feature = "alloc")]
+impl_symmetric_runtime!(LargeBitboard, DynBitboard);
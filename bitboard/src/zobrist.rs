@@ -0,0 +1,107 @@
+//! Zobrist hashing of positions made of bitboard layers.
+//!
+//! A position is `LAYERS` bitboards of the same shape (e.g. one per piece type and color). Each
+//! `(layer, square)` has a random 64-bit key, and the hash of a position is the xor of the keys of
+//! its set squares, so that it can be updated incrementally.
+//!
+//! Keys only depend on the seed, the layer and the square index, through [`zobrist_key`]: they can
+//! be generated at run time with `ZobristKeys::with_seed` (with the `alloc` feature), or at compile time with the
+//! `zobrist_table` const fn of the `#[bitboard]` types and [`ZobristKeys::from_static`]:
+//!
+//! ```ignore
+//! static KEYS: [[u64; 2]; Go19::NB_SQUARES] = Go19::zobrist_table(DEFAULT_SEED);
+//! const ZOBRIST: ZobristKeys<Go19, 2> = ZobristKeys::from_static(&KEYS);
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+use core::marker::PhantomData;
+
+use crate::{BitIter, Bitboard};
+
+/// Seed of [`ZobristKeys::new`].
+pub const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+#[inline(always)]
+const fn splitmix64(mut z: u64) -> u64 {
+	z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	z ^ (z >> 31)
+}
+
+/// Key of the square at `index` in `layer`, for `seed`.
+#[inline]
+pub const fn zobrist_key(seed: u64, layer: usize, index: usize) -> u64 {
+	splitmix64(splitmix64(seed ^ layer as u64) ^ index as u64)
+}
+
+/// Zobrist keys for positions of `LAYERS` bitboards of type `B`.
+#[derive(Clone, Debug)]
+pub struct ZobristKeys<B, const LAYERS: usize> {
+	/// Keys of the layers, by square index.
+	#[cfg(feature = "alloc")]
+	keys: Cow<'static, [[u64; LAYERS]]>,
+	#[cfg(not(feature = "alloc"))]
+	keys: &'static [[u64; LAYERS]],
+	_board: PhantomData<fn() -> B>,
+}
+
+impl<B: Bitboard, const LAYERS: usize> ZobristKeys<B, LAYERS> {
+	/// Keys for boards shaped like `board`, from [`DEFAULT_SEED`].
+	#[cfg(feature = "alloc")]
+	pub fn new(board: &B) -> Self {
+		Self::with_seed(board, DEFAULT_SEED)
+	}
+
+	/// Keys for boards shaped like `board`, from `seed`.
+	#[cfg(feature = "alloc")]
+	pub fn with_seed(board: &B, seed: u64) -> Self {
+		let nb_squares = board.width() as usize * board.height() as usize;
+		let keys = (0..nb_squares).map(|index| core::array::from_fn(|layer| zobrist_key(seed, layer, index))).collect();
+		Self { keys: Cow::Owned(keys), _board: PhantomData }
+	}
+
+	/// Keys from a table indexed by square then layer, e.g. built at compile time by `zobrist_table`.
+	pub const fn from_static(keys: &'static [[u64; LAYERS]]) -> Self {
+		#[cfg(feature = "alloc")]
+		let keys = Cow::Borrowed(keys);
+		Self { keys, _board: PhantomData }
+	}
+
+	/// Number of squares covered by the keys.
+	#[inline(always)]
+	pub fn nb_squares(&self) -> usize {
+		self.keys.len()
+	}
+
+	/// Key of the square at `index` in `layer`.
+	#[inline(always)]
+	pub fn key(&self, layer: usize, index: usize) -> u64 {
+		self.keys[index][layer]
+	}
+
+	/// Hash of the squares of `b` in `layer`.
+	#[inline]
+	pub fn layer_hash(&self, layer: usize, b: &B) -> u64 {
+		b.bits().fold(0, |hash, index| hash ^ self.key(layer, index as usize))
+	}
+
+	/// Hash of a position, computed from scratch.
+	pub fn hash_of(&self, layers: &[B; LAYERS]) -> u64 {
+		layers.iter().enumerate().fold(0, |hash, (layer, b)| hash ^ self.layer_hash(layer, b))
+	}
+
+	/// Updates `hash` for the square at `index` being set or cleared in `layer`.
+	#[inline(always)]
+	pub fn toggle(&self, hash: &mut u64, layer: usize, index: usize) {
+		*hash ^= self.key(layer, index);
+	}
+
+	/// Value to xor into the hash of `old` to get the hash of `new`, from the squares that changed.
+	pub fn hash_delta(&self, old: &[B; LAYERS], new: &[B; LAYERS]) -> u64 {
+		old.iter().zip(new).enumerate().fold(0, |hash, (layer, (old, new))| {
+			hash ^ self.layer_hash(layer, &(old.clone() ^ new.clone()))
+		})
+	}
+}
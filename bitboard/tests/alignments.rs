@@ -1,3 +1,4 @@
+#![allow(clippy::identity_op)]
 #[cfg(test)]
 mod tests {
 	use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
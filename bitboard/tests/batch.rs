@@ -0,0 +1,151 @@
+use bitboard::{Bitboard, batch::BoardBatch, runtime::{RuntimeBitboard, medium_bitboard::MediumBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board_where};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=11,height=9)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard11x9;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+/// Boards from empty to dense, so that alignments are found on some of them only.
+fn random_boards<B: Bitboard>(empty: B, n: usize, rng: &mut Rng) -> Vec<B> {
+	(0..n).map(|i| random_board_where(empty.clone(), rng, |r| r % 64 < i as u64)).collect()
+}
+
+/// Runs `batch_op` on the batch and `op` on each board, and compares the squares.
+///
+/// Bits past the last square are ignored: some shifts of the `#[bitboard]` types leave them set.
+#[track_caller]
+fn check<B: Bitboard + std::fmt::Debug>(boards: &[B], batch_op: impl Fn(&BoardBatch<B>) -> BoardBatch<B>, op: impl Fn(&B) -> B) {
+	let res = batch_op(&BoardBatch::from_boards(boards)).to_boards();
+	for (board, res) in boards.iter().zip(res) {
+		let expected = op(board);
+		let squares = |b: &B| (0..b.width()).flat_map(|x| (0..b.height()).map(move |y| (x, y))).filter(|&(x, y)| b.get(x, y)).collect::<Vec<_>>();
+		assert_eq!(squares(&res), squares(&expected), "{board:?}\n{res:?}\n{expected:?}");
+	}
+}
+
+/// `a & b`, `a | b`, `a ^ b` and `!a` within the board.
+fn logical_ops<B: Bitboard>(a: &B, b: &B) -> [B; 4] {
+	[a.clone() & b.clone(), a.clone() | b.clone(), a.clone() ^ b.clone(), a.flipped()]
+}
+
+/// Generated for the `#[bitboard]` types, which do not share a trait for these.
+macro_rules! check_against_macro {
+	($t:ty, $rng:expr) => {{
+		let boards = random_boards(<$t>::EMPTY, 64, $rng);
+		let other = random_boards(<$t>::EMPTY, 64, $rng);
+		let batch = BoardBatch::<$t>::from_boards(&boards);
+		assert_eq!(batch.to_boards(), boards);
+		for (lane, board) in boards.iter().enumerate() {
+			assert_eq!(&batch.board(lane), board);
+		}
+		let counts = batch.count();
+		assert_eq!(counts.to_vec(), boards.iter().map(|b| b.count()).collect::<Vec<_>>());
+		assert_eq!(batch.any(), boards.iter().enumerate().fold(0, |acc, (i, b)| acc | (b.any() as u64) << i));
+
+		let other_batch = BoardBatch::from_boards(&other);
+		let res = (batch.clone() & &other_batch).to_boards();
+		let res_or = (batch.clone() | other_batch.clone()).to_boards();
+		let res_xor = (batch.clone() ^ &other_batch).to_boards();
+		let res_not = (!batch.clone()).to_boards();
+		for i in 0..64 {
+			assert_eq!([&res[i], &res_or[i], &res_xor[i], &res_not[i]], logical_ops(&boards[i], &other[i]).each_ref());
+		}
+
+		check(&boards, |b| b.shifted_n(), |b| b.shifted_n());
+		check(&boards, |b| b.shifted_s(), |b| b.shifted_s());
+		check(&boards, |b| b.shifted_e(), |b| b.shifted_e());
+		check(&boards, |b| b.shifted_w(), |b| b.shifted_w());
+		check(&boards, |b| b.shifted_ne(), |b| b.shifted_ne());
+		check(&boards, |b| b.shifted_nw(), |b| b.shifted_nw());
+		check(&boards, |b| b.shifted_se(), |b| b.shifted_se());
+		check(&boards, |b| b.shifted_sw(), |b| b.shifted_sw());
+		check(&boards, |b| b.shifted(3, -2), |b| b.shifted(3, -2));
+		check(&boards, |b| b.dilated(), |b| b.dilated());
+		check(&boards, |b| b.eroded(), |b| b.eroded());
+		check(&boards, |b| b.neighbors_of_any(), |b| b.neighbors_of_any());
+		for mask in [0b1, 0b11, 0b101, 0b1011] {
+			check(&boards, |b| b.detect_pattern_h(mask), |b| b.detect_pattern_h(mask));
+			check(&boards, |b| b.detect_pattern_v(mask), |b| b.detect_pattern_v(mask));
+			check(&boards, |b| b.detect_pattern_diag_inc(mask), |b| b.detect_pattern_diag_inc(mask));
+			check(&boards, |b| b.detect_pattern_diag_dec(mask), |b| b.detect_pattern_diag_dec(mask));
+		}
+
+		let (has3, has5) = (batch.has_aligned::<3>(), batch.has_aligned::<5>());
+		let (count2, count4) = (batch.count_aligned::<2>(), batch.count_aligned::<4>());
+		for (i, b) in boards.iter().enumerate() {
+			assert_eq!(has3 >> i & 1 != 0, b.has_aligned::<3>(), "{b:?}");
+			assert_eq!(has5 >> i & 1 != 0, b.has_aligned::<5>(), "{b:?}");
+			assert_eq!(count2[i], b.count_aligned::<2>(), "{b:?}");
+			assert_eq!(count4[i], b.count_aligned::<4>(), "{b:?}");
+		}
+		assert_ne!(has5, 0);
+		assert_ne!(has5, u64::MAX);
+	}};
+}
+
+#[test]
+fn test_against_macro_boards() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	check_against_macro!(Chess, &mut rng);
+	check_against_macro!(Bitboard11x9, &mut rng);
+	check_against_macro!(Go19, &mut rng);
+}
+
+#[test]
+fn test_against_runtime_boards() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	let boards = random_boards(MediumBitboard::empty(10, 12, true), 40, &mut rng);
+	check(&boards, |b| b.dilated(), |b| b.dilated());
+	check(&boards, |b| b.detect_pattern_diag_dec(0b111), |b| b.detect_pattern_diag_dec(0b111));
+	let batch = BoardBatch::<_>::from_boards(&boards);
+	assert_eq!(batch.to_boards()[..40], boards[..]);
+	assert!(batch.to_boards()[40..].iter().all(|b| b.is_empty()));
+	let counts = batch.count_aligned::<3>();
+	for (i, b) in boards.iter().enumerate() {
+		assert_eq!(counts[i], b.count_aligned::<3>());
+	}
+}
+
+#[test]
+fn test_lanes() {
+	let mut batch = BoardBatch::<Chess, 8>::new(&Chess::EMPTY);
+	assert_eq!(BoardBatch::<Chess, 8>::LANES, 0xFF);
+	batch.assign(2, 3, 0b1010_0110);
+	assert_eq!(batch.get(2, 3), 0b1010_0110);
+	assert_eq!(batch.board(1), Chess::from_coords(2, 3));
+	assert_eq!(batch.board(0), Chess::EMPTY);
+
+	// The complement stays in the 8 lanes
+	let full = !batch.empty_with_same_shape();
+	assert_eq!(full, batch.full_with_same_shape());
+	assert_eq!(full.count(), [64; 8]);
+	assert_eq!(full.get(7, 7), 0xFF);
+
+	batch.set_board(1, &Chess::FULL);
+	assert_eq!(batch.board(1), Chess::FULL);
+	assert_eq!(batch.count(), [0, 64, 1, 0, 0, 1, 0, 1]);
+	batch.set_board(1, &Chess::EMPTY);
+	assert_eq!(batch.get(2, 3), 0b1010_0100);
+
+	let boards = BoardBatch::<Go19>::from_boards(&[Go19::FULL; 64]);
+	assert_eq!(boards.count(), [361; 64]);
+	assert_eq!(boards.has_aligned::<19>(), u64::MAX);
+	assert_eq!(boards.count_aligned::<19>(), [19 + 19 + 1 + 1; 64]);
+}
+
+#[test]
+#[should_panic]
+fn test_too_many_boards() {
+	BoardBatch::<Chess, 8>::from_boards(&[Chess::EMPTY; 9]);
+}
@@ -0,0 +1,161 @@
+use bitboard::{BitIter, Bitboard, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=3,height=2)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Tiny;
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=11,height=11)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard11x11;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+/// Both iterators against a scan of every index, forwards, backwards and alternating ends.
+fn check<B: Bitboard + std::fmt::Debug>(b: B) {
+	let nb_squares = b.width() as usize * b.height() as usize;
+	let nb_indexes = nb_squares.next_multiple_of(64).max(128);
+	let expected: Vec<u32> = (0..nb_indexes).filter(|&i| i < nb_squares && b.get_at_index(i)).map(|i| i as u32).collect();
+
+	assert_eq!(b.bits().len(), expected.len(), "{b:?}");
+	assert_eq!(b.bits().collect::<Vec<_>>(), expected, "{b:?}");
+	assert_eq!(b.bits().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+	assert_eq!(b.clone().iter_bits().collect::<Vec<_>>(), expected);
+	assert_eq!(b.clone().iter_bits().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+
+	let (mut bits, mut owned) = (b.bits(), b.clone().iter_bits());
+	let (mut front, mut back) = (0, expected.len());
+	for step in 0..expected.len() {
+		assert_eq!(bits.len(), back - front);
+		let i = if step % 3 == 0 { back -= 1; back } else { front += 1; front - 1 };
+		let (a, o) = if step % 3 == 0 { (bits.next_back(), owned.next_back()) } else { (bits.next(), owned.next()) };
+		assert_eq!(a, Some(expected[i]), "{b:?}");
+		assert_eq!(o, Some(expected[i]));
+	}
+	assert_eq!((bits.next(), bits.next_back(), bits.len()), (None, None, 0));
+	assert_eq!((owned.next_back(), owned.next()), (None, None));
+}
+
+#[test]
+fn test_static() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	check(Go19::EMPTY);
+	check(Go19::FULL);
+	check(Chess::FULL);
+	for sparsity in [1, 2, 7, 40] {
+		check(random_board(Tiny::EMPTY, sparsity, &mut rng));
+		check(random_board(Chess::EMPTY, sparsity, &mut rng));
+		check(random_board(Bitboard11x11::EMPTY, sparsity, &mut rng));
+		check(random_board(Go19::EMPTY, sparsity, &mut rng));
+	}
+}
+
+#[test]
+fn test_runtime() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	check(LargeBitboard::empty(255, 255, false));
+	check(LargeBitboard::full(20, 20, true));
+	for sparsity in [1, 2, 7, 40] {
+		check(random_board(SmallBitboard::empty(8, 7, false), sparsity, &mut rng));
+		check(random_board(MediumBitboard::empty(11, 11, true), sparsity, &mut rng));
+		check(random_board(LargeBitboard::empty(19, 19, false), sparsity, &mut rng));
+		check(random_board(DynBitboard::empty(5, 5, false), sparsity, &mut rng));
+		check(random_board(DynBitboard::empty(30, 30, true), sparsity, &mut rng));
+	}
+}
+
+/// A few stones far apart on the largest board, where most words are empty.
+#[test]
+fn test_sparse_large() {
+	let mut b = LargeBitboard::empty(255, 255, false);
+	for (x, y) in [(0, 0), (254, 254), (128, 3), (3, 128), (200, 100)] {
+		b.set(x, y);
+	}
+	check(b.clone());
+	let indexes: Vec<u32> = b.bits().collect();
+	assert_eq!(indexes, [0, 3 * 255 + 128, 100 * 255 + 200, 128 * 255 + 3, 255 * 255 - 1]);
+	assert_eq!(b.bits().next_back(), Some(255 * 255 - 1));
+	assert_eq!(b.bits().rev().nth(4), Some(0));
+}
+
+/// A board whose `count()` is wrong, to check that iteration only relies on the storage.
+#[derive(Clone, PartialEq, Debug)]
+struct Miscounted(LargeBitboard);
+
+macro_rules! delegate_ops {
+	($($op:ident $f:ident $op_assign:ident $f_assign:ident $rhs:tt),*) => {$(
+		impl core::ops::$op<$rhs> for Miscounted {
+			type Output = Self;
+			fn $f(self, rhs: $rhs) -> Self { Miscounted(core::ops::$op::$f(self.0, delegate_ops!(@unwrap rhs $rhs))) }
+		}
+		impl core::ops::$op_assign<$rhs> for Miscounted {
+			fn $f_assign(&mut self, rhs: $rhs) { core::ops::$op_assign::$f_assign(&mut self.0, delegate_ops!(@unwrap rhs $rhs)) }
+		}
+	)*};
+	(@unwrap $rhs:ident Self) => { $rhs.0 };
+	(@unwrap $rhs:ident usize) => { $rhs };
+}
+
+delegate_ops!(BitAnd bitand BitAndAssign bitand_assign Self, BitOr bitor BitOrAssign bitor_assign Self, BitXor bitxor BitXorAssign bitxor_assign Self,
+	Shl shl ShlAssign shl_assign usize, Shr shr ShrAssign shr_assign usize);
+
+impl core::ops::Not for Miscounted {
+	type Output = Self;
+	fn not(self) -> Self { Miscounted(!self.0) }
+}
+
+impl Bitboard for Miscounted {
+	type Storage = Box<[u64]>;
+	fn is_empty(&self) -> bool { self.0.is_empty() }
+	fn count(&self) -> u32 { self.0.count() + 1000 }
+	fn intersects(&self, other: &Self) -> bool { self.0.intersects(&other.0) }
+	fn width(&self) -> u8 { self.0.width() }
+	fn height(&self) -> u8 { self.0.height() }
+	fn col_major(&self) -> bool { self.0.col_major() }
+	fn storage(&self) -> &Self::Storage { self.0.storage() }
+	fn storage_mut(&mut self) -> &mut Self::Storage { self.0.storage_mut() }
+	fn get_at_index(&self, index: usize) -> bool { self.0.get_at_index(index) }
+	fn assign_at_index(&mut self, index: usize, val: bool) { self.0.assign_at_index(index, val) }
+	fn set_at_index(&mut self, idx: usize) { self.0.set_at_index(idx) }
+	fn reset_at_index(&mut self, idx: usize) { self.0.reset_at_index(idx) }
+	fn toggle_at_index(&mut self, idx: usize) { self.0.toggle_at_index(idx) }
+	fn get(&self, x: u8, y: u8) -> bool { self.0.get(x, y) }
+	fn assign(&mut self, x: u8, y: u8, val: bool) { self.0.assign(x, y, val) }
+	fn set(&mut self, x: u8, y: u8) { self.0.set(x, y) }
+	fn reset(&mut self, x: u8, y: u8) { self.0.reset(x, y) }
+	fn flipped(&self) -> Self { Miscounted(self.0.flipped()) }
+	#[allow(deprecated)]
+	fn lsb(&self) -> u32 { self.0.lsb() }
+	#[allow(deprecated)]
+	fn msb(&self) -> u32 { self.0.msb() }
+	fn pop_lsb(&mut self) -> u32 { self.0.pop_lsb() }
+	fn pext(&self, mask: &Self) -> Self::Storage { self.0.pext(&mask.0) }
+	fn pdep(&self, compressed: Self::Storage) -> Self { Miscounted(self.0.pdep(compressed)) }
+	fn extract_row(&self, y: u8) -> Self::Storage { self.0.extract_row(y) }
+	fn extract_col(&self, x: u8) -> Self::Storage { self.0.extract_col(x) }
+	fn insert_row(&mut self, y: u8, row_bits: Self::Storage) { self.0.insert_row(y, row_bits) }
+	fn insert_col(&mut self, x: u8, col_bits: Self::Storage) { self.0.insert_col(x, col_bits) }
+}
+
+#[test]
+fn test_wrong_count() {
+	let mut b = Miscounted(LargeBitboard::empty(10, 10, false));
+	for index in [3, 64, 99] {
+		b.set_at_index(index);
+	}
+	assert_eq!(b.bits().collect::<Vec<_>>(), [3, 64, 99]);
+	assert_eq!(b.bits().rev().collect::<Vec<_>>(), [99, 64, 3]);
+	assert_eq!(b.bits().len(), 3);
+	let mut owned = b.clone().iter_bits();
+	assert_eq!((owned.next(), owned.next_back(), owned.next_back(), owned.next()), (Some(3), Some(99), Some(64), None));
+	assert_eq!(Miscounted(LargeBitboard::empty(10, 10, false)).bits().next_back(), None);
+}
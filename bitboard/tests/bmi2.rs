@@ -0,0 +1,156 @@
+use bitboard::{Bitboard, bmi2::{self, Bmi2Policy}, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard}, sliding::SlidingTable};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+use std::sync::Mutex;
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=11,height=11)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard11x11;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+impl Rng {
+	/// Masks of every density, with runs and isolated bits.
+	fn mask(&mut self) -> u64 {
+		match self.next() % 4 {
+			0 => self.next(),
+			1 => self.next() & self.next() & self.next(),
+			2 => self.next() | self.next(),
+			_ => (u64::MAX >> (self.next() % 64)) << (self.next() % 64),
+		}
+	}
+}
+
+/// Bit by bit, on any number of words.
+fn pext_reference(value: &[u64], mask: &[u64]) -> Vec<u64> {
+	let mut out = vec![0; mask.len()];
+	for (pos, i) in (0..mask.len() * 64).filter(|i| mask[i / 64] >> (i % 64) & 1 != 0).enumerate() {
+		out[pos / 64] |= (value[i / 64] >> (i % 64) & 1) << (pos % 64);
+	}
+	out
+}
+
+fn pdep_reference(value: &[u64], mask: &[u64]) -> Vec<u64> {
+	let mut out = vec![0; mask.len()];
+	for (pos, i) in (0..mask.len() * 64).filter(|i| mask[i / 64] >> (i % 64) & 1 != 0).enumerate() {
+		out[i / 64] |= (value[pos / 64] >> (pos % 64) & 1) << (i % 64);
+	}
+	out
+}
+
+const POLICIES: [Bmi2Policy; 3] = [Bmi2Policy::Auto, Bmi2Policy::Hardware, Bmi2Policy::Portable];
+
+/// Held by the tests changing the policy, which is global.
+static POLICY_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_words() {
+	let _lock = POLICY_LOCK.lock().unwrap();
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for policy in POLICIES {
+		bmi2::set_policy(policy);
+		assert_eq!(bmi2::policy(), policy);
+		if policy == Bmi2Policy::Portable {
+			assert!(!bmi2::uses_hardware());
+		}
+		for _ in 0..2000 {
+			let (value, mask) = (rng.next(), rng.mask());
+			let expected = pext_reference(&[value], &[mask])[0];
+			assert_eq!(bmi2::portable::pext_u64(value, mask), expected, "{value:#x} {mask:#x}");
+			assert_eq!(bmi2::pext_u64(value, mask), expected);
+			let expected = pdep_reference(&[value], &[mask])[0];
+			assert_eq!(bmi2::portable::pdep_u64(value, mask), expected, "{value:#x} {mask:#x}");
+			assert_eq!(bmi2::pdep_u64(value, mask), expected);
+
+			let (value, mask) = ([rng.next(), rng.next()], [rng.mask(), rng.mask()]);
+			let (value128, mask128) = (value[0] as u128 | (value[1] as u128) << 64, mask[0] as u128 | (mask[1] as u128) << 64);
+			let words = |w: Vec<u64>| w[0] as u128 | (w[1] as u128) << 64;
+			assert_eq!(bmi2::pext_u128(value128, mask128), words(pext_reference(&value, &mask)));
+			assert_eq!(bmi2::pdep_u128(value128, mask128), words(pdep_reference(&value, &mask)));
+		}
+		for len in 0..8 {
+			let value: Vec<u64> = (0..len).map(|_| rng.next()).collect();
+			let mask: Vec<u64> = (0..len).map(|_| rng.mask()).collect();
+			let mut out = vec![u64::MAX; len];
+			bmi2::pext_words(&value, &mask, &mut out);
+			assert_eq!(out, pext_reference(&value, &mask));
+			bmi2::pdep_words(&value, &mask, &mut out);
+			assert_eq!(out, pdep_reference(&value, &mask));
+		}
+	}
+	bmi2::set_policy(Bmi2Policy::Auto);
+}
+
+/// Round trip through every storage, including the arrays beyond 64 extracted bits.
+fn check_round_trip<B: Bitboard + std::fmt::Debug>(b: B, mask: B) {
+	let extracted = b.pext(&mask);
+	assert_eq!(mask.pdep(extracted), b.clone() & mask.clone(), "{b:?} {mask:?}");
+}
+
+#[test]
+fn test_round_trip() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	check_round_trip(Go19::FULL, Go19::FULL);
+	for _ in 0..50 {
+		check_round_trip(random_board(Chess::EMPTY, 2, &mut rng), random_board(Chess::EMPTY, 2, &mut rng));
+		check_round_trip(random_board(Bitboard11x11::EMPTY, 2, &mut rng), random_board(Bitboard11x11::EMPTY, 2, &mut rng));
+		check_round_trip(random_board(MediumBitboard::empty(11, 11, false), 2, &mut rng), random_board(MediumBitboard::empty(11, 11, false), 2, &mut rng));
+
+		let (a, m) = (random_board(Go19::EMPTY, 2, &mut rng), random_board(Go19::EMPTY, 2, &mut rng));
+		assert_eq!(a.pext(&m).to_vec(), pext_reference(&a.storage(), &m.storage()));
+		check_round_trip(a, m);
+
+		let (a, m) = (random_board(LargeBitboard::empty(19, 19, true), 2, &mut rng), random_board(LargeBitboard::empty(19, 19, true), 2, &mut rng));
+		let expected = pext_reference(a.storage(), m.storage());
+		assert_eq!(a.pext(&m)[..], expected[..(m.count() as usize).div_ceil(64)]);
+		check_round_trip(a, m);
+	}
+}
+
+/// A table built with PEXT and one built with magics give the same attacks.
+#[test]
+fn test_sliding_table_policies() {
+	let _lock = POLICY_LOCK.lock().unwrap();
+	bmi2::set_policy(Bmi2Policy::Portable);
+	let magic = SlidingTable::rook(&Chess::EMPTY);
+	bmi2::set_policy(Bmi2Policy::Hardware);
+	let pext = SlidingTable::rook(&Chess::EMPTY);
+	bmi2::set_policy(Bmi2Policy::Auto);
+	let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+	for _ in 0..200 {
+		let occupancy = Chess::from_storage(rng.next() & rng.next());
+		for square in 0..Chess::NB_SQUARES {
+			assert_eq!(magic.attacks(square, &occupancy), pext.attacks(square, &occupancy));
+		}
+	}
+}
+
+/// `MediumBitboard::pdep` deposits the compressed bits into the mask, not the mask into them.
+#[test]
+fn test_medium_pdep_fallback() {
+	let _lock = POLICY_LOCK.lock().unwrap();
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for policy in POLICIES {
+		bmi2::set_policy(policy);
+		for _ in 0..50 {
+			let mask = MediumBitboard::new(11, 11, false, (rng.mask() as u128 | (rng.mask() as u128) << 64) & ((1 << 121) - 1));
+			let compressed = rng.next() as u128 & ((1 << mask.count()) - 1);
+			let expected = pdep_reference(&[compressed as u64, (compressed >> 64) as u64], &[*mask.storage() as u64, (*mask.storage() >> 64) as u64]);
+			let res = *mask.pdep(compressed).storage();
+			assert_eq!([res as u64, (res >> 64) as u64], expected[..], "{policy:?}");
+		}
+	}
+	bmi2::set_policy(Bmi2Policy::Auto);
+	// A one-bit mask takes the lowest compressed bit, whatever the other bits
+	let mask = MediumBitboard::new(11, 11, false, 1 << 100);
+	assert_eq!(*mask.pdep(0b1011).storage(), 1 << 100);
+	assert_eq!(*mask.pdep(0b1010).storage(), 0);
+}
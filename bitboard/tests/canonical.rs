@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+
+use bitboard::{runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, small_bitboard::SmallBitboard}, symmetry::{Canonical, Symmetric, Symmetry, cmp_storage}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=3,height=3)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct TicTacToe;
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=7,height=6)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct ConnectFour;
+
+#[bitboard(width=19,height=19, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+/// Checks that all the images of a board share its canonical form, which is the smallest of them.
+fn check_canonical<B: Symmetric + std::fmt::Display>(b: &B) {
+	let square = b.width() == b.height();
+	let images: Vec<_> = Symmetry::ALL.iter().filter_map(|&s| b.try_transformed(s).map(|image| (image, s))).collect();
+	assert_eq!(images.len(), if square { 8 } else { 4 });
+
+	let (canonical, symmetry) = b.canonical();
+	assert!(b.try_transformed(symmetry).unwrap() == canonical);
+	for (image, s) in &images {
+		assert!(cmp_storage(&canonical, image).is_le(), "{s:?}\n{b}");
+		assert!(image.canonical().0 == canonical, "{s:?}\n{b}");
+	}
+	// Squares map back to the original board through the inverse
+	for y in 0..b.height() {
+		for x in 0..b.width() {
+			let (cx, cy) = symmetry.apply(b.width(), b.height(), x, y);
+			assert_eq!(canonical.get(cx, cy), b.get(x, y));
+			assert_eq!(symmetry.inverse().apply(b.width(), b.height(), cx, cy), (x, y));
+		}
+	}
+}
+
+#[test]
+fn test_canonical() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for _ in 0..32 {
+		let b = random_board(TicTacToe::EMPTY, 2, &mut rng);
+		check_canonical(&b);
+		let b = random_board(Bitboard8x8::EMPTY, 5, &mut rng);
+		check_canonical(&b);
+		// Integer storage orders as the integers
+		let min = b.symmetries().map(|image| image.storage()).min().unwrap();
+		assert_eq!(b.canonical().0.storage(), min);
+		let b = random_board(ConnectFour::EMPTY, 3, &mut rng);
+		check_canonical(&b);
+		let b = random_board(Go19::EMPTY, 9, &mut rng);
+		check_canonical(&b);
+		for col_major in [false, true] {
+			let b = random_board(SmallBitboard::empty(6, 6, col_major), 2, &mut rng);
+			check_canonical(&b);
+			let b = random_board(LargeBitboard::empty(13, 11, col_major), 4, &mut rng);
+			check_canonical(&b);
+		}
+	}
+
+	// The empty board and symmetric boards keep the identity
+	assert_eq!(Go19::EMPTY.canonical(), (Go19::EMPTY, Symmetry::Identity));
+	assert_eq!(TicTacToe::CORNERS.canonical(), (TicTacToe::CORNERS, Symmetry::Identity));
+}
+
+#[test]
+fn test_canonical_layers() {
+	// Black and white stones are transformed together
+	let black = Go19::from_coords(3, 15);
+	let white = Go19::from_coords(15, 15) | Go19::from_coords(3, 3);
+	let ((b, w), symmetry) = (black.clone(), white.clone()).canonical();
+	assert_eq!(b, Go19::from_coords(3, 3));
+	assert_eq!(w, Go19::from_coords(15, 3) | Go19::from_coords(3, 15));
+	assert_eq!(symmetry.apply(19, 19, 3, 15), (3, 3));
+
+	let [b, w] = [black, white.clone()].canonical().0;
+	assert_eq!((b, w), (Go19::from_coords(3, 3), Go19::from_coords(15, 3) | Go19::from_coords(3, 15)));
+
+	// Layers are compared in order: the second one breaks the tie of the first one
+	let ((b, w), _) = (Go19::EMPTY, white).canonical();
+	assert_eq!(b, Go19::EMPTY);
+	assert_eq!(w, Go19::from_coords(15, 3) | Go19::from_coords(3, 15));
+}
+
+/// Number of distinct tic-tac-toe positions up to symmetry, by number of moves, play stopping at a win.
+fn count_tic_tac_toe() -> Vec<usize> {
+	let mut counts = vec![1];
+	let mut positions: HashSet<(u16, u16)> = HashSet::from([(0, 0)]);
+	for ply in 0..9 {
+		let mut next = HashSet::new();
+		for &(x, o) in &positions {
+			let (x, o) = (TicTacToe::from_storage(x), TicTacToe::from_storage(o));
+			if x.has_aligned::<3>() || o.has_aligned::<3>() {
+				continue;
+			}
+			let empty = TicTacToe::FULL & !(x | o);
+			for i in 0..TicTacToe::NB_SQUARES {
+				if empty.get_at_index(i) {
+					let layers = if ply % 2 == 0 { (x | TicTacToe::from_index(i), o) } else { (x, o | TicTacToe::from_index(i)) };
+					let (x, o) = layers.canonical().0;
+					next.insert((x.storage(), o.storage()));
+				}
+			}
+		}
+		counts.push(next.len());
+		positions = next;
+	}
+	counts
+}
+
+#[test]
+fn test_tic_tac_toe() {
+	let counts = count_tic_tac_toe();
+	assert_eq!(counts, [1, 3, 12, 38, 108, 174, 204, 153, 57, 15]);
+	assert_eq!(counts.iter().sum::<usize>(), 765);
+}
@@ -0,0 +1,33 @@
+//! Helpers shared by the tests, and by the benches through `#[path]`.
+#![allow(dead_code)]
+
+use bitboard::Bitboard;
+
+/// Xorshift generator, for reproducible random boards.
+pub struct Rng(pub u64);
+
+impl Rng {
+	pub fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+}
+
+/// Sets about one square in `one_in` of `b`.
+pub fn random_board<B: Bitboard>(b: B, one_in: u64, rng: &mut Rng) -> B {
+	random_board_where(b, rng, |r| r.is_multiple_of(one_in))
+}
+
+/// Sets the squares of `b` for which `set` holds on a new random number.
+pub fn random_board_where<B: Bitboard>(mut b: B, rng: &mut Rng, mut set: impl FnMut(u64) -> bool) -> B {
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			if set(rng.next()) {
+				b.set(x, y);
+			}
+		}
+	}
+	b
+}
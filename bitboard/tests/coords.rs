@@ -0,0 +1,112 @@
+use bitboard::{BitIter, Bitboard, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=5,height=3,col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard5x3;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+const STONES: [(u8, u8); 4] = [(0, 0), (4, 2), (1, 2), (3, 0)];
+
+/// Coordinates, rows and columns against `get`, `extract_row` and `extract_col`.
+fn check<B: Bitboard + std::fmt::Debug>(b: &B)
+where
+	B::Storage: PartialEq + std::fmt::Debug,
+{
+	let mut expected: Vec<(u8, u8)> = (0..b.height()).flat_map(|y| (0..b.width()).map(move |x| (x, y))).filter(|&(x, y)| b.get(x, y)).collect();
+	if b.col_major() {
+		expected.sort();
+	}
+	assert_eq!(b.iter_coords().collect::<Vec<_>>(), expected, "{b:?}");
+	assert_eq!(b.iter_coords().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+	assert_eq!(b.iter_coords().len(), expected.len());
+
+	let rows: Vec<B::Storage> = (0..b.height()).map(|y| b.extract_row(y)).collect();
+	assert_eq!(b.iter_rows().collect::<Vec<_>>(), rows);
+	assert_eq!(b.iter_rows().rev().collect::<Vec<_>>(), rows.into_iter().rev().collect::<Vec<_>>());
+	let cols: Vec<B::Storage> = (0..b.width()).map(|x| b.extract_col(x)).collect();
+	assert_eq!(b.iter_cols().len(), cols.len());
+	assert_eq!(b.iter_cols().collect::<Vec<_>>(), cols);
+
+	assert_eq!(b.first(), b.iter_coords().next().map(|c| index(b, c)));
+	assert_eq!(b.last(), b.iter_coords().next_back().map(|c| index(b, c)));
+}
+
+fn index<B: Bitboard>(b: &B, (x, y): (u8, u8)) -> u32 {
+	if b.col_major() { x as u32 * b.height() as u32 + y as u32 } else { y as u32 * b.width() as u32 + x as u32 }
+}
+
+#[test]
+fn test_static() {
+	let b: Bitboard5x3 = STONES.into_iter().collect();
+	assert_eq!(b.count(), 4);
+	assert!(STONES.iter().all(|&(x, y)| b.get(x, y)));
+	assert_eq!(b.iter_coords().collect::<Vec<_>>(), [(0, 0), (1, 2), (3, 0), (4, 2)]);
+	assert_eq!(b.iter_rows().collect::<Vec<_>>(), [0b01001, 0, 0b10010]);
+	assert_eq!(b.iter_cols().collect::<Vec<_>>(), [0b001, 0b100, 0, 0b001, 0b100]);
+	check(&b);
+
+	let from_indexes: Bitboard5x3 = (&b).into_iter().collect();
+	assert_eq!(from_indexes, b);
+	let mut indexes = Vec::new();
+	for i in &b {
+		indexes.push(i);
+	}
+	assert_eq!(indexes, b.bits().collect::<Vec<_>>());
+
+	let mut chess = Chess::EMPTY;
+	chess.extend([(7, 7), (2, 5)]);
+	chess.extend([0u32, 9]);
+	assert_eq!(chess.iter_coords().collect::<Vec<_>>(), [(0, 0), (1, 1), (2, 5), (7, 7)]);
+	check(&chess);
+	check(&Chess::FULL);
+
+	let go: Go19 = [(18, 18), (0, 18), (9, 9)].into_iter().collect();
+	assert_eq!(go.first(), Some(9 * 19 + 9));
+	assert_eq!(go.last(), Some(19 * 19 - 1));
+	check(&go);
+}
+
+#[test]
+fn test_runtime() {
+	fn filled<B: Bitboard + Extend<(u8, u8)>>(mut b: B) -> B {
+		b.extend(STONES);
+		b
+	}
+	let small = filled(SmallBitboard::empty(5, 3, true));
+	assert_eq!(small.iter_coords().collect::<Vec<_>>(), [(0, 0), (1, 2), (3, 0), (4, 2)]);
+	assert_eq!(small.iter_rows().collect::<Vec<_>>(), [0b01001, 0, 0b10010]);
+	check(&small);
+	check(&filled(MediumBitboard::empty(11, 11, false)));
+	check(&filled(LargeBitboard::empty(19, 19, true)));
+	check(&filled(DynBitboard::empty(5, 3, false)));
+	check(&filled(DynBitboard::empty(30, 30, false)));
+
+	let mut large = LargeBitboard::empty(255, 255, false);
+	large.extend([255 * 255 - 1, 300u32]);
+	assert_eq!((&large).into_iter().collect::<Vec<_>>(), [300, 255 * 255 - 1]);
+	assert_eq!(large.iter_coords().collect::<Vec<_>>(), [(45, 1), (254, 254)]);
+	check(&large);
+}
+
+#[test]
+fn test_first_last_empty() {
+	assert_eq!((Chess::EMPTY.first(), Chess::EMPTY.last()), (None, None));
+	assert_eq!((Go19::EMPTY.first(), Go19::EMPTY.last()), (None, None));
+	assert_eq!(Chess::EMPTY.iter_coords().next(), None);
+	let small = SmallBitboard::empty(8, 8, false);
+	assert_eq!((small.first(), small.last()), (None, None));
+	let large = LargeBitboard::empty(200, 200, false);
+	assert_eq!((large.first(), large.last()), (None, None));
+	let dynamic = DynBitboard::empty(20, 20, true);
+	assert_eq!((dynamic.first(), dynamic.last()), (None, None));
+	assert_eq!(Chess::FULL.first(), Some(0));
+	assert_eq!(Chess::FULL.last(), Some(63));
+}
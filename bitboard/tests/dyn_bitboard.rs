@@ -0,0 +1,123 @@
+use bitboard::{BitIter, Bitboard, runtime::{BitboardShape, RuntimeBitboard, ShapeError, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+
+mod common;
+use common::{Rng, random_board};
+
+#[test]
+fn test_backend_selection() {
+	let b = DynBitboard::for_shape(BitboardShape::new(8, 8, false));
+	assert!(b.inner().as_small().is_some());
+	let b = DynBitboard::for_shape(BitboardShape::new(9, 8, false));
+	assert!(b.inner().as_medium().is_some());
+	let b = DynBitboard::for_shape(BitboardShape::new(16, 8, true));
+	assert!(b.inner().as_medium().is_some());
+	let b = DynBitboard::for_shape(BitboardShape::new(17, 8, false));
+	assert!(b.inner().as_large().is_some());
+	let b = DynBitboard::for_shape(BitboardShape::new(19, 19, true));
+	assert!(b.inner().as_large().is_some());
+	assert_eq!(b.shape(), BitboardShape::new(19, 19, true));
+	assert!(b.is_empty());
+	assert_eq!(b.full_with_same_shape().count(), 361);
+}
+
+/// Boards of the same shape stay on the same backend, so binary operations never mix two backends.
+#[test]
+fn test_backend_invariant() {
+	// Converted to the narrowest backend
+	let from_large = DynBitboard::from(LargeBitboard::full(10, 10, false));
+	assert!(from_large.inner().as_medium().is_some());
+	assert_eq!(from_large.clone() & DynBitboard::full(10, 10, false), from_large);
+	let from_medium = DynBitboard::from(MediumBitboard::west_border(8, 7, true));
+	assert!(from_medium.inner().as_small().is_some());
+	assert_eq!((from_medium ^ DynBitboard::west_border(8, 7, true)).count(), 0);
+
+	// Storage of another shape brings its shape and its backend
+	let mut b = DynBitboard::empty(5, 5, false);
+	*b.storage_mut() = DynBitboard::full(20, 20, false).into_inner();
+	assert!(b.inner().as_large().is_some());
+	assert_eq!(b.shape(), BitboardShape::new(20, 20, false));
+	assert_eq!((b & DynBitboard::full(20, 20, false)).count(), 400);
+}
+
+#[test]
+fn test_matches_backends() {
+	let small = SmallBitboard::north_border(7, 6, true) | SmallBitboard::col_mask(7, 6, true, 2);
+	let dynamic = DynBitboard::north_border(7, 6, true) | DynBitboard::col_mask(7, 6, true, 2);
+	assert_eq!(DynBitboard::from(small), dynamic);
+
+	let medium = MediumBitboard::borders(11, 10, false) ^ MediumBitboard::row_mask(11, 10, false, 4);
+	let dynamic = DynBitboard::borders(11, 10, false) ^ DynBitboard::row_mask(11, 10, false, 4);
+	assert_eq!(DynBitboard::from(medium), dynamic);
+
+	let large = LargeBitboard::east_border(19, 19, false) & !LargeBitboard::south_border(19, 19, false);
+	let dynamic = DynBitboard::east_border(19, 19, false) & !DynBitboard::south_border(19, 19, false);
+	assert_eq!(DynBitboard::from(large), dynamic);
+	assert_eq!(dynamic.count(), 18);
+	assert_eq!(dynamic.first().map(|i| i as usize), Some(DynBitboard::index_from_coords(19, 19, false, 18, 1)));
+	assert_eq!(dynamic.last().map(|i| i as usize), Some(DynBitboard::index_from_coords(19, 19, false, 18, 18)));
+}
+
+#[test]
+fn test_shape_mismatch() {
+	let a = DynBitboard::full(8, 8, false);
+	let b = DynBitboard::full(9, 9, false);
+	assert_eq!(a.clone().try_and(b.clone()), Err(ShapeError { expected: a.shape(), found: b.shape() }));
+	assert!(a.clone().try_or(DynBitboard::full(8, 8, true)).is_err());
+	assert!(a.clone().try_xor(DynBitboard::full(4, 16, false)).is_err());
+	assert_eq!(a.clone().try_xor(a.clone()), Ok(DynBitboard::empty(8, 8, false)));
+
+	let error = a.clone().try_and(b.clone()).unwrap_err();
+	assert_eq!(error.to_string(), "bitboard shape mismatch: expected 8x8, found 9x9");
+
+	let result = std::panic::catch_unwind(|| a | b);
+	assert!(result.is_err());
+}
+
+#[test]
+fn test_row_col_roundtrip() {
+	let mut rng = Rng(0x5DEE_CE66_D1CE_4E5B);
+	for (w, h) in [(8, 8), (7, 5), (11, 10), (16, 8), (19, 19), (25, 9)] {
+		for col_major in [false, true] {
+			let b = random_board(DynBitboard::empty(w, h, col_major), 3, &mut rng);
+			let mut rebuilt = b.empty_with_same_shape();
+			for y in 0..h {
+				rebuilt.insert_row(y, b.extract_row(y));
+			}
+			assert_eq!(rebuilt, b, "rows {w}x{h} col_major={col_major}\n{b}");
+			let mut rebuilt = b.empty_with_same_shape();
+			for x in 0..w {
+				rebuilt.insert_col(x, b.extract_col(x));
+			}
+			assert_eq!(rebuilt, b, "cols {w}x{h} col_major={col_major}\n{b}");
+
+			let mask = random_board(DynBitboard::empty(w, h, col_major), 3, &mut rng);
+			assert_eq!(mask.pdep(b.pext(&mask)), b.clone() & mask, "pext/pdep {w}x{h} col_major={col_major}\n{b}");
+		}
+	}
+}
+
+#[test]
+fn test_trait_defaults() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	for (w, h) in [(8, 8), (11, 10), (19, 19)] {
+		for col_major in [false, true] {
+			let b = random_board(DynBitboard::empty(w, h, col_major), 3, &mut rng);
+			for x in 0..w {
+				for y in 0..h {
+					let expected = x > 0 && b.get(x - 1, y);
+					assert_eq!(b.shifted_e().get(x, y), expected, "shifted_e ({x}, {y}) {w}x{h} col_major={col_major}\n{b}");
+				}
+			}
+			let count: u32 = b.clone().iter_bits().count() as u32;
+			assert_eq!(count, b.count());
+		}
+	}
+
+	let mut b = DynBitboard::empty(19, 19, true);
+	for i in 0..5 {
+		b.set(3 + i, 7 + i);
+	}
+	assert!(b.has_n_aligned_diag_inc(5));
+	assert!(!b.has_n_aligned_diag_inc(6));
+	assert_eq!(b.count_aligned::<4>(), 2);
+}
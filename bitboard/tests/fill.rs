@@ -0,0 +1,206 @@
+use bitboard::{Bitboard, fill::{Connectivity, FloodFill}, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard, bitboard_lit};
+
+mod common;
+use common::{Rng, random_board_where};
+
+#[bitboard(width=9,height=9)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard9x9;
+
+#[bitboard(width=7,height=5, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard7x5ColMajor;
+
+#[bitboard(width=19,height=19, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard19x19ColMajor;
+
+#[bitboard(width=11,height=11, topology="hex")]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Hex11;
+
+#[bitboard(width=8,height=8, wrap_x=true, wrap_y=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Torus8x8;
+
+const FOUR: [(i16, i16); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const EIGHT: [(i16, i16); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (-1, 1), (1, -1), (-1, -1)];
+const HEX: [(i16, i16); 6] = [(1, 0), (-1, 0), (0, 1), (0, -1), (-1, 1), (1, -1)];
+
+fn offsets(connectivity: Connectivity) -> &'static [(i16, i16)] {
+	match connectivity {
+		Connectivity::Four => &FOUR,
+		Connectivity::Eight => &EIGHT,
+		Connectivity::Hex => &HEX,
+	}
+}
+
+/// Reference connected groups, found by a depth-first search on the coordinates.
+fn components_ref<B: Bitboard>(b: &B, connectivity: Connectivity, wrap: bool) -> Vec<Vec<(u8, u8)>> {
+	let (w, h) = (b.width() as i16, b.height() as i16);
+	let mut seen = vec![false; (w * h) as usize];
+	let mut groups = Vec::new();
+	for y in 0..h {
+		for x in 0..w {
+			if !b.get(x as u8, y as u8) || seen[(y * w + x) as usize] {
+				continue;
+			}
+			seen[(y * w + x) as usize] = true;
+			let mut group = Vec::new();
+			let mut stack = vec![(x, y)];
+			while let Some((cx, cy)) = stack.pop() {
+				group.push((cx as u8, cy as u8));
+				for &(dx, dy) in offsets(connectivity) {
+					let (mut nx, mut ny) = (cx + dx, cy + dy);
+					if wrap {
+						(nx, ny) = (nx.rem_euclid(w), ny.rem_euclid(h));
+					}
+					if nx >= 0 && ny >= 0 && nx < w && ny < h && b.get(nx as u8, ny as u8) && !seen[(ny * w + nx) as usize] {
+						seen[(ny * w + nx) as usize] = true;
+						stack.push((nx, ny));
+					}
+				}
+			}
+			group.sort();
+			groups.push(group);
+		}
+	}
+	groups.sort();
+	groups
+}
+
+fn coords<B: Bitboard>(b: &B) -> Vec<(u8, u8)> {
+	let mut res = Vec::new();
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			if b.get(x, y) {
+				res.push((x, y));
+			}
+		}
+	}
+	res.sort();
+	res
+}
+
+fn check_components<B: FloodFill + std::fmt::Display>(b: &B, connectivity: Connectivity, wrap: bool) {
+	let mut groups: Vec<_> = b.components(connectivity).map(|g| coords(&g)).collect();
+	groups.sort();
+	assert_eq!(groups, components_ref(b, connectivity, wrap), "{connectivity:?}\n{b}");
+
+	// Each group is the flood fill of any of its squares
+	for group in b.components(connectivity) {
+		let mut seed = group.clone() ^ group.clone();
+		let (x, y) = coords(&group)[0];
+		seed.set(x, y);
+		assert!(seed.flood_fill(b, connectivity) == group);
+	}
+}
+
+fn check_all<B: FloodFill + std::fmt::Display>(mut b: B, seed: u64) {
+	let mut rng = Rng(seed);
+	for _ in 0..8 {
+		b = random_board_where(b.clone() ^ b, &mut rng, |r| !r.is_multiple_of(3));
+		check_components(&b, Connectivity::Four, false);
+		check_components(&b, Connectivity::Eight, false);
+		check_components(&b, Connectivity::Hex, false);
+	}
+}
+
+#[test]
+fn test_components_static() {
+	check_all(Bitboard9x9::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_all(Bitboard7x5ColMajor::EMPTY, 0x2545_F491_4F6C_DD1D);
+	check_all(Bitboard19x19ColMajor::EMPTY, 0xD1B5_4A32_D192_ED03);
+	check_all(Hex11::EMPTY, 0x94D0_49BB_1331_11EB);
+}
+
+#[test]
+fn test_components_runtime() {
+	for col_major in [false, true] {
+		check_all(SmallBitboard::empty(7, 6, col_major), 0x9E37_79B9_7F4A_7C15);
+		check_all(MediumBitboard::empty(11, 10, col_major), 0x2545_F491_4F6C_DD1D);
+		check_all(LargeBitboard::empty(19, 19, col_major), 0xD1B5_4A32_D192_ED03);
+		check_all(DynBitboard::empty(13, 13, col_major), 0x94D0_49BB_1331_11EB);
+	}
+}
+
+#[test]
+fn test_components_wrap() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for _ in 0..8 {
+		let b = random_board_where(Torus8x8::EMPTY, &mut rng, |r| !r.is_multiple_of(3));
+		check_components(&b, Connectivity::Four, true);
+		check_components(&b, Connectivity::Eight, true);
+	}
+	// Four corners are one group on a torus
+	assert_eq!(Torus8x8::CORNERS.components(Connectivity::Four).count(), 1);
+}
+
+#[test]
+fn test_go_group() {
+	let black = bitboard_lit!(Bitboard9x9,
+		".........",
+		"..##.....",
+		"..#......",
+		"..###....",
+		".........",
+		"......#..",
+		".....##..",
+		"......#..",
+		".........",
+	);
+	let seed = Bitboard9x9::from_coords(2, 7);
+	let group = seed.flood_fill(&black, Connectivity::Four);
+	assert_eq!(group.count(), 6);
+	assert_eq!(black.components(Connectivity::Four).count(), 2);
+	let liberties = group.expanded(Connectivity::Four) & !black & Bitboard9x9::FULL;
+	assert_eq!(liberties.count(), 12);
+
+	// Seeds outside of the passable squares do not spread
+	assert!(Bitboard9x9::from_coords(0, 0).flood_fill(&black, Connectivity::Four).is_empty());
+}
+
+#[test]
+fn test_hex_connects() {
+	// Connected along the hex NW-SE neighbours, not along the square NE-SW diagonal
+	let red = bitboard_lit!(Hex11,
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"#####......",
+		".....######",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+	);
+	let west = Hex11::WEST_BORDER;
+	let east = Hex11::EAST_BORDER;
+	assert!(west.connects(&east, &red, Connectivity::Hex));
+	assert!(!west.connects(&east, &red, Connectivity::Four));
+
+	let blue = bitboard_lit!(Hex11,
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		".....######",
+		"#####......",
+		"...........",
+		"...........",
+		"...........",
+	);
+	assert!(!west.connects(&east, &blue, Connectivity::Hex));
+	assert!(west.connects(&east, &blue, Connectivity::Eight));
+
+	let b = LargeBitboard::borders(19, 19, true);
+	let west = LargeBitboard::col_mask(19, 19, true, 0);
+	let east = LargeBitboard::col_mask(19, 19, true, 18);
+	assert!(west.connects(&east, &b, Connectivity::Four));
+	assert!(!west.connects(&east, &(b.clone() ^ LargeBitboard::row_mask(19, 19, true, 0) ^ LargeBitboard::row_mask(19, 19, true, 18)), Connectivity::Eight));
+}
@@ -0,0 +1,341 @@
+use bitboard::{Bitboard, fill::FloodFill, go::{Color, KoRule, MoveError, Position, Score}, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard, bitboard_lit};
+
+mod common;
+use common::{Rng};
+
+#[bitboard(width=9,height=9)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Board9;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Board19;
+
+#[bitboard(width=19,height=19, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Board19ColMajor;
+
+/// Reference rules on an array of points: 0 empty, 1 black, 2 white.
+#[derive(Clone)]
+struct RefGame {
+	w: usize,
+	h: usize,
+	points: Vec<u8>,
+	to_move: u8,
+	superko: bool,
+	/// Every position since the start, the current one last.
+	history: Vec<Vec<u8>>,
+	/// Position before the last move, `None` after a pass.
+	previous: Option<Vec<u8>>,
+}
+
+impl RefGame {
+	fn new(w: usize, h: usize, superko: bool) -> Self {
+		let points = vec![0; w * h];
+		Self { w, h, points: points.clone(), to_move: 1, superko, history: vec![points], previous: None }
+	}
+
+	fn neighbors(&self, p: usize) -> Vec<usize> {
+		let (x, y) = (p % self.w, p / self.w);
+		let mut res = Vec::new();
+		if x > 0 { res.push(p - 1); }
+		if x + 1 < self.w { res.push(p + 1); }
+		if y > 0 { res.push(p - self.w); }
+		if y + 1 < self.h { res.push(p + self.w); }
+		res
+	}
+
+	/// Group of the stone at `p` and whether it has a liberty.
+	fn group(&self, points: &[u8], p: usize) -> (Vec<usize>, bool) {
+		let mut group = vec![p];
+		let mut free = false;
+		let mut i = 0;
+		while i < group.len() {
+			for n in self.neighbors(group[i]) {
+				if points[n] == 0 {
+					free = true;
+				} else if points[n] == points[p] && !group.contains(&n) {
+					group.push(n);
+				}
+			}
+			i += 1;
+		}
+		(group, free)
+	}
+
+	fn play(&mut self, x: u8, y: u8) -> Result<usize, MoveError> {
+		let p = y as usize * self.w + x as usize;
+		if self.points[p] != 0 {
+			return Err(MoveError::Occupied);
+		}
+		let mut points = self.points.clone();
+		points[p] = self.to_move;
+		let mut captured = 0;
+		for n in self.neighbors(p) {
+			if points[n] == 3 - self.to_move {
+				let (group, free) = self.group(&points, n);
+				if !free {
+					captured += group.len();
+					for s in group {
+						points[s] = 0;
+					}
+				}
+			}
+		}
+		if !self.group(&points, p).1 {
+			return Err(MoveError::Suicide);
+		}
+		if self.previous.as_ref() == Some(&points) {
+			return Err(MoveError::Ko);
+		}
+		if self.superko && self.history.contains(&points) {
+			return Err(MoveError::Superko);
+		}
+		self.previous = Some(std::mem::replace(&mut self.points, points.clone()));
+		self.history.push(points);
+		self.to_move = 3 - self.to_move;
+		Ok(captured)
+	}
+
+	fn pass(&mut self) {
+		self.previous = None;
+		self.to_move = 3 - self.to_move;
+	}
+
+	/// Tromp-Taylor area score.
+	fn score(&self) -> Score {
+		let mut score = [0, 0, 0];
+		for p in 0..self.points.len() {
+			if self.points[p] != 0 {
+				score[self.points[p] as usize] += 1;
+				continue;
+			}
+			let mut region = vec![p];
+			let mut reach = [false; 3];
+			let mut i = 0;
+			while i < region.len() {
+				for n in self.neighbors(region[i]) {
+					if self.points[n] == 0 {
+						if !region.contains(&n) {
+							region.push(n);
+						}
+					} else {
+						reach[self.points[n] as usize] = true;
+					}
+				}
+				i += 1;
+			}
+			if reach[1] != reach[2] {
+				score[if reach[1] { 1 } else { 2 }] += 1;
+			}
+		}
+		Score { black: score[1], white: score[2] }
+	}
+}
+
+/// Plays random games and compares every move, the stones and the score with the reference rules.
+fn check_random_games<B: FloodFill>(board: B, seed: u64) {
+	let (w, h) = (board.width(), board.height());
+	let mut rng = Rng(seed);
+	for game in 0..6 {
+		let ko_rule = if game % 2 == 0 { KoRule::Simple } else { KoRule::PositionalSuperko };
+		let mut pos = Position::new(board.clone(), ko_rule);
+		let mut reference = RefGame::new(w as usize, h as usize, ko_rule == KoRule::PositionalSuperko);
+		let mut prisoners = [0, 0];
+		for _ in 0..(w as usize * h as usize * 2) {
+			if rng.next().is_multiple_of(20) {
+				pos.pass();
+				reference.pass();
+				continue;
+			}
+			let (x, y) = ((rng.next() % w as u64) as u8, (rng.next() % h as u64) as u8);
+			let color = pos.to_move();
+			let expected = reference.play(x, y);
+			assert_eq!(pos.check_move(x, y).err(), expected.err(), "({x}, {y})");
+			let played = pos.play(x, y).map(|captured| captured.count() as usize);
+			assert_eq!(played, expected, "({x}, {y})");
+			if let Ok(captured) = played {
+				prisoners[color as usize] += captured as u32;
+			}
+			for y in 0..h {
+				for x in 0..w {
+					let expected = match reference.points[y as usize * w as usize + x as usize] {
+						1 => Some(Color::Black),
+						2 => Some(Color::White),
+						_ => None,
+					};
+					assert_eq!(pos.get(x, y), expected);
+				}
+			}
+			assert_eq!(pos.prisoners(Color::Black), prisoners[0]);
+			assert_eq!(pos.prisoners(Color::White), prisoners[1]);
+		}
+		assert_eq!(pos.area_score(), reference.score());
+	}
+}
+
+#[test]
+fn test_random_games() {
+	check_random_games(Board9::FULL, 0x9E37_79B9_7F4A_7C15);
+	check_random_games(MediumBitboard::full(9, 9, true), 0x2545_F491_4F6C_DD1D);
+	check_random_games(Board19::FULL, 0xD1B5_4A32_D192_ED03);
+	check_random_games(Board19ColMajor::FULL, 0x94D0_49BB_1331_11EB);
+	check_random_games(LargeBitboard::full(19, 19, false), 0x9E37_79B9_7F4A_7C15);
+}
+
+#[test]
+fn test_capture_and_suicide() {
+	let black = bitboard_lit!(Board9,
+		".........",
+		".........",
+		".........",
+		".........",
+		"....#....",
+		"...#.#...",
+		"...#.#...",
+		"#...#....",
+		".#.......",
+	);
+	let white = bitboard_lit!(Board9,
+		".........",
+		".........",
+		".........",
+		".........",
+		".........",
+		"....#....",
+		".........",
+		".........",
+		"#........",
+	);
+	let mut pos = Position::from_stones(Board9::FULL, black, white, Color::White, KoRule::Simple);
+	assert_eq!(pos.liberties(4, 3), Board9::from_coords(4, 2));
+	assert_eq!(pos.liberties(0, 0), Board9::EMPTY);
+
+	// White may not fill its own last liberty, black captures there
+	assert_eq!(pos.play(4, 2), Err(MoveError::Suicide));
+	assert_eq!(pos.to_move(), Color::White);
+	pos.pass();
+	assert_eq!(pos.play(4, 2), Ok(Board9::from_coords(4, 3)));
+	assert_eq!(pos.prisoners(Color::Black), 1);
+	assert_eq!(pos.play(4, 2), Err(MoveError::Occupied));
+	assert_eq!(pos.play(9, 0), Err(MoveError::OutOfBoard));
+	assert_eq!(pos.group(4, 2).count(), 6);
+	assert_eq!(pos.group(0, 0), Board9::from_coords(0, 0));
+}
+
+#[test]
+fn test_ko() {
+	// Black takes the ko at (3, 4), white may only retake at (2, 4) after a move elsewhere
+	let black = bitboard_lit!(Board9,
+		".........",
+		".........",
+		".........",
+		"..#......",
+		".#.......",
+		"..#......",
+		".........",
+		".........",
+		".........",
+	);
+	let white = bitboard_lit!(Board9,
+		".........",
+		".........",
+		".........",
+		"...#.....",
+		"..#.#....",
+		"...#.....",
+		".........",
+		".........",
+		".........",
+	);
+	for ko_rule in [KoRule::Simple, KoRule::PositionalSuperko] {
+		let mut pos = Position::from_stones(Board9::FULL, black, white, Color::Black, ko_rule);
+		assert_eq!(pos.play(3, 4), Ok(Board9::from_coords(2, 4)));
+		assert_eq!(pos.ko(), Some(&Board9::from_coords(2, 4)));
+		assert_eq!(pos.play(2, 4), Err(MoveError::Ko));
+
+		// Passes clear the ko, which then only superko forbids
+		pos.pass();
+		pos.pass();
+		assert_eq!(pos.ko(), None);
+		assert_eq!(pos.consecutive_passes(), 2);
+		match ko_rule {
+			KoRule::Simple => assert_eq!(pos.play(2, 4), Ok(Board9::from_coords(3, 4))),
+			KoRule::PositionalSuperko => assert_eq!(pos.play(2, 4), Err(MoveError::Superko)),
+		}
+
+		// Threat and answer, then the ko can be retaken
+		let mut pos = Position::from_stones(Board9::FULL, black, white, Color::Black, ko_rule);
+		pos.play(3, 4).unwrap();
+		pos.play(7, 7).unwrap();
+		pos.play(7, 6).unwrap();
+		assert_eq!(pos.play(2, 4), Ok(Board9::from_coords(3, 4)));
+		assert_eq!(pos.ko(), Some(&Board9::from_coords(3, 4)));
+	}
+}
+
+#[test]
+fn test_big_capture_19x19() {
+	// A black wall along the south edge, crossing the words of the storage, surrounded by white
+	let mut black = Board19::EMPTY;
+	let mut white = Board19::EMPTY;
+	for x in 0..19 {
+		black.set(x, 0);
+		black.set(x, 1);
+		if x != 18 {
+			white.set(x, 2);
+		}
+	}
+	let mut pos = Position::from_stones(Board19::FULL, black.clone(), white.clone(), Color::White, KoRule::PositionalSuperko);
+	assert_eq!(pos.liberties(0, 0), Board19::from_coords(18, 2));
+	let captured = pos.play(18, 2).unwrap();
+	assert_eq!(captured, black);
+	assert_eq!(pos.prisoners(Color::White), 38);
+	assert_eq!(pos.area_score(), Score { black: 0, white: 361 });
+
+	// The same on the runtime bitboard
+	let runtime = |b: &Board19| {
+		let mut res = LargeBitboard::empty(19, 19, false);
+		for (x, y) in (0..19).flat_map(|x| (0..19).map(move |y| (x, y))).filter(|&(x, y)| b.get(x, y)) {
+			res.set(x, y);
+		}
+		res
+	};
+	let mut pos = Position::from_stones(LargeBitboard::full(19, 19, false), runtime(&black), runtime(&white), Color::White, KoRule::Simple);
+	assert_eq!(pos.play(18, 2).unwrap().count(), 38);
+	assert_eq!(pos.area_score(), Score { black: 0, white: 361 });
+}
+
+#[test]
+fn test_area_score() {
+	let pos = Position::new(Board9::FULL, KoRule::Simple);
+	assert_eq!(pos.area_score(), Score { black: 0, white: 0 });
+
+	// Black owns the west, white the east, column 6 is shared (dame)
+	let black = bitboard_lit!(Board9,
+		"...#.....",
+		"...#.....",
+		"...#.....",
+		"...#.....",
+		"...#.....",
+		"...#.....",
+		"...#.....",
+		"...#.....",
+		"...#.....",
+	);
+	let white = bitboard_lit!(Board9,
+		".......#.",
+		".......#.",
+		".......#.",
+		".......#.",
+		".......#.",
+		".......#.",
+		".......#.",
+		".......#.",
+		".......#.",
+	);
+	let pos = Position::from_stones(Board9::FULL, black, white, Color::Black, KoRule::Simple);
+	assert_eq!(pos.area_score(), Score { black: 36, white: 18 });
+	assert_eq!(pos.area(Color::White), white | white.shifted_e());
+}
@@ -0,0 +1,177 @@
+use bitboard::Bitboard;
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard, bitboard_lit};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=11,height=11, topology="hex", tables(neighbors_hex, ray_hex_e, ray_hex_ne, ray_hex_nw, ray_hex_w, ray_hex_sw, ray_hex_se))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Hex11;
+
+#[bitboard(width=7,height=5, col_major=true, topology="hex", tables(neighbors_hex))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Hex7x5ColMajor;
+
+#[bitboard(width=13,height=13, topology="hex")]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Hex13;
+
+#[bitboard(width=19,height=19, col_major=true, topology="hex", tables(neighbors_hex, ray_hex_se))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Hex19ColMajor;
+
+const DIRECTIONS: [(i16, i16); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// Reference shift: every set cell moved by `(dx, dy)`, dropping those leaving the board.
+fn shifted_ref<B: Bitboard>(b: &B, dx: i16, dy: i16) -> B {
+	let mut res = b.clone();
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			res.reset(x, y);
+		}
+	}
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			let (nx, ny) = (x as i16 + dx, y as i16 + dy);
+			if b.get(x, y) && nx >= 0 && ny >= 0 && nx < b.width() as i16 && ny < b.height() as i16 {
+				res.set(nx as u8, ny as u8);
+			}
+		}
+	}
+	res
+}
+
+/// Reference count of the runs of `n` set cells along the hex axes.
+fn count_aligned_ref<B: Bitboard>(b: &B, n: i16) -> u32 {
+	let mut count = 0;
+	for (dx, dy) in [(1, 0), (0, 1), (-1, 1)] {
+		for x in 0..b.width() as i16 {
+			for y in 0..b.height() as i16 {
+				let run = (0..n).all(|k| {
+					let (cx, cy) = (x + k * dx, y + k * dy);
+					cx >= 0 && cy >= 0 && cx < b.width() as i16 && cy < b.height() as i16 && b.get(cx as u8, cy as u8)
+				});
+				if run {
+					count += 1;
+				}
+			}
+		}
+	}
+	count
+}
+
+macro_rules! hex_test {
+	($T:ty, $seed:expr) => {{
+		let mut rng = Rng($seed);
+		for _ in 0..16 {
+			let b = random_board(<$T>::EMPTY, 2, &mut rng);
+
+			let shifted = [
+				b.shifted_hex_e(), b.shifted_hex_ne(), b.shifted_hex_nw(),
+				b.shifted_hex_w(), b.shifted_hex_sw(), b.shifted_hex_se(),
+			];
+			let mut dilated = b.clone();
+			for (s, (dx, dy)) in shifted.iter().zip(DIRECTIONS) {
+				let expected = shifted_ref(&b, dx, dy);
+				assert_eq!(*s, expected, "({dx}, {dy})\n{b}");
+				dilated |= expected;
+			}
+			assert_eq!(b.hex_dilated(), dilated);
+			assert_eq!(b.hex_neighbors_of_any(), dilated & !b.clone());
+
+			let mut s = b.clone();
+			s.shift_hex_nw();
+			assert_eq!(s, b.shifted_hex_nw());
+
+			let sparse = random_board(<$T>::EMPTY, 3, &mut rng);
+			let dense = !random_board(<$T>::EMPTY, 5, &mut rng) & <$T>::FULL;
+			for b in [b, sparse, dense] {
+				assert_eq!(b.count_aligned::<3>(), count_aligned_ref(&b, 3), "\n{b}");
+				assert_eq!(b.count_aligned::<5>(), count_aligned_ref(&b, 5), "\n{b}");
+				assert_eq!(b.has_aligned::<4>(), count_aligned_ref(&b, 4) > 0, "\n{b}");
+			}
+		}
+
+		for i in 0..<$T>::NB_SQUARES {
+			let (x, y) = <$T>::coords_from_index(i);
+			let cell = <$T>::from_index(i);
+			let neighbors = DIRECTIONS.iter().fold(<$T>::EMPTY, |acc, &(dx, dy)| acc | shifted_ref(&cell, dx, dy));
+			assert_eq!(<$T>::compute_neighbors_hex_mask(i), neighbors, "({x}, {y})");
+			assert_eq!(<$T>::compute_ray_hex_nw_mask(i).count() as u8, x.min(<$T>::HEIGHT - 1 - y));
+		}
+	}};
+}
+
+#[test]
+fn test_hex() {
+	hex_test!(Hex11, 0x9E37_79B9_7F4A_7C15);
+	hex_test!(Hex7x5ColMajor, 0x2545_F491_4F6C_DD1D);
+	hex_test!(Hex13, 0xD1B5_4A32_D192_ED03);
+	hex_test!(Hex19ColMajor, 0x94D0_49BB_1331_11EB);
+}
+
+#[test]
+fn test_hex_tables() {
+	assert_eq!(Hex11::NEIGHBORS_HEX, &Hex11::generate_neighbors_hex_table());
+	assert_eq!(Hex11::RAY_HEX_E, &Hex11::generate_ray_hex_e_table());
+	assert_eq!(Hex11::RAY_HEX_NE, &Hex11::generate_ray_hex_ne_table());
+	assert_eq!(Hex11::RAY_HEX_NW, &Hex11::generate_ray_hex_nw_table());
+	assert_eq!(Hex11::RAY_HEX_W, &Hex11::generate_ray_hex_w_table());
+	assert_eq!(Hex11::RAY_HEX_SW, &Hex11::generate_ray_hex_sw_table());
+	assert_eq!(Hex11::RAY_HEX_SE, &Hex11::generate_ray_hex_se_table());
+	assert_eq!(Hex7x5ColMajor::NEIGHBORS_HEX, &Hex7x5ColMajor::generate_neighbors_hex_table());
+	assert_eq!(Hex19ColMajor::NEIGHBORS_HEX, &Hex19ColMajor::generate_neighbors_hex_table());
+	assert_eq!(Hex19ColMajor::RAY_HEX_SE, &Hex19ColMajor::generate_ray_hex_se_table());
+
+	// Corners have 2 or 3 neighbours, inner cells 6
+	assert_eq!(Hex11::neighbors_hex_mask(Hex11::index_from_coords(0, 0)).count(), 2);
+	assert_eq!(Hex11::neighbors_hex_mask(Hex11::index_from_coords(10, 0)).count(), 3);
+	assert_eq!(Hex11::neighbors_hex_mask(Hex11::index_from_coords(5, 5)).count(), 6);
+}
+
+#[test]
+fn test_hex_alignments() {
+	// The three hex axes count, the square anti-diagonal does not
+	let b = bitboard_lit!(Hex11,
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"....#......",
+		"...#.......",
+		"..#........",
+		".#.........",
+		"#..........",
+	);
+	assert!(!b.has_aligned::<2>());
+	assert_eq!(b.count_aligned::<2>(), 0);
+
+	let b = bitboard_lit!(Hex11,
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"...........",
+		"#..........",
+		".#.........",
+		"..#........",
+		"...#.......",
+		"....#......",
+		"...........",
+	);
+	assert!(b.has_aligned_hex_nwse::<5>());
+	assert!(!b.has_aligned::<6>());
+	assert_eq!(b.count_aligned_hex_nwse::<5>(), 1);
+	assert_eq!(b.count_aligned::<5>(), 1);
+	assert_eq!(b.count_aligned::<3>(), 3);
+
+	let mut b = Hex19ColMajor::EMPTY;
+	for y in 0..19 {
+		b.set(7, y);
+	}
+	assert!(b.has_aligned_hex_nesw::<19>());
+	assert!(!b.has_aligned_hex_ew::<2>());
+	assert_eq!(b.count_aligned::<10>(), 10);
+}
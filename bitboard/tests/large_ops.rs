@@ -0,0 +1,168 @@
+use bitboard::{BitIter, Bitboard, fill::{Connectivity, FloodFill}, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, large_bitboard_n::LargeBitboardN}, symmetry::Symmetric};
+
+mod common;
+use common::{Rng, random_board};
+
+/// Same words, `n` having exactly as many as `l`.
+#[track_caller]
+fn assert_same<const WORDS: usize>(l: &LargeBitboard, n: &LargeBitboardN<WORDS>) {
+	assert_eq!(l.shape(), n.shape());
+	assert_eq!(&l.storage()[..], &n.storage()[..], "\n{l:?}\n{n:?}");
+}
+
+/// `LargeBitboardN` against `LargeBitboard`, on boards stored in exactly `WORDS` words.
+fn check_same_as_large<const WORDS: usize>(w: u8, h: u8, col_major: bool, rng: &mut Rng) {
+	let (la, lb) = (random_board(LargeBitboard::empty(w, h, col_major), 3, rng), random_board(LargeBitboard::empty(w, h, col_major), 3, rng));
+	assert_eq!(la.storage().len(), WORDS);
+	let na = LargeBitboardN::<WORDS>::new(w, h, col_major, la.storage()[..].try_into().unwrap());
+	let nb = LargeBitboardN::<WORDS>::new(w, h, col_major, lb.storage()[..].try_into().unwrap());
+
+	assert_same(&LargeBitboard::full(w, h, col_major), &LargeBitboardN::<WORDS>::full(w, h, col_major));
+	assert_same(&LargeBitboard::borders(w, h, col_major), &LargeBitboardN::<WORDS>::borders(w, h, col_major));
+	for y in [0, h / 2, h - 1] {
+		assert_same(&la.row_mask_with_same_shape(y), &na.row_mask_with_same_shape(y));
+		assert_eq!(&la.extract_row(y)[..], &na.extract_row(y)[..la.extract_row(y).len()]);
+	}
+	for x in [0, w / 2, w - 1] {
+		assert_same(&la.col_mask_with_same_shape(x), &na.col_mask_with_same_shape(x));
+	}
+
+	assert_same(&(la.clone() & lb.clone()), &(na & nb));
+	assert_same(&(la.clone() | lb.clone()), &(na | nb));
+	assert_same(&(la.clone() ^ lb.clone()), &(na ^ nb));
+	// `LargeBitboard` keeps the bits past the board that `!` and `<<` set, `LargeBitboardN` clears them
+	let full = LargeBitboard::full(w, h, col_major);
+	assert_same(&(!la.clone() & full.clone()), &!na);
+	assert_same(&(la.clone() << 37usize & full), &(na << 37));
+	assert_same(&(la.clone() >> 70usize), &(na >> 70));
+	assert_eq!(na.flipped(), !na);
+	assert_eq!((na.count(), na.first(), na.last()), (la.count(), la.first(), la.last()));
+	assert_eq!((na.first(), na.last()), (la.first(), la.last()));
+	assert_eq!(na.intersects(&nb), (na & nb).any());
+
+	for (dx, dy) in [(1, 0), (-1, 2), (0, -3), (5, 5)] {
+		assert_same(&la.shifted(dx, dy), &na.shifted(dx, dy));
+	}
+	assert_same(&la.dilated(), &na.dilated());
+	assert_same(&la.eroded(), &na.eroded());
+	assert_eq!(la.count_aligned::<3>(), na.count_aligned::<3>());
+	assert_same(&la.expanded(Connectivity::Eight), &na.expanded(Connectivity::Eight));
+	assert_same(&la.flip_vertical(), &na.flip_vertical());
+
+	assert_eq!(&la.pext(&lb)[..], &na.pext(&nb)[..la.pext(&lb).len()]);
+	assert_eq!(nb.pdep(na.pext(&nb)), na & nb);
+	let mut n = na;
+	n.insert_row(1, nb.extract_row(1));
+	assert_eq!(n.extract_row(1), nb.extract_row(1));
+	assert_eq!(n & !na.row_mask_with_same_shape(1), na & !na.row_mask_with_same_shape(1));
+}
+
+#[test]
+fn test_large_bitboard_n() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for _ in 0..10 {
+		check_same_as_large::<6>(19, 19, false, &mut rng);
+		check_same_as_large::<6>(19, 19, true, &mut rng);
+		check_same_as_large::<15>(30, 31, false, &mut rng);
+		check_same_as_large::<2>(9, 13, true, &mut rng);
+	}
+	// Extra words stay zero
+	let b = LargeBitboardN::<8>::full(10, 10, false);
+	assert_eq!(b.count(), 100);
+	assert_eq!(b.storage()[2..], [0; 6]);
+	assert_eq!(b.flipped().count(), 0);
+	assert_eq!(b.bits().len(), 100);
+}
+
+/// `!` and `<<` do not set the bits past the last square, whichever way they are called.
+#[test]
+fn test_large_bitboard_n_past_board() {
+	let empty = LargeBitboardN::<4>::empty(10, 10, false);
+	let full = LargeBitboardN::<4>::full(10, 10, false);
+	assert_eq!((!empty).count(), 100);
+	assert_eq!(!empty, full);
+	assert_eq!(!&empty, full);
+	assert_eq!((full << 5).last(), Some(99));
+	assert_eq!((&full << 5).count(), 95);
+	let mut shifted = full;
+	shifted <<= 64;
+	assert_eq!(shifted.storage()[1..], [0xF_FFFF_FFFF, 0, 0]);
+
+	let mut out = LargeBitboardN::<4>::empty(10, 10, false);
+	LargeBitboardN::not_into(&empty, &mut out);
+	assert_eq!(out, full);
+	LargeBitboardN::shl_into(&full, 99, &mut out);
+	assert_eq!(out.bits().collect::<Vec<_>>(), [99]);
+	LargeBitboardN::andnot_into(&full, &empty, &mut out);
+	assert_eq!(out.storage()[2..], [0, 0]);
+
+	// Also on a board filling its last word exactly
+	let full = LargeBitboardN::<2>::full(16, 8, true);
+	assert_eq!((!LargeBitboardN::<2>::empty(16, 8, true)).count(), 128);
+	assert_eq!((full << 1).count(), 127);
+}
+
+#[test]
+#[should_panic]
+fn test_large_bitboard_n_too_small() {
+	LargeBitboardN::<2>::empty(12, 11, false);
+}
+
+#[test]
+fn test_borrowed_operators() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	let a = random_board(LargeBitboard::empty(40, 40, false), 3, &mut rng);
+	let b = random_board(LargeBitboard::empty(40, 40, false), 3, &mut rng);
+	let c = random_board(LargeBitboard::empty(40, 40, false), 3, &mut rng);
+	let expected = (a.clone() & b.clone()) | c.clone();
+	assert_eq!((&a & &b) | &c, expected);
+	assert_eq!(&(&a & &b) | &c, expected);
+	assert_eq!(&a ^ &b, a.clone() ^ b.clone());
+	assert_eq!(a.clone() ^ &b, a.clone() ^ b.clone());
+	assert_eq!(!&a, !a.clone());
+	assert_eq!(&a << 41, a.clone() << 41usize);
+	assert_eq!(&a >> 3, a.clone() >> 3usize);
+
+	let (na, nb) = (LargeBitboardN::<25>::full(40, 40, false), LargeBitboardN::<25>::west_border(40, 40, false));
+	// Generic code over borrowed boards works with the `Copy` type too
+	let (ra, rb) = (&na, &nb);
+	assert_eq!(ra & rb, nb);
+	assert_eq!((!rb) & ra, na & !nb);
+}
+
+#[test]
+fn test_into() {
+	let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+	let a = random_board(LargeBitboard::empty(255, 255, false), 3, &mut rng);
+	let b = random_board(LargeBitboard::empty(255, 255, false), 3, &mut rng);
+	let mut out = LargeBitboard::empty(255, 255, false);
+	let words = out.storage().as_ptr();
+
+	LargeBitboard::and_into(&a, &b, &mut out);
+	assert_eq!(out, a.clone() & b.clone());
+	LargeBitboard::or_into(&a, &b, &mut out);
+	assert_eq!(out, a.clone() | b.clone());
+	LargeBitboard::xor_into(&a, &b, &mut out);
+	assert_eq!(out, a.clone() ^ b.clone());
+	LargeBitboard::andnot_into(&a, &b, &mut out);
+	assert_eq!(out, a.clone() & !b.clone());
+	LargeBitboard::not_into(&a, &mut out);
+	assert_eq!(out, !a.clone());
+	LargeBitboard::shl_into(&a, 255, &mut out);
+	assert_eq!(out, a.clone() << 255usize);
+	LargeBitboard::shr_into(&a, 1, &mut out);
+	assert_eq!(out, a.clone() >> 1usize);
+	// Same shape: the words of `out` are reused
+	assert_eq!(out.storage().as_ptr(), words);
+
+	// Another shape: `out` takes the shape of `a`
+	let mut out = LargeBitboard::empty(3, 3, true);
+	LargeBitboard::and_into(&a, &b, &mut out);
+	assert_eq!(out, a.clone() & b.clone());
+
+	let (na, nb) = (LargeBitboardN::<3>::full(12, 12, true), LargeBitboardN::<3>::north_border(12, 12, true));
+	let mut out = LargeBitboardN::<3>::empty(12, 12, true);
+	LargeBitboardN::andnot_into(&na, &nb, &mut out);
+	assert_eq!(out, na & !nb);
+	assert_eq!(out.count(), 12 * 11);
+}
@@ -0,0 +1,115 @@
+use bitboard::{Bitboard, layers::Layers, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=4,height=3)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard4x3;
+
+const WHITE_PAWN: usize = 0;
+const WHITE_KING: usize = 5;
+const BLACK_PAWN: usize = 6;
+const BLACK_ROOK: usize = 9;
+const BLACK_KING: usize = 11;
+const SYMBOLS: [char; 12] = ['P', 'N', 'B', 'R', 'Q', 'K', 'p', 'n', 'b', 'r', 'q', 'k'];
+
+#[test]
+fn test_chess() {
+	let mut pos = Layers::<Chess, 12>::new(Chess::EMPTY);
+	for x in 0..8 {
+		assert_eq!(pos.put(WHITE_PAWN, Chess::index_from_coords(x, 1)), None);
+		assert_eq!(pos.put(BLACK_PAWN, Chess::index_from_coords(x, 6)), None);
+	}
+	pos.put(WHITE_KING, Chess::index_from_coords(4, 0));
+	pos.put(BLACK_KING, Chess::index_from_coords(4, 7));
+	pos.put(BLACK_ROOK, Chess::index_from_coords(0, 7));
+	assert_eq!(pos.occupied().count(), 19);
+	assert_eq!(*pos.layer(WHITE_PAWN), Chess::row_mask(1));
+	assert_eq!(pos.piece_at(Chess::index_from_coords(4, 0)), Some(WHITE_KING));
+	assert_eq!(pos.piece_at(Chess::index_from_coords(4, 4)), None);
+
+	// e2-e4, then the rook takes a pawn
+	assert_eq!(pos.move_piece(Chess::index_from_coords(4, 1), Chess::index_from_coords(4, 3)), None);
+	assert_eq!(pos.move_piece(Chess::index_from_coords(0, 7), Chess::index_from_coords(0, 1)), Some(WHITE_PAWN));
+	assert_eq!(pos.layer(WHITE_PAWN).count(), 7);
+	assert_eq!(pos.piece_at(Chess::index_from_coords(0, 1)), Some(BLACK_ROOK));
+	assert_eq!(pos.occupied().count(), 18);
+	assert!(pos.is_consistent());
+
+	// Putting a piece replaces the previous one
+	assert_eq!(pos.put(WHITE_PAWN, Chess::index_from_coords(0, 1)), Some(BLACK_ROOK));
+	assert!(pos.layer(BLACK_ROOK).is_empty());
+	assert_eq!(pos.remove(Chess::index_from_coords(0, 1)), Some(WHITE_PAWN));
+	assert_eq!(pos.remove(Chess::index_from_coords(0, 1)), None);
+	assert_eq!(Layers::from_layers(*pos.layers()), pos);
+}
+
+/// Random operations against a reference board of layer indexes.
+fn check_random<B: Bitboard + std::fmt::Debug>(empty: B, seed: u64) {
+	let nb_squares = empty.width() as usize * empty.height() as usize;
+	let mut rng = Rng(seed);
+	let mut pos = Layers::<B, 3>::new(empty);
+	let mut reference = vec![None; nb_squares];
+	for _ in 0..2000 {
+		let index = (rng.next() % nb_squares as u64) as usize;
+		match rng.next() % 3 {
+			0 => {
+				let layer = (rng.next() % 3) as usize;
+				assert_eq!(pos.put(layer, index), reference[index]);
+				reference[index] = Some(layer);
+			}
+			1 => assert_eq!(pos.remove(index), reference[index].take()),
+			_ => {
+				let from = (0..nb_squares).find(|&i| reference[i].is_some());
+				if let Some(from) = from {
+					assert_eq!(pos.move_piece(from, index), if from == index { None } else { reference[index] });
+					reference[index] = reference[from].take().or(reference[index]);
+				}
+			}
+		}
+		assert!(pos.is_consistent());
+		assert_eq!(pos.occupied().count() as usize, reference.iter().flatten().count());
+	}
+	for (index, &layer) in reference.iter().enumerate() {
+		assert_eq!(pos.piece_at(index), layer);
+	}
+}
+
+#[test]
+fn test_random() {
+	check_random(Chess::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_random(LargeBitboard::empty(19, 19, false), 0x2545_F491_4F6C_DD1D);
+	check_random(LargeBitboard::empty(13, 11, true), 0xD1B5_4A32_D192_ED03);
+}
+
+#[test]
+fn test_display() {
+	let mut pos = Layers::<Bitboard4x3, 3>::new(Bitboard4x3::EMPTY);
+	pos.put(0, Bitboard4x3::index_from_coords(0, 0));
+	pos.put(1, Bitboard4x3::index_from_coords(3, 0));
+	pos.put(2, Bitboard4x3::index_from_coords(1, 2));
+	// Same layout as the bitboards
+	assert_eq!(pos.display_with(['#'; 3]).to_string(), pos.occupied().to_string());
+	assert_eq!(pos.to_string(), " 2 | .2..\n 1 | ....\n 0 | 0..1\n     0123\n");
+	assert_eq!(pos.display_with(['x', 'o', 'k']).to_string(), " 2 | .k..\n 1 | ....\n 0 | x..o\n     0123\n");
+
+	let mut chess = Layers::<Chess, 12>::new(Chess::EMPTY);
+	chess.put(BLACK_KING, Chess::index_from_coords(4, 7));
+	chess.put(WHITE_KING, Chess::index_from_coords(4, 0));
+	let board = chess.display_with(SYMBOLS).to_string();
+	assert!(board.contains(" 7 | ....k..."), "{board}");
+	assert!(board.contains(" 0 | ....K..."), "{board}");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "layers overlap")]
+fn test_overlapping_layers() {
+	let _ = Layers::from_layers([Chess::row_mask(0), Chess::col_mask(0)]);
+}
@@ -0,0 +1,83 @@
+use bitboard::Bitboard;
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard, bitboard_lit};
+
+#[bitboard(width=4,height=3)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard4x3;
+
+#[bitboard(width=4,height=3, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard4x3ColMajor;
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=17,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard17x8;
+
+const GLIDER: Bitboard4x3 = bitboard_lit!(Bitboard4x3,
+	".#..",
+	"..#.",
+	"###.",
+);
+
+const GLIDER_COL_MAJOR: Bitboard4x3ColMajor = bitboard_lit!(Bitboard4x3ColMajor,
+	".#..",
+	"..#.",
+	"###.",
+);
+
+const START: Bitboard8x8 = bitboard_lit!(Bitboard8x8,
+	"########",
+	"########",
+	"........",
+	"........",
+	"........",
+	"........",
+	"########",
+	"########",
+);
+
+#[test]
+fn test_literal() {
+	assert_eq!(GLIDER.count(), 5);
+	assert!(GLIDER.get(1, 2));
+	assert!(GLIDER.get(2, 1));
+	assert!(GLIDER.get(0, 0) && GLIDER.get(1, 0) && GLIDER.get(2, 0));
+	assert_eq!(GLIDER.to_string().parse::<Bitboard4x3>(), Ok(GLIDER));
+
+	assert_eq!(GLIDER_COL_MAJOR.count(), 5);
+	for x in 0..4 {
+		for y in 0..3 {
+			assert_eq!(GLIDER_COL_MAJOR.get(x, y), GLIDER.get(x, y), "({x}, {y})");
+		}
+	}
+	assert_eq!(GLIDER_COL_MAJOR.storage(), 0b1110_1001);
+
+	assert_eq!(START, Bitboard8x8::from_storage(0xFFFF_0000_0000_FFFF));
+}
+
+#[test]
+fn test_literal_array_storage() {
+	let b = bitboard_lit!(Bitboard17x8,
+		"#................",
+		".................",
+		".................",
+		".................",
+		".................",
+		".................",
+		".................",
+		"................#",
+	);
+	assert_eq!(b.count(), 2);
+	assert!(b.get(0, 7));
+	assert!(b.get(16, 0));
+}
+
+/// Rows that do not match the type fail to compile, with the error on the offending row.
+#[test]
+fn test_literal_errors() {
+	trybuild::TestCases::new().compile_fail("tests/ui/literal_*.rs");
+}
@@ -0,0 +1,110 @@
+use bitboard::{Bitboard, parse::{Grid, ParseError, ParseErrorKind}, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=12,height=11)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard12x11;
+
+#[bitboard(width=17,height=8, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard17x8;
+
+#[bitboard(width=4,height=3)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard4x3;
+
+fn error(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+	ParseError { line, column, kind }
+}
+
+#[test]
+fn test_display_roundtrip() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for _ in 0..8 {
+		let b = random_board(Bitboard8x8::EMPTY, 3, &mut rng);
+		assert_eq!(b.to_string().parse::<Bitboard8x8>(), Ok(b));
+
+		let b = random_board(Bitboard12x11::EMPTY, 3, &mut rng);
+		assert_eq!(b.to_string().parse::<Bitboard12x11>(), Ok(b));
+
+		let b = random_board(Bitboard17x8::EMPTY, 3, &mut rng);
+		assert_eq!(b.to_string().parse::<Bitboard17x8>(), Ok(b));
+	}
+}
+
+#[test]
+fn test_runtime_roundtrip() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	for col_major in [false, true] {
+		let b = random_board(SmallBitboard::empty(7, 6, col_major), 3, &mut rng);
+		assert_eq!(SmallBitboard::parse(&b.to_string(), col_major), Ok(b));
+
+		let b = random_board(MediumBitboard::empty(11, 10, col_major), 3, &mut rng);
+		assert_eq!(MediumBitboard::parse(&b.to_string(), col_major), Ok(b));
+
+		let b = random_board(LargeBitboard::empty(19, 19, col_major), 3, &mut rng);
+		assert_eq!(LargeBitboard::parse(&b.to_string(), col_major), Ok(b.clone()));
+
+		let parsed = DynBitboard::parse(&b.to_string(), col_major).unwrap();
+		assert_eq!(parsed.shape(), b.shape());
+		assert_eq!(parsed.count(), b.count());
+	}
+}
+
+#[test]
+fn test_compact_form() {
+	let b: Bitboard4x3 = "
+		#...
+		.#..
+		..##
+	".parse().unwrap();
+	let mut expected = Bitboard4x3::EMPTY;
+	expected.set(0, 2);
+	expected.set(1, 1);
+	expected.set(2, 0);
+	expected.set(3, 0);
+	assert_eq!(b, expected);
+
+	let b = SmallBitboard::parse("#..\n...\n..#\n...\n", true).unwrap();
+	assert_eq!((b.width(), b.height()), (3, 4));
+	assert!(b.get(0, 3));
+	assert!(b.get(2, 1));
+	assert_eq!(b.count(), 2);
+
+	let grid = Grid::parse("  ##.\n  ...").unwrap();
+	assert_eq!((grid.width(), grid.height()), (3, 2));
+	assert_eq!(grid.iter_set().collect::<Vec<_>>(), vec![(0, 1), (1, 1)]);
+}
+
+#[test]
+fn test_labeled_without_column_labels() {
+	let b: Bitboard4x3 = " 2 | #...\n 1 | ....\n 0 | ...#\n".parse().unwrap();
+	assert!(b.get(0, 2));
+	assert!(b.get(3, 0));
+	assert_eq!(b.count(), 2);
+}
+
+#[test]
+fn test_errors() {
+	assert_eq!(Grid::parse("\n  \n"), Err(error(2, 1, ParseErrorKind::Empty)));
+	assert_eq!(Grid::parse("#..\n.x.\n"), Err(error(2, 2, ParseErrorKind::UnexpectedChar('x'))));
+	assert_eq!(Grid::parse("#..\n....\n"), Err(error(2, 4, ParseErrorKind::RowLength { expected: 3, found: 4 })));
+	assert_eq!(Grid::parse("#..\n..\n"), Err(error(2, 3, ParseErrorKind::RowLength { expected: 3, found: 2 })));
+	assert_eq!(Grid::parse(" 1 | #..\n...\n"), Err(error(2, 1, ParseErrorKind::MixedFormat)));
+	assert_eq!(Grid::parse(" 1 | #..\n 2 | ...\n"), Err(error(2, 2, ParseErrorKind::RowLabel { expected: 0 })));
+	assert_eq!(Grid::parse(" 1 | #..\n 0 | ...\n     012\n 0 | ...\n"), Err(error(4, 2, ParseErrorKind::MixedFormat)));
+	assert_eq!(SmallBitboard::parse(&"#########\n".repeat(9), false), Err(error(1, 1, ParseErrorKind::TooLarge { width: 9, height: 9 })));
+
+	let err = "#...\n....\n".parse::<Bitboard4x3>().unwrap_err();
+	assert_eq!(err, error(1, 1, ParseErrorKind::Shape { expected: (4, 3), found: (4, 2) }));
+	assert_eq!(err.to_string(), "line 1, column 1: grid is 4x2, expected 4x3");
+	let err = "#...\n.?..\n....\n".parse::<Bitboard4x3>().unwrap_err();
+	assert_eq!(err.to_string(), "line 2, column 2: unexpected character '?', expected '#' or '.'");
+}
@@ -0,0 +1,261 @@
+use bitboard::runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard};
+
+mod common;
+
+fn from_indices<B: RuntimeBitboard>(w: u8, h: u8, col_major: bool, indices: &[u16]) -> B {
+	let mut bb = B::empty(w, h, col_major);
+	for &i in indices {
+		bb.set_at_index(i as usize);
+	}
+	bb
+}
+
+mod tests_7x7_col {
+	use super::*;
+
+	fn from(indices: &[u16]) -> SmallBitboard {
+		from_indices(7, 7, true, indices)
+	}
+
+	#[test]
+	fn test_horizontal_alignment() {
+		let bb = from(&[0, 7, 14, 21]);
+		assert!(bb.has_n_aligned_horizontal(4), "Horizontal alignment not detected");
+		assert!(bb.has_aligned_horizontal::<4>(), "Horizontal alignment not detected");
+
+		let bb = from(&[21+6, 28+6, 35+6, 42+6]);
+		assert!(bb.has_n_aligned_horizontal(4), "Horizontal alignment not detected");
+		assert!(bb.has_aligned_horizontal::<4>(), "Horizontal alignment not detected");
+
+		let bleed = from(&[35, 42, 0, 7]);
+		assert!(!bleed.has_n_aligned_horizontal(4), "Horizontal bleeding detected!");
+		assert!(!bleed.has_aligned_horizontal::<4>(), "Horizontal bleeding detected!");
+	}
+
+	#[test]
+	fn test_vertical_alignment() {
+		let bb = from(&[0, 1, 2, 3]);
+		assert!(bb.has_n_aligned_vertical(4), "Vertical alignment not detected");
+		assert!(bb.has_aligned_vertical::<4>(), "Vertical alignment not detected");
+
+		let bleed = from(&[5, 6, 7, 8]);
+		assert!(!bleed.has_n_aligned_vertical(4), "Vertical bleeding detected!");
+		assert!(!bleed.has_aligned_vertical::<4>(), "Vertical bleeding detected!");
+	}
+
+	#[test]
+	fn test_diagonal_alignment() {
+		let bb = from(&[0, 8, 16, 24]);
+		assert!(bb.has_n_aligned_diag_inc(4), "Diagonal INC alignment not detected");
+		assert!(bb.has_aligned_diag_inc::<4>(), "Diagonal INC alignment not detected");
+
+		let bb = from(&[3, 9, 15, 21]);
+		assert!(bb.has_n_aligned_diag_dec(4), "Diagonal DEC alignment not detected");
+		assert!(!bb.has_n_aligned_diag_inc(4), "Diagonal INC alignment incorrectly detected");
+		assert!(bb.has_aligned_diag_dec::<4>(), "Diagonal DEC alignment not detected");
+		assert!(!bb.has_aligned_diag_inc::<4>(), "Diagonal INC alignment incorrectly detected");
+	}
+
+	#[test]
+	fn test_n_parameter() {
+		let bb = from(&[0, 1, 2, 3, 4]);
+		assert!(bb.has_n_aligned(5));
+		assert!(!bb.has_n_aligned(6));
+		assert!(bb.has_aligned::<2>());
+		assert_eq!(bb.count_aligned::<2>(), 4);
+		assert_eq!(bb.count_aligned::<3>(), 3);
+		assert_eq!(bb.count_aligned::<4>(), 2);
+		assert_eq!(bb.count_aligned::<5>(), 1);
+		assert!(!bb.has_aligned::<6>());
+		assert_eq!(bb.count_aligned::<6>(), 0);
+	}
+}
+
+mod tests_8x8 {
+	use super::*;
+
+	fn from(indices: &[u16]) -> MediumBitboard {
+		from_indices(8, 8, false, indices)
+	}
+
+	#[test]
+	fn test_8x8_vertical() {
+		let bb = from(&[0, 8, 16, 24, 32]);
+		assert!(bb.has_n_aligned_vertical(5), "Vertical 5-align not detected");
+
+		let bb = from(&[24+7, 32+7, 40+7, 48+7, 56+7]);
+		assert!(bb.has_n_aligned(5), "Vertical 5-align not detected");
+
+		let bleed = from(&[40, 48, 56, 1]);
+		assert!(!bleed.has_n_aligned(4), "Vertical bleeding");
+	}
+
+	#[test]
+	fn test_8x8_horizontal() {
+		let bb = from(&[10, 11, 12, 13]);
+		assert!(bb.has_n_aligned_horizontal(4), "Horizontal 4-align not detected");
+
+		let bleed = from(&[6, 7, 8, 9]);
+		assert!(!bleed.has_n_aligned_horizontal(4), "Bleeding Horizontal");
+	}
+
+	#[test]
+	fn test_8x8_diagonals() {
+		let bb_inc = from(&[0, 9, 18, 27]);
+		assert!(bb_inc.has_n_aligned_diag_inc(4), "Diagonal INC (/) not detected");
+		assert!(!bb_inc.has_n_aligned_diag_dec(4), "Diagonal DEC (\\) incorrectly detected");
+
+		let bb_dec = from(&[7, 14, 21, 28]);
+		assert!(bb_dec.has_n_aligned_diag_dec(4), "Diagonal DEC (\\) not detected");
+		assert!(!bb_dec.has_n_aligned_diag_inc(4), "Diagonal INC (/) incorrectly detected");
+	}
+
+	#[test]
+	fn test_8x8_edge_cases() {
+		let bb = from(&[0, 1, 8, 9]);
+		assert!(!bb.has_n_aligned(4), "4-alignment detected on a 2x2 square");
+
+		let full_col = from(&[0, 8, 16, 24, 32, 40, 48, 56]);
+		assert!(full_col.has_n_aligned(8));
+		assert!(!full_col.has_n_aligned(9));
+		assert!(full_col.has_aligned::<8>());
+		assert!(!full_col.has_aligned::<9>());
+	}
+}
+
+mod tests_goban {
+	use super::*;
+
+	fn from(col_major: bool, indices: &[u16]) -> LargeBitboard {
+		from_indices(19, 19, col_major, indices)
+	}
+
+	#[test]
+	fn test_goban_vertical() {
+		let bb = from(false, &[9, 9+19, 9+19*2, 9+19*3, 9+19*4]);
+		assert!(bb.has_n_aligned_vertical(5), "Vertical 5-align not detected");
+
+		let bb = from(true, &[100, 101, 102, 103, 104]);
+		assert!(bb.has_n_aligned_vertical(5), "Vertical 5-align not detected");
+
+		let bleed = from(false, &[360, 360-19, 360-2*19, 18]);
+		assert!(!bleed.has_n_aligned(4), "Vertical bleeding");
+	}
+
+	#[test]
+	fn test_goban_horizontal() {
+		let bb = from(false, &[10, 11, 12, 13]);
+		assert!(bb.has_n_aligned_horizontal(4), "Horizontal 4-align not detected");
+
+		let bleed = from(false, &[16, 17, 18, 19]);
+		assert!(!bleed.has_n_aligned_horizontal(4), "Bleeding horizontal not detected");
+
+		let bleed = from(true, &[16, 17, 18, 19]);
+		assert!(!bleed.has_n_aligned(4), "Bleeding vertical not detected");
+	}
+
+	#[test]
+	fn test_goban_diagonals() {
+		let bb_inc = from(false, &[0, 20, 40, 60]);
+		assert!(bb_inc.has_n_aligned_diag_inc(4), "Diagonal INC (/) not detected");
+		assert!(!bb_inc.has_n_aligned_diag_dec(4), "Diagonal DEC (\\) incorrectly detected");
+
+		let bb_dec = from(false, &[7, 7+18, 7+2*18, 7+3*18]);
+		assert!(bb_dec.has_n_aligned_diag_dec(4), "Diagonal DEC (\\) not detected");
+		assert!(!bb_dec.has_n_aligned_diag_inc(4), "Diagonal INC (/) incorrectly detected");
+	}
+
+	#[test]
+	fn test_goban_edge_cases() {
+		let bb = from(false, &[0, 1, 19, 20]);
+		assert!(!bb.has_n_aligned(4), "4-alignment detected on a 2x2 square");
+
+		for col_major in [false, true] {
+			let full_col = LargeBitboard::west_border(19, 19, col_major);
+			assert!(full_col.has_n_aligned(19));
+			assert!(!full_col.has_n_aligned(20));
+			let full = LargeBitboard::full(19, 19, col_major);
+			assert!(full.has_aligned::<19>());
+			assert!(!full.has_aligned::<20>());
+			let empty = LargeBitboard::empty(19, 19, col_major);
+			assert!(empty.has_aligned::<0>());
+			assert!(!empty.has_aligned::<1>());
+		}
+	}
+}
+
+mod tests_random {
+	use super::*;
+	use crate::common::{Rng, random_board_where};
+
+	const DIRECTIONS: [(i16, i16); 4] = [(1, 0), (0, 1), (1, -1), (1, 1)];
+
+	fn slow_count(b: &impl RuntimeBitboard, n: i16, (dx, dy): (i16, i16)) -> u32 {
+		let (w, h) = (b.width() as i16, b.height() as i16);
+		let mut count = 0;
+		for x in 0..w {
+			for y in 0..h {
+				let run = (0..n).all(|k| {
+					let (nx, ny) = (x + k * dx, y + k * dy);
+					nx >= 0 && ny >= 0 && nx < w && ny < h && b.get(nx as u8, ny as u8)
+				});
+				count += run as u32;
+			}
+		}
+		count
+	}
+
+	fn check<B: RuntimeBitboard + std::fmt::Display>(shapes: &[(u8, u8)]) {
+		let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+		for &(w, h) in shapes {
+			for col_major in [false, true] {
+				for _ in 0..16 {
+					let b = random_board_where(B::empty(w, h, col_major), &mut rng, |r| !r.is_multiple_of(3));
+					let counts = [
+						b.count_aligned_horizontal::<3>(),
+						b.count_aligned_vertical::<3>(),
+						b.count_aligned_diag_dec::<3>(),
+						b.count_aligned_diag_inc::<3>(),
+					];
+					for (count, dir) in counts.into_iter().zip(DIRECTIONS) {
+						assert_eq!(count, slow_count(&b, 3, dir), "{dir:?} {w}x{h} col_major={col_major}\n{b}");
+					}
+					let total: u32 = DIRECTIONS.iter().map(|&dir| slow_count(&b, 5, dir)).sum();
+					assert_eq!(b.count_aligned::<5>(), total, "{w}x{h} col_major={col_major}\n{b}");
+					assert_eq!(b.has_aligned::<5>(), total > 0);
+
+					// pattern "X.X": bit 0 and bit 2
+					let pattern = b.detect_pattern_h(0b101);
+					for x in 0..w {
+						for y in 0..h {
+							let expected = x >= 2 && b.get(x, y) && b.get(x - 2, y);
+							assert_eq!(pattern.get(x, y), expected, "detect_pattern_h ({x}, {y}) {w}x{h} col_major={col_major}\n{b}");
+						}
+					}
+					let pattern = b.detect_pattern_diag_dec(0b11);
+					for x in 0..w {
+						for y in 0..h {
+							let expected = x >= 1 && y + 1 < h && b.get(x, y) && b.get(x - 1, y + 1);
+							assert_eq!(pattern.get(x, y), expected, "detect_pattern_diag_dec ({x}, {y}) {w}x{h} col_major={col_major}\n{b}");
+						}
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_small_random() {
+		check::<SmallBitboard>(&[(8, 8), (7, 6), (4, 9)]);
+	}
+
+	#[test]
+	fn test_medium_random() {
+		check::<MediumBitboard>(&[(11, 11), (16, 7), (6, 20)]);
+	}
+
+	#[test]
+	fn test_large_random() {
+		check::<LargeBitboard>(&[(19, 19), (25, 9), (13, 17)]);
+	}
+}
@@ -0,0 +1,57 @@
+use bitboard::runtime::{BitboardShape, RuntimeBitboard, ShapeError, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard};
+
+fn check<B: RuntimeBitboard + std::fmt::Debug>(w: u8, h: u8) {
+	for col_major in [false, true] {
+		let shape = BitboardShape::new(w, h, col_major);
+		let a = B::west_border(w, h, col_major);
+		let b = B::south_border(w, h, col_major);
+		assert_eq!(a.shape(), shape);
+		assert_eq!(a.full_with_same_shape().shape(), shape);
+
+		assert_eq!(a.clone().try_and(b.clone()), Ok(a.clone() & b.clone()));
+		assert_eq!(a.clone().try_or(b.clone()), Ok(a.clone() | b.clone()));
+		assert_eq!(a.clone().try_xor(b.clone()), Ok(a.clone() ^ b.clone()));
+		let andnot = a.clone().try_andnot(b.clone()).unwrap();
+		assert_eq!(andnot.count(), h as u32 - 1);
+		assert!(!andnot.get(0, 0));
+
+		let other = B::full(w, h, !col_major);
+		let error = ShapeError { expected: shape, found: other.shape() };
+		assert_eq!(a.check_shape(&other), Err(error));
+		assert_eq!(a.clone().try_and(other.clone()), Err(error));
+		assert_eq!(a.clone().try_or(other.clone()), Err(error));
+		assert_eq!(a.clone().try_xor(other.clone()), Err(error));
+		assert_eq!(a.clone().try_andnot(other), Err(error));
+
+		let other = B::full(w - 1, h, col_major);
+		assert_eq!(a.clone().try_and(other.clone()).unwrap_err().found, BitboardShape::new(w - 1, h, col_major));
+	}
+}
+
+#[test]
+fn test_small_shape() {
+	check::<SmallBitboard>(8, 8);
+}
+
+#[test]
+fn test_medium_shape() {
+	check::<MediumBitboard>(11, 10);
+}
+
+#[test]
+fn test_large_shape() {
+	check::<LargeBitboard>(19, 19);
+}
+
+#[test]
+fn test_shape_error_display() {
+	let error = ShapeError { expected: BitboardShape::new(8, 8, false), found: BitboardShape::new(7, 7, true) };
+	assert_eq!(error.to_string(), "bitboard shape mismatch: expected 8x8, found 7x7 (column-major)");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "bitboard shape mismatch")]
+fn test_operator_debug_assert() {
+	let _ = SmallBitboard::full(8, 8, false) & SmallBitboard::full(7, 9, false);
+}
@@ -0,0 +1,110 @@
+use bitboard::{Bitboard, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+const SHAPES: [(u8, u8); 6] = [(8, 8), (7, 5), (5, 9), (11, 10), (12, 12), (23, 9)];
+
+fn slow_shifted<B: RuntimeBitboard>(b: &B, dx: i16, dy: i16) -> B {
+	let mut res = b.empty_with_same_shape();
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			let (nx, ny) = (x as i16 + dx, y as i16 + dy);
+			if b.get(x, y) && nx >= 0 && ny >= 0 && nx < b.width() as i16 && ny < b.height() as i16 {
+				res.set(nx as u8, ny as u8);
+			}
+		}
+	}
+	res
+}
+
+fn slow_dilated<B: RuntimeBitboard>(b: &B) -> B {
+	let mut res = b.clone();
+	for dx in -1..=1 {
+		for dy in -1..=1 {
+			res |= slow_shifted(b, dx, dy);
+		}
+	}
+	res
+}
+
+fn check_shapes<B: RuntimeBitboard + std::fmt::Display + std::fmt::Debug>(max_bits: usize) {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	for (w, h) in SHAPES {
+		if w as usize * h as usize > max_bits {
+			continue;
+		}
+		for col_major in [false, true] {
+			for _ in 0..8 {
+				let b = random_board(B::empty(w, h, col_major), 3, &mut rng);
+				assert_eq!(b.shifted_n(), slow_shifted(&b, 0, 1), "shifted_n {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_s(), slow_shifted(&b, 0, -1), "shifted_s {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_e(), slow_shifted(&b, 1, 0), "shifted_e {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_w(), slow_shifted(&b, -1, 0), "shifted_w {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_ne(), slow_shifted(&b, 1, 1), "shifted_ne {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_nw(), slow_shifted(&b, -1, 1), "shifted_nw {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_se(), slow_shifted(&b, 1, -1), "shifted_se {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.shifted_sw(), slow_shifted(&b, -1, -1), "shifted_sw {w}x{h} col_major={col_major}\n{b}");
+				for (dx, dy) in [(2, 3), (-3, 1), (4, -2), (-1, -4), (0, 5), (6, 0), (-20, 0), (0, 12)] {
+					assert_eq!(b.shifted(dx, dy), slow_shifted(&b, dx, dy), "shifted({dx}, {dy}) {w}x{h} col_major={col_major}\n{b}");
+				}
+
+				let dilated = slow_dilated(&b);
+				assert_eq!(b.dilated(), dilated, "dilated {w}x{h} col_major={col_major}\n{b}");
+				assert_eq!(b.neighbors_of_any(), dilated & !b.clone(), "neighbors_of_any {w}x{h} col_major={col_major}\n{b}");
+				let inverse = b.full_with_same_shape() & !b.clone();
+				let eroded = b.full_with_same_shape() & !slow_dilated(&inverse);
+				let border_free = b.full_with_same_shape() & !B::borders(w, h, col_major);
+				assert_eq!(b.eroded() & border_free.clone(), eroded & border_free, "eroded {w}x{h} col_major={col_major}\n{b}");
+			}
+		}
+	}
+}
+
+#[test]
+fn test_small_shifts() {
+	check_shapes::<SmallBitboard>(64);
+}
+
+#[test]
+fn test_medium_shifts() {
+	check_shapes::<MediumBitboard>(128);
+}
+
+#[test]
+fn test_large_shifts() {
+	check_shapes::<LargeBitboard>(usize::MAX);
+}
+
+#[test]
+fn test_no_wrap_masks() {
+	let b = SmallBitboard::empty(8, 8, false);
+	assert_eq!(*b.no_wrap_n_mask().storage(), Bitboard8x8::NO_WRAP_N_MASK.storage());
+	assert_eq!(*b.no_wrap_s_mask().storage(), Bitboard8x8::NO_WRAP_S_MASK.storage());
+	assert_eq!(*b.no_wrap_e_mask().storage(), Bitboard8x8::NO_WRAP_E_MASK.storage());
+	assert_eq!(*b.no_wrap_w_mask().storage(), Bitboard8x8::NO_WRAP_W_MASK.storage());
+	assert_eq!(*b.no_wrap_sw_mask().storage(), Bitboard8x8::NO_WRAP_SW_MASK.storage());
+	assert_eq!(*b.no_wrap_ne_mask().storage(), Bitboard8x8::NO_WRAP_NE_MASK.storage());
+
+	let b = MediumBitboard::empty(11, 10, true);
+	assert_eq!(b.no_wrap_n_mask().count(), 99);
+	assert!(!b.no_wrap_n_mask().get(3, 9));
+	assert!(b.no_wrap_n_mask().get(3, 8));
+	assert!(!b.no_wrap_e_mask().get(10, 4));
+	assert!(b.no_wrap_e_mask().get(9, 4));
+}
+
+/// Offsets at the ends of the `i16` range empty the board instead of overflowing.
+#[test]
+fn test_extreme_shifts() {
+	let full = LargeBitboard::full(255, 255, false);
+	for (dx, dy) in [(i16::MIN, 0), (0, i16::MIN), (i16::MAX, 0), (0, i16::MAX), (i16::MIN, i16::MIN)] {
+		assert!(full.shifted(dx, dy).is_empty(), "({dx}, {dy})");
+		assert!(SmallBitboard::full(8, 8, true).shifted(dx, dy).is_empty(), "({dx}, {dy})");
+	}
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "serde")]
+
+use bitboard::{Bitboard, runtime::{BitboardShape, RuntimeBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=7,height=6)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard7x6;
+
+#[bitboard(width=11,height=11)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard11x11;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard19x19;
+
+#[test]
+fn test_static_roundtrip() {
+	let b = Bitboard8x8::BORDER;
+	let json = serde_json::to_string(&b).unwrap();
+	assert_eq!(json, Bitboard8x8::BORDER.storage().to_string());
+	assert_eq!(serde_json::from_str::<Bitboard8x8>(&json).unwrap(), b);
+
+	let b = Bitboard11x11::CENTER;
+	let json = serde_json::to_string(&b).unwrap();
+	assert_eq!(serde_json::from_str::<Bitboard11x11>(&json).unwrap(), b);
+
+	let mut b = Bitboard19x19::EMPTY;
+	b.set(3, 3);
+	b.set(18, 18);
+	let json = serde_json::to_string(&b).unwrap();
+	assert!(json.starts_with('['));
+	assert_eq!(serde_json::from_str::<Bitboard19x19>(&json).unwrap(), b);
+}
+
+#[test]
+fn test_static_out_of_range() {
+	let err = serde_json::from_str::<Bitboard7x6>(&(1u64 << 42).to_string()).unwrap_err();
+	assert!(err.to_string().contains("bits set outside of the 7x6 board"), "{err}");
+	assert!(serde_json::from_str::<Bitboard7x6>(&(1u64 << 41).to_string()).is_ok());
+
+	let mut words = [0u64; 6];
+	words[5] = 1 << 41;
+	let err = serde_json::from_str::<Bitboard19x19>(&serde_json::to_string(&words).unwrap()).unwrap_err();
+	assert!(err.to_string().contains("bits set outside of the 19x19 board"), "{err}");
+	assert!(serde_json::from_str::<Bitboard19x19>("[0, 0, 0]").is_err());
+}
+
+#[test]
+fn test_runtime_roundtrip() {
+	let b = SmallBitboard::borders(7, 5, true);
+	let json = serde_json::to_string(&b).unwrap();
+	assert_eq!(json, format!(r#"{{"shape":{{"w":7,"h":5,"col_major":true}},"bits":{}}}"#, b.storage()));
+	assert_eq!(serde_json::from_str::<SmallBitboard>(&json).unwrap(), b);
+
+	let b = MediumBitboard::row_mask(11, 10, false, 9) | MediumBitboard::col_mask(11, 10, false, 3);
+	let json = serde_json::to_string(&b).unwrap();
+	assert_eq!(serde_json::from_str::<MediumBitboard>(&json).unwrap(), b);
+
+	let b = LargeBitboard::borders(19, 19, false);
+	let json = serde_json::to_string(&b).unwrap();
+	assert_eq!(serde_json::from_str::<LargeBitboard>(&json).unwrap(), b);
+
+	let shape = BitboardShape::new(9, 9, false);
+	assert_eq!(serde_json::from_str::<BitboardShape>(&serde_json::to_string(&shape).unwrap()).unwrap(), shape);
+}
+
+#[test]
+fn test_runtime_validation() {
+	let err = serde_json::from_str::<SmallBitboard>(r#"{"shape":{"w":7,"h":5,"col_major":false},"bits":34359738368}"#).unwrap_err();
+	assert!(err.to_string().contains("bits set outside of the 7x5 board"), "{err}");
+	let err = serde_json::from_str::<SmallBitboard>(r#"{"shape":{"w":9,"h":9,"col_major":false},"bits":0}"#).unwrap_err();
+	assert!(err.to_string().contains("does not fit in a SmallBitboard"), "{err}");
+	let err = serde_json::from_str::<MediumBitboard>(r#"{"shape":{"w":0,"h":9,"col_major":false},"bits":0}"#).unwrap_err();
+	assert!(err.to_string().contains("invalid bitboard shape"), "{err}");
+	let err = serde_json::from_str::<LargeBitboard>(r#"{"shape":{"w":19,"h":19,"col_major":false},"bits":[0,0]}"#).unwrap_err();
+	assert!(err.to_string().contains("6 words"), "{err}");
+	let err = serde_json::from_str::<LargeBitboard>(r#"{"shape":{"w":19,"h":19,"col_major":false},"bits":[0,0,0,0,0,2199023255552]}"#).unwrap_err();
+	assert!(err.to_string().contains("bits set outside"), "{err}");
+}
@@ -286,3 +286,32 @@ fn test_bitboard10x10() {
 		}
 	}
 }
+
+#[test]
+fn test_neighbors_of_any() {
+	// The stones themselves are not their own neighbors
+	const CENTER: Bitboard8x8 = Bitboard8x8::from_coords(3, 4).neighbors_of_any();
+	assert_eq!(CENTER.count(), 8);
+	assert!(!CENTER.get(3, 4));
+	for (x, y) in [(2, 3), (3, 3), (4, 3), (2, 4), (4, 4), (2, 5), (3, 5), (4, 5)] {
+		assert!(CENTER.get(x, y), "({x}, {y})\n{CENTER}");
+	}
+
+	// Nothing past the board, where the stone is shifted by shifted_n
+	let nei = Bitboard10x10::from_coords(9, 9).neighbors_of_any();
+	assert_eq!(nei.count(), 3);
+	assert!(nei.get(8, 9) && nei.get(8, 8) && nei.get(9, 8));
+	for (x, y) in [(0, 0), (11, 0), (0, 11), (11, 11)] {
+		assert_eq!(Bitboard12x12::from_coords(x, y).neighbors_of_any().count(), 3, "({x}, {y})");
+	}
+
+	for x in 0..7 {
+		for y in 0..7 {
+			let mut b = Bitboard7x7::EVEN_SQUARES;
+			b.set(x, y);
+			let nei = b.neighbors_of_any();
+			assert_eq!(nei, b.dilated() & b.flipped(), "({x}, {y})\n{b}");
+			assert!((nei & b).is_empty());
+		}
+	}
+}
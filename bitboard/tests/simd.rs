@@ -0,0 +1,142 @@
+use bitboard::{Bitboard, runtime::{RuntimeBitboard, large_bitboard::LargeBitboard}, simd};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+#[bitboard(width=64,height=40, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard64x40ColMajor;
+
+impl Rng {
+	fn words(&mut self, len: usize) -> Vec<u64> {
+		(0..len).map(|_| self.next() & self.next()).collect()
+	}
+}
+
+type BinaryKernel = fn(&mut [u64], &[u64]);
+
+/// The kernels of the build, SIMD with the `simd` feature, give the same words as the scalar ones.
+#[test]
+fn test_kernels() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for len in 0..14 {
+		let (a, b) = (rng.words(len), rng.words(len));
+		let binary: [(BinaryKernel, BinaryKernel); 3] = [
+			(simd::and_assign, simd::scalar::and_assign),
+			(simd::or_assign, simd::scalar::or_assign),
+			(simd::xor_assign, simd::scalar::xor_assign),
+		];
+		for (kernel, reference) in binary {
+			let (mut res, mut expected) = (a.clone(), a.clone());
+			kernel(&mut res, &b);
+			reference(&mut expected, &b);
+			assert_eq!(res, expected);
+		}
+		let (mut res, mut expected) = (a.clone(), a.clone());
+		simd::not_assign(&mut res);
+		simd::scalar::not_assign(&mut expected);
+		assert_eq!(res, expected);
+
+		for rhs in 0..len * 64 + 3 {
+			let (mut res, mut expected) = (a.clone(), a.clone());
+			simd::shl_assign(&mut res, rhs);
+			simd::scalar::shl_assign(&mut expected, rhs);
+			assert_eq!(res, expected, "{len} words << {rhs}");
+			let (mut res, mut expected) = (a.clone(), a.clone());
+			simd::shr_assign(&mut res, rhs);
+			simd::scalar::shr_assign(&mut expected, rhs);
+			assert_eq!(res, expected, "{len} words >> {rhs}");
+		}
+
+		assert_eq!(simd::count_ones(&a), a.iter().map(|w| w.count_ones()).sum::<u32>());
+		assert_eq!(simd::is_zero(&a), a.iter().all(|&w| w == 0));
+		assert!(simd::is_zero(&vec![0; len]));
+		if len > 0 {
+			let mut one = vec![0; len];
+			one[len - 1] = 1 << 63;
+			assert!(!simd::is_zero(&one));
+		}
+	}
+}
+
+/// The scalar shifts against `u128` shifts.
+#[test]
+fn test_scalar_shifts() {
+	let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+	for _ in 0..64 {
+		let words = rng.words(2);
+		let value = words[0] as u128 | (words[1] as u128) << 64;
+		for rhs in 0..128 {
+			let mut res = words.clone();
+			simd::scalar::shl_assign(&mut res, rhs);
+			assert_eq!(res, [(value << rhs) as u64, ((value << rhs) >> 64) as u64]);
+			let mut res = words.clone();
+			simd::scalar::shr_assign(&mut res, rhs);
+			assert_eq!(res, [(value >> rhs) as u64, ((value >> rhs) >> 64) as u64]);
+		}
+	}
+}
+
+/// The operators of the array storage against its `const fn`, which stay scalar.
+fn check_array_ops<B: Bitboard<Storage = [u64; N]> + std::fmt::Debug, const N: usize>(empty: B, const_ops: impl Fn(&B, &B, usize) -> [B; 6], seed: u64) {
+	let mut rng = Rng(seed);
+	for _ in 0..32 {
+		let (mut a, mut b) = (empty.clone(), empty.clone());
+		a.storage_mut().copy_from_slice(&rng.words(N));
+		b.storage_mut().copy_from_slice(&rng.words(N));
+		let rhs = (rng.next() % (N as u64 * 64)) as usize;
+		let [and, or, xor, not, shl, shr] = const_ops(&a, &b, rhs);
+		assert_eq!(a.clone() & b.clone(), and);
+		assert_eq!(a.clone() | b.clone(), or);
+		assert_eq!(a.clone() ^ b.clone(), xor);
+		assert_eq!(!a.clone(), not);
+		assert_eq!(a.clone() << rhs, shl);
+		assert_eq!(a.clone() >> rhs, shr);
+		let mut c = a.clone();
+		c ^= b.clone();
+		c <<= rhs;
+		assert_eq!(c, (a.clone() ^ b.clone()) << rhs);
+		assert_eq!(a.count(), a.storage().iter().map(|w| w.count_ones()).sum::<u32>());
+		assert!(!a.is_empty() && empty.is_empty());
+	}
+}
+
+#[test]
+fn test_array_storage() {
+	check_array_ops(Go19::EMPTY, |a, b, rhs| [a.and_const(b), a.or_const(b), a.xor_const(b), a.not_const(), a.shl_const(rhs), a.shr_const(rhs)], 0xD1B5_4A32_D192_ED03);
+	check_array_ops(Bitboard64x40ColMajor::EMPTY, |a, b, rhs| [a.and_const(b), a.or_const(b), a.xor_const(b), a.not_const(), a.shl_const(rhs), a.shr_const(rhs)], 0x94D0_49BB_1331_11EB);
+}
+
+#[test]
+fn test_large_bitboard() {
+	let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+	for (w, h) in [(19, 19), (64, 40), (33, 7)] {
+		let a = random_board(LargeBitboard::empty(w, h, false), 3, &mut rng);
+		let b = random_board(LargeBitboard::empty(w, h, false), 2, &mut rng);
+		let words = |b: &LargeBitboard| b.storage().to_vec();
+		let reference = |op: fn(&mut [u64], &[u64])| {
+			let mut res = words(&a);
+			op(&mut res, &words(&b));
+			res
+		};
+		assert_eq!(words(&(a.clone() & b.clone())), reference(simd::scalar::and_assign));
+		assert_eq!(words(&(a.clone() | b.clone())), reference(simd::scalar::or_assign));
+		assert_eq!(words(&(a.clone() ^ b.clone())), reference(simd::scalar::xor_assign));
+		for rhs in [0, 1, 63, 64, 65, 200, w as usize * h as usize] {
+			let mut expected = words(&a);
+			simd::scalar::shl_assign(&mut expected, rhs);
+			assert_eq!(words(&(a.clone() << rhs)), expected);
+			let mut expected = words(&a);
+			simd::scalar::shr_assign(&mut expected, rhs);
+			assert_eq!(words(&(a.clone() >> rhs)), expected);
+		}
+		let count = (0..w).flat_map(|x| (0..h).map(move |y| (x, y))).filter(|&(x, y)| a.get(x, y)).count();
+		assert_eq!(a.count() as usize, count);
+		assert!(!a.is_empty() && (a.clone() ^ a.clone()).is_empty());
+	}
+}
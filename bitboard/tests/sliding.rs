@@ -0,0 +1,210 @@
+use bitboard::{BitIter, Bitboard, WordStorage, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}, sliding::{BISHOP_DIRECTIONS, OccludedFill, ROOK_DIRECTIONS, SlidingTable, attacks_of_all, sliding_attacks}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=8,height=8, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8ColMajor;
+
+#[bitboard(width=6,height=5)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard6x5;
+
+#[bitboard(width=17,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard17x8;
+
+#[bitboard(width=16,height=16)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard16x16;
+
+#[bitboard(width=16,height=16, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard16x16ColMajor;
+
+#[bitboard(width=5,height=20)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard5x20;
+
+#[bitboard(width=30,height=2)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard30x2;
+
+#[bitboard(width=8,height=8, tables(rook_sliding, bishop_sliding))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=43,height=3, col_major=true, tables(bishop_sliding))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard43x3;
+
+fn slow_attacks<B: Bitboard>(empty: &B, x0: u8, y0: u8, occupancy: &B, directions: &[(i8, i8)]) -> B {
+	let mut attacks = empty.clone();
+	for &(dx, dy) in directions {
+		let mut x = x0 as i16 + dx as i16;
+		let mut y = y0 as i16 + dy as i16;
+		while x >= 0 && y >= 0 && x < empty.width() as i16 && y < empty.height() as i16 {
+			attacks.set(x as u8, y as u8);
+			if occupancy.get(x as u8, y as u8) {
+				break;
+			}
+			x += dx as i16;
+			y += dy as i16;
+		}
+	}
+	attacks
+}
+
+fn check_table<B: Bitboard + std::fmt::Display + std::fmt::Debug>(empty: &B, table: &SlidingTable<B>, directions: &[(i8, i8)])
+where
+	B::Storage: WordStorage,
+{
+	let mut rng = Rng(0x1234_5678_9ABC_DEF1);
+	for _ in 0..64 {
+		let occupancy = random_board(empty.clone(), 4, &mut rng);
+		for x in 0..empty.width() {
+			for y in 0..empty.height() {
+				let mut from = empty.clone();
+				from.set(x, y);
+				let square = from.iter_bits().next().unwrap() as usize;
+				let expected = slow_attacks(empty, x, y, &occupancy, directions);
+				assert_eq!(*table.attacks(square, &occupancy), expected, "({x}, {y}) with occupancy\n{occupancy}");
+			}
+		}
+	}
+}
+
+#[test]
+fn test_rook_8x8() {
+	let table = Bitboard8x8::generate_rook_sliding_table();
+	assert_eq!(table.mask(0).count(), 12);
+	assert!(table.len() >= 102400);
+	check_table(&Bitboard8x8::EMPTY, &table, &ROOK_DIRECTIONS);
+}
+
+#[test]
+fn test_bishop_8x8() {
+	let table = Bitboard8x8::generate_bishop_sliding_table();
+	assert_eq!(table.mask(0).count(), 6);
+	assert!(table.len() >= 5248);
+	check_table(&Bitboard8x8::EMPTY, &table, &BISHOP_DIRECTIONS);
+}
+
+#[test]
+fn test_col_major() {
+	let table = Bitboard8x8ColMajor::generate_rook_sliding_table();
+	check_table(&Bitboard8x8ColMajor::EMPTY, &table, &ROOK_DIRECTIONS);
+	let table = Bitboard8x8ColMajor::generate_bishop_sliding_table();
+	check_table(&Bitboard8x8ColMajor::EMPTY, &table, &BISHOP_DIRECTIONS);
+}
+
+#[test]
+fn test_queen_small_board() {
+	let queen: Vec<(i8, i8)> = ROOK_DIRECTIONS.iter().chain(BISHOP_DIRECTIONS.iter()).copied().collect();
+	let table = Bitboard6x5::generate_sliding_table(&queen);
+	check_table(&Bitboard6x5::EMPTY, &table, &queen);
+}
+
+#[test]
+fn test_array_storage() {
+	let table = Bitboard17x8::generate_bishop_sliding_table();
+	check_table(&Bitboard17x8::EMPTY, &table, &BISHOP_DIRECTIONS);
+}
+
+#[test]
+fn test_macro_tables() {
+	check_table(&Chess::EMPTY, Chess::ROOK_SLIDING, &ROOK_DIRECTIONS);
+	check_table(&Chess::EMPTY, Chess::BISHOP_SLIDING, &BISHOP_DIRECTIONS);
+	assert_eq!(Chess::ROOK_SLIDING.mask(0).count(), 12);
+	let occupancy = Chess::from_coords(3, 5) | Chess::from_coords(6, 3);
+	let square = Chess::from_coords(3, 3).first().unwrap() as usize;
+	assert_eq!(occupancy.rook_sliding(square), sliding_attacks(square, &occupancy, &ROOK_DIRECTIONS));
+	check_table(&Bitboard43x3::EMPTY, Bitboard43x3::BISHOP_SLIDING, &BISHOP_DIRECTIONS);
+}
+
+#[test]
+fn test_runtime_bitboard() {
+	let empty = SmallBitboard::empty(7, 7, false);
+	let table = empty.generate_sliding_table(&ROOK_DIRECTIONS);
+	check_table(&empty, &table, &ROOK_DIRECTIONS);
+}
+
+/// Queen directions and a (2, 1) rider.
+const DIRECTIONS: [(i8, i8); 9] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (-1, 1), (1, -1), (-1, -1), (2, 1)];
+
+/// Checks the occluded fills against the slow attacks, for every square of random occupancies.
+fn check_kogge_stone<B: OccludedFill + std::fmt::Display>(empty: &B, seed: u64) {
+	let mut rng = Rng(seed);
+	for _ in 0..8 {
+		let mut occupancy = empty.clone();
+		let mut sliders = empty.clone();
+		for x in 0..empty.width() {
+			for y in 0..empty.height() {
+				match rng.next() % 8 {
+					0 | 1 => occupancy.set(x, y),
+					2 => sliders.set(x, y),
+					_ => {}
+				}
+			}
+		}
+		let mut all = empty.clone();
+		for x in 0..empty.width() {
+			for y in 0..empty.height() {
+				let mut from = empty.clone();
+				from.set(x, y);
+				let square = from.clone().iter_bits().next().unwrap() as usize;
+				for direction in DIRECTIONS {
+					let expected = slow_attacks(empty, x, y, &occupancy, &[direction]);
+					assert!(sliding_attacks(square, &occupancy, &[direction]) == expected, "{direction:?} from ({x}, {y}) with occupancy\n{occupancy}");
+				}
+				if sliders.get(x, y) {
+					all |= slow_attacks(empty, x, y, &(occupancy.clone() | sliders.clone()), &DIRECTIONS);
+				}
+			}
+		}
+		// Sliders block each other
+		assert!(attacks_of_all(&sliders, &(occupancy.clone() | sliders.clone()), &DIRECTIONS) == all, "sliders\n{sliders}occupancy\n{occupancy}");
+	}
+}
+
+#[test]
+fn test_kogge_stone_static() {
+	check_kogge_stone(&Bitboard8x8::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_kogge_stone(&Bitboard8x8ColMajor::EMPTY, 0x2545_F491_4F6C_DD1D);
+	check_kogge_stone(&Bitboard6x5::EMPTY, 0xD1B5_4A32_D192_ED03);
+	check_kogge_stone(&Bitboard17x8::EMPTY, 0x94D0_49BB_1331_11EB);
+	check_kogge_stone(&Bitboard16x16::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_kogge_stone(&Bitboard16x16ColMajor::EMPTY, 0x2545_F491_4F6C_DD1D);
+	check_kogge_stone(&Bitboard5x20::EMPTY, 0xD1B5_4A32_D192_ED03);
+	check_kogge_stone(&Bitboard30x2::EMPTY, 0x94D0_49BB_1331_11EB);
+}
+
+#[test]
+fn test_kogge_stone_runtime() {
+	for col_major in [false, true] {
+		check_kogge_stone(&SmallBitboard::empty(8, 8, col_major), 0x9E37_79B9_7F4A_7C15);
+		check_kogge_stone(&SmallBitboard::empty(32, 2, col_major), 0x2545_F491_4F6C_DD1D);
+		check_kogge_stone(&MediumBitboard::empty(11, 9, col_major), 0xD1B5_4A32_D192_ED03);
+		check_kogge_stone(&LargeBitboard::empty(16, 16, col_major), 0x94D0_49BB_1331_11EB);
+		check_kogge_stone(&DynBitboard::empty(13, 7, col_major), 0x9E37_79B9_7F4A_7C15);
+	}
+}
+
+#[test]
+fn test_kogge_stone_rook_16x16() {
+	// Rook on (3, 5), blockers on (3, 9) and (10, 5)
+	let mut occupancy = Bitboard16x16::EMPTY;
+	occupancy.set(3, 9);
+	occupancy.set(10, 5);
+	let from = Bitboard16x16::index_from_coords(3, 5);
+	let attacks = sliding_attacks(from, &occupancy, &ROOK_DIRECTIONS);
+	assert_eq!(attacks.count(), 3 + 7 + 4 + 5);
+	assert!(attacks.get(3, 9) && attacks.get(10, 5) && !attacks.get(3, 10) && !attacks.get(11, 5));
+	assert_eq!(attacks, Bitboard16x16::from_index(from).sliding_attacks_towards(&occupancy.flipped(), (1, 0)) | sliding_attacks(from, &occupancy, &[(-1, 0), (0, 1), (0, -1)]));
+}
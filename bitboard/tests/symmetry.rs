@@ -0,0 +1,149 @@
+use bitboard::{Bitboard, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard, medium_bitboard::MediumBitboard, small_bitboard::SmallBitboard}, symmetry::{Symmetric, Symmetry}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard, bitboard_lit};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=8,height=8, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8ColMajor;
+
+#[bitboard(width=5,height=5)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard5x5;
+
+#[bitboard(width=7,height=5)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard7x5;
+
+#[bitboard(width=3,height=11, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard3x11ColMajor;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard19x19;
+
+#[bitboard(width=12,height=13, col_major=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard12x13ColMajor;
+
+#[bitboard(width=6,height=4, wrap_x=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Cylinder6x4;
+
+fn coords<B: Bitboard>(b: &B) -> Vec<(u8, u8)> {
+	let mut res = Vec::new();
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			if b.get(x, y) {
+				res.push((x, y));
+			}
+		}
+	}
+	res.sort();
+	res
+}
+
+/// Reference image of `b` by `symmetry`, as sorted coordinates.
+fn transformed_ref<B: Bitboard>(b: &B, symmetry: Symmetry) -> Vec<(u8, u8)> {
+	let mut res: Vec<_> = coords(b).into_iter().map(|(x, y)| symmetry.apply(b.width(), b.height(), x, y)).collect();
+	res.sort();
+	res
+}
+
+/// Checks every transform of random boards, and that no bit is set outside the results.
+fn check_symmetries<B: Symmetric + std::fmt::Display>(mut b: B, seed: u64) {
+	let mut rng = Rng(seed);
+	for _ in 0..16 {
+		b ^= b.clone();
+		b = random_board(b, 2, &mut rng);
+		let same_shape = [(Symmetry::FlipHorizontal, b.flip_horizontal()), (Symmetry::FlipVertical, b.flip_vertical()), (Symmetry::Rotate180, b.rotate180())];
+		for (symmetry, image) in same_shape {
+			assert_eq!(coords(&image), transformed_ref(&b, symmetry), "{symmetry:?}\n{b}");
+			assert_eq!(image.count(), b.count(), "{symmetry:?}\n{b}");
+		}
+		let swapped = [(Symmetry::Transpose, b.transpose()), (Symmetry::Rotate90, b.rotate90()), (Symmetry::Rotate270, b.rotate270()), (Symmetry::AntiTranspose, b.anti_transpose())];
+		for (symmetry, image) in swapped {
+			assert_eq!((image.width(), image.height()), (b.height(), b.width()));
+			assert_eq!(coords(&image), transformed_ref(&b, symmetry), "{symmetry:?}\n{b}");
+			assert_eq!(image.count(), b.count(), "{symmetry:?}\n{b}");
+		}
+		assert!(b.transpose().transpose() == b);
+		assert!(b.rotate90().rotate270() == b);
+		assert!(b.rotate90().rotate90() == b.rotate180());
+	}
+}
+
+#[test]
+fn test_symmetries_static() {
+	check_symmetries(Bitboard8x8::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_symmetries(Bitboard8x8ColMajor::EMPTY, 0x2545_F491_4F6C_DD1D);
+	check_symmetries(Bitboard5x5::EMPTY, 0xD1B5_4A32_D192_ED03);
+	check_symmetries(Bitboard7x5::EMPTY, 0x94D0_49BB_1331_11EB);
+	check_symmetries(Bitboard7x5Transposed::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_symmetries(Bitboard3x11ColMajor::EMPTY, 0x2545_F491_4F6C_DD1D);
+	check_symmetries(Bitboard19x19::EMPTY, 0xD1B5_4A32_D192_ED03);
+	check_symmetries(Bitboard12x13ColMajor::EMPTY, 0x94D0_49BB_1331_11EB);
+	check_symmetries(Cylinder6x4::EMPTY, 0x9E37_79B9_7F4A_7C15);
+}
+
+#[test]
+fn test_symmetries_runtime() {
+	for col_major in [false, true] {
+		check_symmetries(SmallBitboard::empty(8, 8, col_major), 0x9E37_79B9_7F4A_7C15);
+		check_symmetries(SmallBitboard::empty(7, 5, col_major), 0x2545_F491_4F6C_DD1D);
+		check_symmetries(MediumBitboard::empty(9, 11, col_major), 0xD1B5_4A32_D192_ED03);
+		check_symmetries(LargeBitboard::empty(19, 19, col_major), 0x94D0_49BB_1331_11EB);
+		check_symmetries(DynBitboard::empty(13, 6, col_major), 0x9E37_79B9_7F4A_7C15);
+	}
+}
+
+#[test]
+fn test_symmetries_iter() {
+	let b = bitboard_lit!(Bitboard8x8,
+		"........",
+		"........",
+		"........",
+		"........",
+		"........",
+		"#.......",
+		"#.......",
+		"##......",
+	);
+	let images: Vec<_> = b.symmetries().collect();
+	assert_eq!(images.len(), 8);
+	for (symmetry, image) in Symmetry::ALL.iter().zip(&images) {
+		assert_eq!(*image, b.transformed(*symmetry));
+		assert_eq!(image.transformed(symmetry.inverse()), b);
+	}
+	// An L tromino has no symmetry, its eight images are distinct
+	for (i, a) in images.iter().enumerate() {
+		assert!(images[i + 1..].iter().all(|b| b != a));
+	}
+	assert_eq!(b.rotate90(), bitboard_lit!(Bitboard8x8,
+		"###.....",
+		"#.......",
+		"........",
+		"........",
+		"........",
+		"........",
+		"........",
+		"........",
+	));
+
+	// The companion type of a non-square board
+	let b = Bitboard7x5::from_coords(6, 0);
+	let t: Bitboard7x5Transposed = b.transpose();
+	assert_eq!(Bitboard7x5Transposed::WIDTH, 5);
+	assert_eq!(Bitboard7x5Transposed::HEIGHT, 7);
+	assert_eq!(t, Bitboard7x5Transposed::from_coords(0, 6));
+	assert_eq!(b.rotate90(), Bitboard7x5Transposed::from_coords(0, 0));
+	assert_eq!(b.rotate270(), Bitboard7x5Transposed::from_coords(4, 6));
+	assert_eq!(t.transpose(), b);
+	assert_eq!((Cylinder6x4Transposed::WRAP_X, Cylinder6x4Transposed::WRAP_Y), (false, true));
+}
@@ -0,0 +1,100 @@
+use bitboard::Bitboard;
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+#[bitboard(width=8,height=8, tables(ray_n, ray_s, ray_e, ray_w, ray_ne, ray_nw, ray_se, ray_sw, neighbors_ortho, neighbors_diag, neighbors_8, diag_inc, diag_dec, knight, king, ray_between))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8;
+
+#[bitboard(width=8,height=8, col_major=true, tables(ray_ne, neighbors_8, diag_dec, knight, ray_between))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard8x8ColMajor;
+
+#[bitboard(width=11,height=7, tables(ray_nw, diag_inc, knight, ray_between))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard11x7;
+
+#[bitboard(width=17,height=8, col_major=true, tables(ray_sw, neighbors_8, diag_inc, knight, ray_between))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard17x8;
+
+#[bitboard(width=16,height=16, tables(ray_between))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Bitboard16x16;
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+
+#[test]
+fn test_tables_match_const_generators() {
+	assert_eq!(Bitboard8x8::RAY_N, &Bitboard8x8::generate_ray_n_table());
+	assert_eq!(Bitboard8x8::RAY_S, &Bitboard8x8::generate_ray_s_table());
+	assert_eq!(Bitboard8x8::RAY_E, &Bitboard8x8::generate_ray_e_table());
+	assert_eq!(Bitboard8x8::RAY_W, &Bitboard8x8::generate_ray_w_table());
+	assert_eq!(Bitboard8x8::RAY_NE, &Bitboard8x8::generate_ray_ne_table());
+	assert_eq!(Bitboard8x8::RAY_NW, &Bitboard8x8::generate_ray_nw_table());
+	assert_eq!(Bitboard8x8::RAY_SE, &Bitboard8x8::generate_ray_se_table());
+	assert_eq!(Bitboard8x8::RAY_SW, &Bitboard8x8::generate_ray_sw_table());
+	assert_eq!(Bitboard8x8::NEIGHBORS_ORTHO, &Bitboard8x8::generate_neighbors_ortho_table());
+	assert_eq!(Bitboard8x8::NEIGHBORS_DIAG, &Bitboard8x8::generate_neighbors_diag_table());
+	assert_eq!(Bitboard8x8::NEIGHBORS_8, &Bitboard8x8::generate_neighbors_8_table());
+	assert_eq!(Bitboard8x8::KING, &Bitboard8x8::generate_neighbors_8_table());
+	assert_eq!(Bitboard8x8::DIAG_INC, &Bitboard8x8::generate_diag_inc_table());
+	assert_eq!(Bitboard8x8::DIAG_DEC, &Bitboard8x8::generate_diag_dec_table());
+	assert_eq!(Bitboard8x8::KNIGHT, &Bitboard8x8::generate_jump_attacks_table(&KNIGHT_OFFSETS));
+	assert_eq!(Bitboard8x8::RAY_BETWEEN, &Bitboard8x8::generate_ray_between_table());
+
+	assert_eq!(Bitboard8x8ColMajor::RAY_NE, &Bitboard8x8ColMajor::generate_ray_ne_table());
+	assert_eq!(Bitboard8x8ColMajor::NEIGHBORS_8, &Bitboard8x8ColMajor::generate_neighbors_8_table());
+	assert_eq!(Bitboard8x8ColMajor::DIAG_DEC, &Bitboard8x8ColMajor::generate_diag_dec_table());
+	assert_eq!(Bitboard8x8ColMajor::KNIGHT, &Bitboard8x8ColMajor::generate_jump_attacks_table(&KNIGHT_OFFSETS));
+	assert_eq!(Bitboard8x8ColMajor::RAY_BETWEEN, &Bitboard8x8ColMajor::generate_ray_between_table());
+
+	assert_eq!(Bitboard11x7::RAY_NW, &Bitboard11x7::generate_ray_nw_table());
+	assert_eq!(Bitboard11x7::DIAG_INC, &Bitboard11x7::generate_diag_inc_table());
+	assert_eq!(Bitboard11x7::KNIGHT, &Bitboard11x7::generate_jump_attacks_table(&KNIGHT_OFFSETS));
+	assert_eq!(Bitboard11x7::RAY_BETWEEN, &Bitboard11x7::generate_ray_between_table());
+}
+
+#[test]
+fn test_tables_array_storage() {
+	assert_eq!(Bitboard17x8::RAY_SW, &Bitboard17x8::generate_ray_sw_table());
+	assert_eq!(Bitboard17x8::NEIGHBORS_8, &Bitboard17x8::generate_neighbors_8_table());
+	assert_eq!(Bitboard17x8::DIAG_INC, &Bitboard17x8::generate_diag_inc_table());
+	assert_eq!(Bitboard17x8::KNIGHT, &Bitboard17x8::generate_jump_attacks_table(&KNIGHT_OFFSETS));
+	for from in 0..Bitboard17x8::NB_SQUARES {
+		for to in 0..Bitboard17x8::NB_SQUARES {
+			assert_eq!(Bitboard17x8::ray_between_mask(from, to), Bitboard17x8::compute_ray_between_mask(from, to), "{from} -> {to}");
+		}
+	}
+}
+
+#[test]
+fn test_ray_between_16x16() {
+	assert_eq!(Bitboard16x16::RAY_BETWEEN.len(), 256);
+	for from in 0..Bitboard16x16::NB_SQUARES {
+		for to in 0..Bitboard16x16::NB_SQUARES {
+			assert_eq!(Bitboard16x16::ray_between_mask(from, to), Bitboard16x16::compute_ray_between_mask(from, to), "{from} -> {to}");
+		}
+	}
+	let a1 = Bitboard16x16::index_from_coords(0, 0);
+	let p16 = Bitboard16x16::index_from_coords(15, 15);
+	assert_eq!(Bitboard16x16::ray_between_mask(a1, p16).count(), 14);
+}
+
+#[test]
+fn test_table_accessors() {
+	let sq = Bitboard8x8::index_from_coords(3, 3);
+	assert_eq!(Bitboard8x8::knight_mask(sq).count(), 8);
+	assert_eq!(Bitboard8x8::FULL.knight(sq), Bitboard8x8::knight_mask(sq));
+	assert!(Bitboard8x8::EMPTY.knight(sq).is_empty());
+
+	let mut occupancy = Bitboard8x8::EMPTY;
+	occupancy.set(3, 5);
+	occupancy.set(5, 3);
+	assert_eq!(occupancy.ray_n(sq), Bitboard8x8::from_coords(3, 5));
+	assert_eq!(occupancy.ray_between(sq, Bitboard8x8::index_from_coords(3, 7)), Bitboard8x8::from_coords(3, 5));
+	assert!(occupancy.ray_between(sq, Bitboard8x8::index_from_coords(7, 7)).is_empty());
+
+	let corner = Bitboard17x8::index_from_coords(0, 0);
+	assert_eq!(Bitboard17x8::knight_mask(corner).count(), 2);
+	assert_eq!(Bitboard17x8::FULL.neighbors_8(corner).count(), 3);
+}
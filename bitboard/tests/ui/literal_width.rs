@@ -0,0 +1,14 @@
+use bitboard_proc_macro::{bitboard, bitboard_lit};
+
+#[bitboard(width=4,height=3)]
+struct Bitboard4x3;
+
+const GLIDER: Bitboard4x3 = bitboard_lit!(Bitboard4x3,
+	".#...",
+	"..#..",
+	"###..",
+);
+
+fn main() {
+	let _ = GLIDER;
+}
@@ -0,0 +1,218 @@
+use bitboard::Bitboard;
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard, bitboard_lit};
+
+mod common;
+use common::{Rng, random_board};
+
+#[bitboard(width=8,height=8, wrap_x=true, wrap_y=true, tables(neighbors_8, knight))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Torus8x8;
+
+#[bitboard(width=7,height=5, col_major=true, wrap_x=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Cylinder7x5ColMajor;
+
+#[bitboard(width=17,height=8, col_major=true, wrap_x=true, wrap_y=true, tables(neighbors_ortho))]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Torus17x8ColMajor;
+
+#[bitboard(width=12,height=12, wrap_y=true)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Cylinder12x12;
+
+const KING_OFFSETS: [(i16, i16); 8] = [(0, 1), (0, -1), (1, 0), (-1, 0), (1, 1), (-1, 1), (1, -1), (-1, -1)];
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+
+/// Reference step of `(dx, dy)` from `(x, y)`, wrapping around the wrapped edges.
+fn step_ref(w: u8, h: u8, wrap: (bool, bool), x: u8, y: u8, dx: i16, dy: i16) -> Option<(u8, u8)> {
+	let (mut nx, mut ny) = (x as i16 + dx, y as i16 + dy);
+	if wrap.0 {
+		nx = nx.rem_euclid(w as i16);
+	}
+	if wrap.1 {
+		ny = ny.rem_euclid(h as i16);
+	}
+	(nx >= 0 && ny >= 0 && nx < w as i16 && ny < h as i16).then_some((nx as u8, ny as u8))
+}
+
+/// Reference shift: every set square moved by `(dx, dy)`.
+fn shifted_ref<B: Bitboard>(b: &B, wrap: (bool, bool), dx: i16, dy: i16) -> B {
+	let mut res = b.clone();
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			res.reset(x, y);
+		}
+	}
+	for x in 0..b.width() {
+		for y in 0..b.height() {
+			if b.get(x, y) && let Some((nx, ny)) = step_ref(b.width(), b.height(), wrap, x, y, dx, dy) {
+				res.set(nx, ny);
+			}
+		}
+	}
+	res
+}
+
+/// Reference count of the runs of `n` distinct set squares along the four axes.
+fn count_aligned_ref<B: Bitboard>(b: &B, wrap: (bool, bool), n: usize) -> u32 {
+	let mut count = 0;
+	for (dx, dy) in [(1, 0), (0, 1), (1, -1), (1, 1)] {
+		for x in 0..b.width() {
+			for y in 0..b.height() {
+				let mut cells = vec![(x, y)];
+				while cells.len() < n {
+					let &(cx, cy) = cells.last().unwrap();
+					match step_ref(b.width(), b.height(), wrap, cx, cy, dx, dy) {
+						Some(next) if !cells.contains(&next) => cells.push(next),
+						_ => break,
+					}
+				}
+				if cells.len() == n && cells.iter().all(|&(cx, cy)| b.get(cx, cy)) {
+					count += 1;
+				}
+			}
+		}
+	}
+	count
+}
+
+macro_rules! wrap_test {
+	($T:ty, $seed:expr) => {{
+		let wrap = (<$T>::WRAP_X, <$T>::WRAP_Y);
+		let mut rng = Rng($seed);
+		for _ in 0..16 {
+			let b = random_board(<$T>::EMPTY, 2, &mut rng);
+
+			let shifted = [
+				b.shifted_n(), b.shifted_s(), b.shifted_e(), b.shifted_w(),
+				b.shifted_ne(), b.shifted_nw(), b.shifted_se(), b.shifted_sw(),
+			];
+			let mut dilated = b.clone();
+			let mut eroded = b.clone();
+			for (s, (dx, dy)) in shifted.iter().zip(KING_OFFSETS) {
+				let expected = shifted_ref(&b, wrap, dx, dy);
+				assert_eq!(*s, expected, "({dx}, {dy})\n{b}");
+				dilated |= expected.clone();
+				eroded &= expected;
+			}
+			assert_eq!(b.dilated(), dilated);
+			assert_eq!(b.eroded(), eroded);
+
+			let mut s = b.clone();
+			s.shift_sw_by(3);
+			assert_eq!(s, shifted_ref(&b, wrap, -3, -3));
+			assert_eq!(b.shifted(5, -2), shifted_ref(&b, wrap, 5, -2));
+			assert_eq!(b.shifted(-30, 9), shifted_ref(&b, wrap, -30, 9));
+
+			let sparse = random_board(<$T>::EMPTY, 3, &mut rng);
+			let dense = !random_board(<$T>::EMPTY, 6, &mut rng) & <$T>::FULL;
+			for b in [b, sparse, dense] {
+				assert_eq!(b.count_aligned::<3>(), count_aligned_ref(&b, wrap, 3), "\n{b}");
+				assert_eq!(b.count_aligned::<5>(), count_aligned_ref(&b, wrap, 5), "\n{b}");
+				assert_eq!(b.has_aligned::<4>(), count_aligned_ref(&b, wrap, 4) > 0, "\n{b}");
+				assert_eq!(b.has_n_aligned(4), b.has_aligned::<4>());
+			}
+		}
+
+		for i in 0..<$T>::NB_SQUARES {
+			let cell = <$T>::from_index(i);
+			let ortho = KING_OFFSETS[..4].iter().fold(<$T>::EMPTY, |acc, &(dx, dy)| acc | shifted_ref(&cell, wrap, dx, dy));
+			let diag = KING_OFFSETS[4..].iter().fold(<$T>::EMPTY, |acc, &(dx, dy)| acc | shifted_ref(&cell, wrap, dx, dy));
+			assert_eq!(<$T>::compute_neighbors_ortho_mask(i), ortho);
+			assert_eq!(<$T>::compute_neighbors_diag_mask(i), diag);
+			assert_eq!(<$T>::compute_neighbors_8_mask(i), ortho | diag);
+		}
+	}};
+}
+
+#[test]
+fn test_wrap() {
+	wrap_test!(Torus8x8, 0x9E37_79B9_7F4A_7C15);
+	wrap_test!(Cylinder7x5ColMajor, 0x2545_F491_4F6C_DD1D);
+	wrap_test!(Torus17x8ColMajor, 0xD1B5_4A32_D192_ED03);
+	wrap_test!(Cylinder12x12, 0x94D0_49BB_1331_11EB);
+}
+
+#[test]
+fn test_wrap_tables() {
+	assert_eq!(Torus8x8::NEIGHBORS_8, &Torus8x8::generate_neighbors_8_table());
+	assert_eq!(Torus8x8::KNIGHT, &Torus8x8::generate_jump_attacks_table(&KNIGHT_OFFSETS));
+	assert_eq!(Torus17x8ColMajor::NEIGHBORS_ORTHO, &Torus17x8ColMajor::generate_neighbors_ortho_table());
+	assert!(Torus8x8::KNIGHT.iter().all(|b| b.count() == 8));
+	assert!(Torus8x8::NEIGHBORS_8.iter().all(|b| b.count() == 8));
+
+	// Rays stop at the edges
+	assert_eq!(Torus8x8::compute_ray_e_mask(Torus8x8::index_from_coords(5, 0)).count(), 2);
+}
+
+#[test]
+fn test_wrap_alignments() {
+	let b = bitboard_lit!(Torus8x8,
+		"........",
+		"........",
+		"........",
+		"##....##",
+		"........",
+		"........",
+		"........",
+		"........",
+	);
+	assert!(b.has_aligned_horizontal::<4>());
+	assert!(!b.has_aligned::<5>());
+	assert_eq!(b.count_aligned::<4>(), 1);
+
+	// A full line holds as many runs as squares, but no run longer than the line
+	let full_row = Torus8x8::from_storage(0xFF << 16);
+	assert_eq!(full_row.count_aligned_horizontal::<5>(), 8);
+	assert!(full_row.has_aligned::<8>());
+	assert!(!full_row.has_aligned::<9>());
+
+	// Only the east/west edges connect on a cylinder
+	let mut b = Cylinder7x5ColMajor::EMPTY;
+	for y in 0..5 {
+		b.set((5 + y) % 7, y);
+	}
+	assert!(b.has_aligned_diag_inc::<5>());
+	let mut b = Cylinder7x5ColMajor::EMPTY;
+	b.set(2, 3);
+	b.set(2, 4);
+	b.set(2, 0);
+	assert!(!b.has_aligned_vertical::<3>());
+	assert_eq!(b.shifted_n(), Cylinder7x5ColMajor::from_coords(2, 4) | Cylinder7x5ColMajor::from_coords(2, 1));
+}
+
+/// Conway's Game of Life step on the torus.
+fn life_step(b: &Torus8x8) -> Torus8x8 {
+	let mut next = Torus8x8::EMPTY;
+	for i in 0..Torus8x8::NB_SQUARES {
+		let neighbors = b.neighbors_8(i).count();
+		if neighbors == 3 || (neighbors == 2 && b.get_at_index(i)) {
+			next.set_at_index(i);
+		}
+	}
+	next
+}
+
+#[test]
+fn test_game_of_life_glider() {
+	let glider = bitboard_lit!(Torus8x8,
+		".#......",
+		"..#.....",
+		"###.....",
+		"........",
+		"........",
+		"........",
+		"........",
+		"........",
+	);
+	let mut b = glider;
+	for generation in 1..=32 {
+		b = life_step(&b);
+		assert_eq!(b.count(), 5);
+		if generation % 4 == 0 {
+			let k = (generation / 4) as i16;
+			assert_eq!(b, glider.shifted(k, -k), "generation {generation}");
+		}
+	}
+	assert_eq!(b, glider);
+}
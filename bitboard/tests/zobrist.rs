@@ -0,0 +1,81 @@
+use bitboard::{Bitboard, go::{KoRule, Position}, runtime::{RuntimeBitboard, dyn_bitboard::DynBitboard, large_bitboard::LargeBitboard}, zobrist::{DEFAULT_SEED, ZobristKeys}};
+use bitboard_proc_macro::{BitboardDebug, BitboardDisplay, bitboard};
+
+mod common;
+use common::{Rng};
+
+#[bitboard(width=8,height=8)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Chess;
+
+#[bitboard(width=19,height=19)]
+#[derive(BitboardDebug, BitboardDisplay)]
+struct Go19;
+
+static CHESS_KEYS: [[u64; 12]; Chess::NB_SQUARES] = Chess::zobrist_table(DEFAULT_SEED);
+const CHESS_ZOBRIST: ZobristKeys<Chess, 12> = ZobristKeys::from_static(&CHESS_KEYS);
+
+/// Checks the incremental updates against the full recomputation, along random changes.
+fn check_incremental<B: Bitboard, const LAYERS: usize>(keys: &ZobristKeys<B, LAYERS>, empty: B, seed: u64) {
+	let mut rng = Rng(seed);
+	let mut layers: [B; LAYERS] = std::array::from_fn(|_| empty.clone());
+	let mut hash = keys.hash_of(&layers);
+	assert_eq!(hash, 0);
+	let nb_squares = empty.width() as usize * empty.height() as usize;
+	for _ in 0..200 {
+		let old = layers.clone();
+		for _ in 0..(rng.next() % 4) {
+			let (layer, index) = ((rng.next() % LAYERS as u64) as usize, (rng.next() % nb_squares as u64) as usize);
+			layers[layer].toggle_at_index(index);
+			keys.toggle(&mut hash, layer, index);
+		}
+		assert_eq!(hash, keys.hash_of(&layers));
+		assert_eq!(keys.hash_of(&old) ^ keys.hash_delta(&old, &layers), hash);
+	}
+}
+
+#[test]
+fn test_zobrist_incremental() {
+	check_incremental(&CHESS_ZOBRIST, Chess::EMPTY, 0x9E37_79B9_7F4A_7C15);
+	check_incremental(&ZobristKeys::<Go19, 2>::new(&Go19::EMPTY), Go19::EMPTY, 0x2545_F491_4F6C_DD1D);
+	let large = LargeBitboard::empty(13, 11, true);
+	check_incremental(&ZobristKeys::<_, 3>::with_seed(&large, 42), large, 0xD1B5_4A32_D192_ED03);
+	let dyn_board = DynBitboard::empty(9, 9, false);
+	check_incremental(&ZobristKeys::<_, 2>::new(&dyn_board), dyn_board, 0x94D0_49BB_1331_11EB);
+}
+
+#[test]
+fn test_zobrist_keys() {
+	// Compile-time and run-time keys agree, and depend on the seed
+	let runtime = ZobristKeys::<Chess, 12>::new(&Chess::EMPTY);
+	assert_eq!(CHESS_ZOBRIST.nb_squares(), 64);
+	assert_eq!(runtime.nb_squares(), 64);
+	for layer in 0..12 {
+		for index in 0..64 {
+			assert_eq!(runtime.key(layer, index), CHESS_ZOBRIST.key(layer, index));
+		}
+	}
+	let other = ZobristKeys::<Chess, 12>::with_seed(&Chess::EMPTY, 1);
+	assert_ne!(other.key(0, 0), runtime.key(0, 0));
+
+	// Keys are distinct across layers and squares
+	let mut all: Vec<u64> = CHESS_KEYS.iter().flatten().copied().collect();
+	all.sort();
+	all.dedup();
+	assert_eq!(all.len(), 12 * 64);
+
+	// The same key for the same square index, whatever the backend
+	let large = ZobristKeys::<LargeBitboard, 2>::new(&LargeBitboard::empty(19, 19, false));
+	let go = ZobristKeys::<Go19, 2>::new(&Go19::EMPTY);
+	assert_eq!(large.key(1, 360), go.key(1, 360));
+}
+
+#[test]
+fn test_go_position_hash() {
+	let keys = ZobristKeys::<Go19, 2>::new(&Go19::EMPTY);
+	let mut pos = Position::new(Go19::FULL, KoRule::Simple);
+	for (x, y) in [(3, 3), (15, 15), (3, 15), (15, 3), (2, 3)] {
+		pos.play(x, y).unwrap();
+		assert_eq!(pos.hash(), keys.hash_of(&[pos.black().clone(), pos.white().clone()]));
+	}
+}
@@ -4,7 +4,9 @@ use quote::quote;
 pub(crate) fn bitboard_mask_array_impl(ident: &syn::Ident) -> proc_macro2::TokenStream {
 	let impl_array = quote! {
 		macro_rules! impl_const_bitwise {
-			($name:ident, $trait:ident, $method:ident, $const_method:ident, $assign_const:ident, $trait_assign:ident, $method_assign:ident, $op_assign:tt) => {
+			($name:ident, $trait:ident, $method:ident, $const_method:ident, $assign_const:ident, $trait_assign:ident, $method_assign:ident, $op_assign:tt, $kernel:ident) => {
+				// The operators go through `bitboard::simd`, so these may be unused
+				#[allow(dead_code)]
 				impl $name {
 					#[inline(always)]
 					pub const fn $assign_const(&mut self, rhs: &Self) {
@@ -27,26 +29,27 @@ pub(crate) fn bitboard_mask_array_impl(ident: &syn::Ident) -> proc_macro2::Token
 					}
 				}
 
-				impl std::ops::$trait for $name {
+				impl core::ops::$trait for $name {
 					type Output = Self;
 					#[inline(always)]
-					fn $method(self, rhs: Self) -> Self {
-						self.$const_method(&rhs)
+					fn $method(mut self, rhs: Self) -> Self {
+						bitboard::simd::$kernel(&mut self.0, &rhs.0);
+						self
 					}
 				}
 
-				impl std::ops::$trait_assign for $name {
+				impl core::ops::$trait_assign for $name {
 					#[inline(always)]
 					fn $method_assign(&mut self, rhs: Self) {
-						self.$assign_const(&rhs)
+						bitboard::simd::$kernel(&mut self.0, &rhs.0);
 					}
 				}
 			};
 		}
 
-		impl_const_bitwise!(#ident, BitAnd, bitand, and_const, and_assign_const, BitAndAssign, bitand_assign, &=);
-		impl_const_bitwise!(#ident, BitOr, bitor, or_const, or_assign_const, BitOrAssign, bitor_assign, |=);
-		impl_const_bitwise!(#ident, BitXor, bitxor, xor_const, xor_assign_const, BitXorAssign, bitxor_assign, ^=);
+		impl_const_bitwise!(#ident, BitAnd, bitand, and_const, and_assign_const, BitAndAssign, bitand_assign, &=, and_assign);
+		impl_const_bitwise!(#ident, BitOr, bitor, or_const, or_assign_const, BitOrAssign, bitor_assign, |=, or_assign);
+		impl_const_bitwise!(#ident, BitXor, bitxor, xor_const, xor_assign_const, BitXorAssign, bitxor_assign, ^=, xor_assign);
 
 		impl #ident {
 			#[inline]
@@ -68,12 +71,13 @@ pub(crate) fn bitboard_mask_array_impl(ident: &syn::Ident) -> proc_macro2::Token
 				}
 			}
 		}
-		impl std::ops::Not for #ident {
+		impl core::ops::Not for #ident {
 			type Output = Self;
 		
 			#[inline(always)]
-			fn not(self) -> Self::Output {
-				self.not_const()
+			fn not(mut self) -> Self::Output {
+				bitboard::simd::not_assign(&mut self.0);
+				self
 			}
 		}
 		impl #ident {
@@ -220,51 +224,55 @@ pub(crate) fn bitboard_mask_array_impl(ident: &syn::Ident) -> proc_macro2::Token
 				}
 			}
 		}
-		impl std::ops::Shl<usize> for #ident {
+		impl core::ops::Shl<usize> for #ident {
 			type Output = Self;
 		
 			#[inline(always)]
-			fn shl(self, rhs: usize) -> Self {
-				self.shl_const(rhs)
+			fn shl(mut self, rhs: usize) -> Self {
+				bitboard::simd::shl_assign(&mut self.0, rhs);
+				self
 			}
 		}
 
-		impl std::ops::Shl<u8> for #ident {
+		impl core::ops::Shl<u8> for #ident {
 			type Output = Self;
 			#[inline(always)]
-			fn shl(self, rhs: u8) -> Self {
-				self.shl_const(rhs as usize)
+			fn shl(mut self, rhs: u8) -> Self {
+				bitboard::simd::shl_assign(&mut self.0, rhs as usize);
+				self
 			}
 		}
 
-		impl std::ops::Shr<usize> for #ident {
+		impl core::ops::Shr<usize> for #ident {
 			type Output = Self;
 		
 			#[inline(always)]
-			fn shr(self, rhs: usize) -> Self {
-				self.shr_const(rhs)
+			fn shr(mut self, rhs: usize) -> Self {
+				bitboard::simd::shr_assign(&mut self.0, rhs);
+				self
 			}
 		}
-		impl std::ops::Shr<u8> for #ident {
+		impl core::ops::Shr<u8> for #ident {
 			type Output = Self;
 		
 			#[inline(always)]
-			fn shr(self, rhs: u8) -> Self {
-				self.shr_const(rhs as usize)
+			fn shr(mut self, rhs: u8) -> Self {
+				bitboard::simd::shr_assign(&mut self.0, rhs as usize);
+				self
 			}
 		}
 
-		impl std::ops::ShlAssign<usize> for #ident {
+		impl core::ops::ShlAssign<usize> for #ident {
 			#[inline(always)]
 			fn shl_assign(&mut self, rhs: usize) {
-				*self = self.clone().shl_const(rhs);
+				bitboard::simd::shl_assign(&mut self.0, rhs);
 			}
 		}
 	
-		impl std::ops::ShrAssign<usize> for #ident {
+		impl core::ops::ShrAssign<usize> for #ident {
 			#[inline(always)]
 			fn shr_assign(&mut self, rhs: usize) {
-				*self = self.clone().shr_const(rhs);
+				bitboard::simd::shr_assign(&mut self.0, rhs);
 			}
 		}
 
@@ -283,7 +291,7 @@ pub(crate) fn bitboard_mask_array_impl(ident: &syn::Ident) -> proc_macro2::Token
 			}
 		}
 
-		impl std::ops::SubAssign<usize> for #ident {
+		impl core::ops::SubAssign<usize> for #ident {
 			fn sub_assign(&mut self, rhs: usize) {
 				let mut carry = rhs as u64;
 				for word in self.0.iter_mut() {
@@ -295,7 +303,7 @@ pub(crate) fn bitboard_mask_array_impl(ident: &syn::Ident) -> proc_macro2::Token
 			}
 		}
 
-		impl std::ops::Sub<usize> for #ident {
+		impl core::ops::Sub<usize> for #ident {
 			type Output = Self;
 		
 			fn sub(mut self, rhs: usize) -> Self::Output {
@@ -348,51 +356,51 @@ pub(crate) fn bitboard_mask_int_impl(ident: &syn::Ident) -> proc_macro2::TokenSt
 			pub const fn shr_assign_const(&mut self, rhs: usize) { self.0 >>= rhs; }
 		}
 
-		impl std::ops::BitAnd for #ident {
+		impl core::ops::BitAnd for #ident {
 			type Output = Self;
 			#[inline(always)]
 			fn bitand(self, rhs: Self) -> Self { self.and_const(&rhs) }
 		}
 		
-		impl std::ops::BitOr for #ident {
+		impl core::ops::BitOr for #ident {
 			type Output = Self;
 			#[inline(always)]
 			fn bitor(self, rhs: Self) -> Self { self.or_const(&rhs) }
 		}
 		
-		impl std::ops::BitXor for #ident {
+		impl core::ops::BitXor for #ident {
 			type Output = Self;
 			#[inline(always)]
 			fn bitxor(self, rhs: Self) -> Self { self.xor_const(&rhs) }
 		}
 
-		impl std::ops::BitAndAssign for #ident {
+		impl core::ops::BitAndAssign for #ident {
 			#[inline(always)]
 			fn bitand_assign(&mut self, rhs: Self) {
 				self.0 &= rhs.0;
 			}
 		}
 		
-		impl std::ops::BitOrAssign for #ident {
+		impl core::ops::BitOrAssign for #ident {
 			#[inline(always)]
 			fn bitor_assign(&mut self, rhs: Self) {
 				self.0 |= rhs.0;
 			}
 		}
 		
-		impl std::ops::BitXorAssign for #ident {
+		impl core::ops::BitXorAssign for #ident {
 			#[inline(always)]
 			fn bitxor_assign(&mut self, rhs: Self) {
 				self.0 ^= rhs.0;
 			}
 		}
-		impl std::ops::Not for #ident {
+		impl core::ops::Not for #ident {
 			type Output = Self;
 			#[inline(always)]
 			fn not(self) -> Self { self.not_const() }
 		}
 
-		impl std::ops::Shl<usize> for #ident {
+		impl core::ops::Shl<usize> for #ident {
 			type Output = Self;
 			
 			#[inline(always)]
@@ -401,7 +409,7 @@ pub(crate) fn bitboard_mask_int_impl(ident: &syn::Ident) -> proc_macro2::TokenSt
 			}
 		}
 		
-		impl std::ops::Shr<usize> for #ident {
+		impl core::ops::Shr<usize> for #ident {
 			type Output = Self;
 			
 			#[inline(always)]
@@ -409,19 +417,19 @@ pub(crate) fn bitboard_mask_int_impl(ident: &syn::Ident) -> proc_macro2::TokenSt
 				Self::from_storage(self.storage() >> rhs)
 			}
 		}
-		impl std::ops::ShlAssign<usize> for #ident {
+		impl core::ops::ShlAssign<usize> for #ident {
 			#[inline(always)]
 			fn shl_assign(&mut self, rhs: usize) {
 				self.0 <<= rhs;
 			}
 		}
-		impl std::ops::ShrAssign<usize> for #ident {
+		impl core::ops::ShrAssign<usize> for #ident {
 			#[inline(always)]
 			fn shr_assign(&mut self, rhs: usize) {
 				self.0 >>= rhs;
 			}
 		}
-		impl std::ops::Shl<u8> for #ident {
+		impl core::ops::Shl<u8> for #ident {
 			type Output = Self;
 			
 			#[inline(always)]
@@ -430,7 +438,7 @@ pub(crate) fn bitboard_mask_int_impl(ident: &syn::Ident) -> proc_macro2::TokenSt
 			}
 		}
 		
-		impl std::ops::Shr<u8> for #ident {
+		impl core::ops::Shr<u8> for #ident {
 			type Output = Self;
 			
 			#[inline(always)]
@@ -0,0 +1,212 @@
+use quote::{format_ident, quote};
+
+/// The six hex directions as `(name, dx, dy)`, counter-clockwise from east.
+pub(crate) const HEX_DIRECTIONS: [(&str, i16, i16); 6] = [
+	("e", 1, 0), ("ne", 0, 1), ("nw", -1, 1), ("w", -1, 0), ("sw", 0, -1), ("se", 1, -1),
+];
+
+/// Generates the items of `topology = "hex"` boards.
+///
+/// Cells use axial coordinates on a rhombus, as on a Hex board: each row is drawn half a
+/// cell to the right of the row below, so the neighbours of `(x, y)` are `(x±1, y)`,
+/// `(x, y±1)`, `(x+1, y-1)` and `(x-1, y+1)`. The three axes are E-W, NE-SW and NW-SE.
+///
+/// The no-wrap masks are computed from the coordinates rather than from the border
+/// constants, so that they hold for column-major and array storage alike.
+pub(crate) fn hex_impl(ident: &syn::Ident) -> proc_macro2::TokenStream {
+	let mut direction_items = Vec::new();
+	for (name, dx, dy) in HEX_DIRECTIONS {
+		let upper = name.to_uppercase();
+		let mask = format_ident!("HEX_NO_WRAP_{}_MASK", upper);
+		let shift = format_ident!("shift_hex_{}", name);
+		let shifted = format_ident!("shifted_hex_{}", name);
+		let compute_ray = format_ident!("compute_ray_hex_{}_mask", name);
+		let generate_ray = format_ident!("generate_ray_hex_{}_table", name);
+		let (dx8, dy8) = (dx as i8, dy as i8);
+		let offset = [(dx, quote! { Self::H_OFFSET as isize }), (dy, quote! { Self::V_OFFSET as isize })]
+			.into_iter()
+			.filter(|(d, _)| *d != 0)
+			.map(|(d, offset)| if d > 0 { quote! { + #offset } } else { quote! { - #offset } });
+		let offset = quote! { 0 #(#offset)* };
+		let (dx, dy) = (dx as isize, dy as isize);
+		let mask_doc = format!("Cells having a hex neighbour to the {}, to prevent wrapping during shifts.", upper);
+		let shift_doc = format!("Shift bitboard one cell {} on the hex grid.", upper);
+		let shifted_doc = format!("Shifted bitboard one cell {} on the hex grid.", upper);
+		let compute_doc = format!("Computes a ray from `index` in hex direction {} until board edge.", upper);
+		let generate_doc = format!("Generates a table of hex {} ray for all squares.", upper);
+		direction_items.push(quote! {
+			#[doc = #mask_doc]
+			pub const #mask: Self = Self::compute_hex_no_wrap_mask(#dx8, #dy8);
+			#[doc = #shift_doc]
+			#[inline(always)]
+			pub const fn #shift(&mut self) {
+				*self = self.#shifted();
+			}
+			#[doc = #shifted_doc]
+			#[inline(always)]
+			pub const fn #shifted(&self) -> Self {
+				self.and_const(&Self::#mask).shifted_by_offset(#offset)
+			}
+			#[doc = #compute_doc]
+			#[inline]
+			pub const fn #compute_ray(index: usize) -> Self {
+				Self::compute_ray_mask(index, #dx, #dy)
+			}
+			#[doc = #generate_doc]
+			pub const fn #generate_ray() -> [Self; Self::NB_SQUARES] {
+				let mut arr = [Self::EMPTY; Self::NB_SQUARES];
+				let mut i = 0;
+				while i < Self::NB_SQUARES {
+					arr[i] = Self::#compute_ray(i);
+					i += 1;
+				}
+				arr
+			}
+		});
+	}
+	let directions = HEX_DIRECTIONS.iter().map(|&(_, dx, dy)| {
+		let (dx, dy) = (dx as i8, dy as i8);
+		quote! { (#dx, #dy) }
+	});
+	let shifted_fns: Vec<_> = HEX_DIRECTIONS.iter().map(|(name, _, _)| format_ident!("shifted_hex_{}", name)).collect();
+
+	quote! {
+		impl #ident {
+			/// Hex directions `(dx, dy)`, counter-clockwise from east: E, NE, NW, W, SW, SE.
+			pub const HEX_DIRECTIONS: [(i8, i8); 6] = [#(#directions),*];
+
+			/// Computes the cells whose neighbour in direction `(dx, dy)` is on the board.
+			const fn compute_hex_no_wrap_mask(dx: i8, dy: i8) -> Self {
+				let mut bb = Self::EMPTY;
+				let mut i = 0;
+				while i < Self::NB_SQUARES {
+					let (x, y) = Self::coords_from_index(i);
+					let nx = x as i16 + dx as i16;
+					let ny = y as i16 + dy as i16;
+					if nx >= 0 && ny >= 0 && nx < Self::WIDTH as i16 && ny < Self::HEIGHT as i16 {
+						bb.set_at_index(i);
+					}
+					i += 1;
+				}
+				bb
+			}
+			/// Shifts the storage by a signed offset, left when positive.
+			#[inline(always)]
+			const fn shifted_by_offset(&self, offset: isize) -> Self {
+				if offset >= 0 {
+					self.shl_const(offset as usize)
+				} else {
+					self.shr_const((-offset) as usize)
+				}
+			}
+			#(#direction_items)*
+
+			/// Computes the six hex neighbours of the square at `index`.
+			#[inline]
+			pub const fn compute_neighbors_hex_mask(index: usize) -> Self {
+				let (x, y) = Self::coords_from_index(index);
+				let mut bb = Self::EMPTY;
+				let mut d = 0;
+				while d < 6 {
+					let (dx, dy) = Self::HEX_DIRECTIONS[d];
+					let nx = x as i16 + dx as i16;
+					let ny = y as i16 + dy as i16;
+					if nx >= 0 && ny >= 0 && nx < Self::WIDTH as i16 && ny < Self::HEIGHT as i16 {
+						bb.set_at_index(Self::index_from_coords(nx as u8, ny as u8));
+					}
+					d += 1;
+				}
+				bb
+			}
+			/// Generates a table of hex neighbours for all squares.
+			pub const fn generate_neighbors_hex_table() -> [Self; Self::NB_SQUARES] {
+				let mut arr = [Self::EMPTY; Self::NB_SQUARES];
+				let mut i = 0;
+				while i < Self::NB_SQUARES {
+					arr[i] = Self::compute_neighbors_hex_mask(i);
+					i += 1;
+				}
+				arr
+			}
+			/// Return the board dilated by its six hex neighbours
+			pub const fn hex_dilated(&self) -> Self {
+				let mut res = self.clone_const();
+				#(res.or_assign_const(&self.#shifted_fns());)*
+				res
+			}
+			/// Return the board eroded by its six hex neighbours
+			pub const fn hex_eroded(&self) -> Self {
+				let mut res = self.clone_const();
+				#(res.and_assign_const(&self.#shifted_fns());)*
+				res
+			}
+			/// Return all hex neighbours of any stone in the bitboard
+			pub const fn hex_neighbors_of_any(&self) -> Self {
+				self.hex_dilated().and_const(&self.not_const())
+			}
+
+			/// Cells starting a run of `N` set cells along `axis` (0: E-W, 1: NE-SW, 2: NW-SE).
+			#[inline]
+			const fn get_aligned_hex_axis<const N: usize>(&self, axis: u8) -> Self {
+				let mut temp = self.clone_const();
+				let mut i = 1;
+				while i < N {
+					// Keeps the cells whose next cell along the axis is in the run
+					let shifted = match axis {
+						0 => temp.shifted_hex_w(),
+						1 => temp.shifted_hex_sw(),
+						_ => temp.shifted_hex_se(),
+					};
+					temp.and_assign_const(&shifted);
+					i += 1;
+				}
+				temp
+			}
+			/// Returns `true` if `N` set cells are aligned along one of the three hex axes.
+			#[inline]
+			pub const fn has_aligned<const N: usize>(&self) -> bool {
+				if N == 0 { return true; }
+				self.has_aligned_hex_ew::<N>() ||
+					self.has_aligned_hex_nesw::<N>() ||
+					self.has_aligned_hex_nwse::<N>()
+			}
+			/// Returns `true` if `N` set cells are aligned along the E-W axis.
+			#[inline]
+			pub const fn has_aligned_hex_ew<const N: usize>(&self) -> bool {
+				N == 0 || self.get_aligned_hex_axis::<N>(0).any()
+			}
+			/// Returns `true` if `N` set cells are aligned along the NE-SW axis.
+			#[inline]
+			pub const fn has_aligned_hex_nesw<const N: usize>(&self) -> bool {
+				N == 0 || self.get_aligned_hex_axis::<N>(1).any()
+			}
+			/// Returns `true` if `N` set cells are aligned along the NW-SE axis.
+			#[inline]
+			pub const fn has_aligned_hex_nwse<const N: usize>(&self) -> bool {
+				N == 0 || self.get_aligned_hex_axis::<N>(2).any()
+			}
+			/// Counts the runs of `N` set cells along the three hex axes, overlapping runs included.
+			#[inline]
+			pub fn count_aligned<const N: usize>(&self) -> u32 {
+				self.count_aligned_hex_ew::<N>()
+					+ self.count_aligned_hex_nesw::<N>()
+					+ self.count_aligned_hex_nwse::<N>()
+			}
+			/// Counts the runs of `N` set cells along the E-W axis.
+			#[inline]
+			pub fn count_aligned_hex_ew<const N: usize>(&self) -> u32 {
+				self.get_aligned_hex_axis::<N>(0).count()
+			}
+			/// Counts the runs of `N` set cells along the NE-SW axis.
+			#[inline]
+			pub fn count_aligned_hex_nesw<const N: usize>(&self) -> u32 {
+				self.get_aligned_hex_axis::<N>(1).count()
+			}
+			/// Counts the runs of `N` set cells along the NW-SE axis.
+			#[inline]
+			pub fn count_aligned_hex_nwse<const N: usize>(&self) -> u32 {
+				self.get_aligned_hex_axis::<N>(2).count()
+			}
+		}
+	}
+}
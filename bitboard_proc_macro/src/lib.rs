@@ -1,13 +1,27 @@
 
 mod bit_ops;
 mod common_impls;
-use crate::{bit_ops::*, common_impls::common_impl};
+mod tables;
+mod literal;
+mod hex;
+mod wrap;
+mod symmetry;
+use crate::{bit_ops::*, common_impls::common_impl, hex::hex_impl, literal::bitboard_lit_impl, symmetry::symmetry_impl, tables::tables_impl, wrap::wrap_impl};
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
 	parse_macro_input, ItemStruct, Fields,
 };
 
+/// Grid topology selected with `topology = "..."` in `#[bitboard(...)]`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Topology {
+	/// 4/8-connected square cells (default).
+	Square,
+	/// 6-connected hexagonal cells, in axial coordinates (see `hex_impl`).
+	Hex,
+}
+
 #[proc_macro_attribute]
 pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 	use syn::{parse::Parser, Meta, Expr};
@@ -18,9 +32,22 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 	let mut width = None;
 	let mut height = None;
 	let mut col_major = None;
+	let mut topology = Topology::Square;
+	let mut wrap_x = false;
+	let mut wrap_y = false;
+	let mut wrap_span = None;
+	let mut tables = Vec::new();
+	// Set on the companion type generated for the transposes of a non-square board
+	let mut transposed_of = None;
 	
 	for meta in metas {
-		if let Meta::NameValue(nv) = meta {
+		if let Meta::List(list) = &meta && list.path.is_ident("tables") {
+			let names = list.parse_args_with(syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated);
+			match names {
+				Ok(names) => tables.extend(names),
+				Err(err) => return err.to_compile_error().into(),
+			}
+		} else if let Meta::NameValue(nv) = meta {
 			let ident = nv.path.get_ident().unwrap().to_string();
 			
 			match ident.as_str() {
@@ -39,12 +66,48 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 						col_major = Some(lit_bool.value);
 					}
 				}
+				"wrap_x" | "wrap_y" => {
+					if let Expr::Lit(expr_lit) = &nv.value && let syn::Lit::Bool(lit_bool) = &expr_lit.lit {
+						if ident == "wrap_x" {
+							wrap_x = lit_bool.value;
+						} else {
+							wrap_y = lit_bool.value;
+						}
+						if lit_bool.value {
+							wrap_span = Some(nv.path.clone());
+						}
+					}
+				}
+				"topology" => {
+					let value = match &nv.value {
+						Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit_str), .. }) => lit_str.value(),
+						_ => String::new(),
+					};
+					topology = match value.as_str() {
+						"square" => Topology::Square,
+						"hex" => Topology::Hex,
+						_ => return syn::Error::new_spanned(&nv.value, "expected `topology = \"square\"` or `topology = \"hex\"`")
+							.to_compile_error()
+							.into(),
+					};
+				}
+				"__transposed_of" => {
+					if let Expr::Path(path) = &nv.value {
+						transposed_of = path.path.get_ident().cloned();
+					}
+				}
 				_ => {}
 			}
 		}
 	}
 	
 	
+	if topology == Topology::Hex && let Some(path) = wrap_span {
+		return syn::Error::new_spanned(path, "`wrap_x` and `wrap_y` are not supported with `topology = \"hex\"`")
+			.to_compile_error()
+			.into();
+	}
+	
 	let width = width.expect("missing width");
 	let height = height.expect("missing height");
 	let col_major = col_major.unwrap_or(false);
@@ -105,7 +168,50 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 	//	pub const DIAG_DEC_OFFSET: u8 = Self::WIDTH - 1;
 	//	
 	//};
-	let bitboard_impl_common = common_impl(&struct_ident, width_u8, height_u8, col_major);
+	let bitboard_impl_common = common_impl(&struct_ident, width_u8, height_u8, col_major, topology, wrap_x, wrap_y);
+	let bitboard_impl_hex = match topology {
+		Topology::Square => quote! {},
+		Topology::Hex => hex_impl(&struct_ident),
+	};
+	let bitboard_impl_wrap = if wrap_x || wrap_y {
+		wrap_impl(&struct_ident)
+	} else {
+		quote! {}
+	};
+	// Square topology boards get the symmetries of the square. The transforms exchanging the width
+	// and the height of a non-square board return a companion type, generated here with the
+	// dimensions (and the wrapped axes) exchanged.
+	let transposed_ident = match &transposed_of {
+		Some(ident) => ident.clone(),
+		None if width == height => struct_ident.clone(),
+		None => format_ident!("{}Transposed", struct_ident),
+	};
+	let (bitboard_impl_symmetry, companion) = match topology {
+		Topology::Square => {
+			let companion = if transposed_ident != struct_ident && transposed_of.is_none() {
+				let attrs = struct_attrs.iter().filter(|attr| !attr.path().is_ident("doc"));
+				let doc = format!("[`{}`] with its width and height exchanged, returned by its transposes and quarter turns.", struct_ident);
+				let (w, h) = (proc_macro2::Literal::usize_unsuffixed(height), proc_macro2::Literal::usize_unsuffixed(width));
+				let companion_attr = quote! {
+					width = #w, height = #h, col_major = #col_major, wrap_x = #wrap_y, wrap_y = #wrap_x, __transposed_of = #struct_ident
+				};
+				let companion_item = quote! {
+					#[doc = #doc]
+					#(#attrs)*
+					#struct_vis struct #transposed_ident;
+				};
+				proc_macro2::TokenStream::from(bitboard(companion_attr.into(), companion_item.into()))
+			} else {
+				quote! {}
+			};
+			(symmetry_impl(&struct_ident, &transposed_ident, width, height, col_major, total_bits > 128), companion)
+		}
+		Topology::Hex => (quote! {}, quote! {}),
+	};
+	let bitboard_tables = match tables_impl(&struct_ident, width, height, col_major, topology, (wrap_x, wrap_y), &tables) {
+		Ok(tables) => tables,
+		Err(err) => return err.to_compile_error().into(),
+	};
 	let bits = width * height;
 	
 	let full_mask = if bits >= 128 {
@@ -191,135 +297,23 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 			}
 			#[inline]
 			fn pop_lsb(&mut self) -> u32 {
-				#[cfg(target_feature = "bmi2")]
-				unsafe {
-					let low = self.0 as u64;
-					
-					if low != 0 {
-						let idx = std::arch::x86_64::_tzcnt_u64(low);
-						let new_low = std::arch::x86_64::_blsr_u64(low);
-						
-						let high = self.0 & (!0u128 << 64);
-						self.0 = high | new_low as u128;
-						
-						return idx as u32;
-					}
-					
-					let high = (self.0 >> 64) as u64;
-					let idx = std::arch::x86_64::_tzcnt_u64(high);
-					let new_high = std::arch::x86_64::_blsr_u64(high);
-					
-					self.0 = (new_high as u128) << 64;
-					
-					return (idx + 64) as u32;
-				}
-				#[cfg(not(target_feature = "bmi2"))]
-				{
-					// fallback
-					let low = self.0 as u64;
-					
-					if low != 0 {
-						let idx = low.trailing_zeros();
-						self.0 &= self.0 - 1;
-						return idx;
-					}
-					
-					let high = (self.0 >> 64) as u64;
-					let idx = high.trailing_zeros();
-					self.0 &= self.0 - 1;
-					idx + 64
-				}
+				let idx = self.0.trailing_zeros();
+				self.0 &= self.0 - 1;
+				idx
 			}
-			
 			#[inline]
 			fn pext(&self, mask: &Self) -> Self::Storage {
-				#[cfg(target_feature = "bmi2")]
-				unsafe {
-					let lo  = self.0 as u64;
-					let hi  = (self.0 >> 64) as u64;
-					
-					let mlo = mask.0 as u64;
-					let mhi = (mask.0 >> 64) as u64;
-					
-					let lo_res = std::arch::x86_64::_pext_u64(lo, mlo);
-					let hi_res = std::arch::x86_64::_pext_u64(hi, mhi);
-					
-					let shift = mlo.count_ones() as u32;
-					
-					return ((hi_res as u128) << shift) | (lo_res as u128);
-				}
-				#[cfg(not(target_feature = "bmi2"))]
-				{
-					let mut res: u128 = 0;
-					let mut bit: u128 = 1;
-					let mut m = mask.0;
-					let mut v = self.0;
-					
-					while m != 0 {
-						if m & 1 != 0 {
-							if v & 1 != 0 {
-								res |= bit;
-							}
-							bit <<= 1;
-						}
-						m >>= 1;
-						v >>= 1;
-					}
-					res
-				}
+				bitboard::bmi2::pext_u128(self.0, mask.0)
 			}
 			#[inline]
 			fn pdep(&self, compressed: Self::Storage) -> Self {
-				#[cfg(target_feature = "bmi2")]
-				unsafe {
-					let src = compressed;
-					
-					let lo_mask = self.0 as u64;
-					let hi_mask = (self.0 >> 64) as u64;
-					
-					let lo_count = lo_mask.count_ones() as u32;
-					
-					let src_lo = src as u64;
-					let src_hi = (src >> lo_count) as u64;
-					
-					let lo_res = std::arch::x86_64::_pdep_u64(src_lo, lo_mask);
-					let hi_res = std::arch::x86_64::_pdep_u64(src_hi, hi_mask);
-					
-					return Self((lo_res as u128) | ((hi_res as u128) << 64));
-				}
-				#[cfg(not(target_feature = "bmi2"))]
-				{
-					
-					// fallback
-					let mut res: u128 = 0;
-					let mut bit: u128 = 1;
-					let mut m = compressed;
-					let mut v = self.0;
-					
-					while m != 0 {
-						if m & 1 != 0 {
-							if v & 1 != 0 {
-								res |= bit;
-							}
-							v >>= 1;
-						}
-						bit <<= 1;
-						m >>= 1;
-					}
-					Self(res)
-				}
+				Self(bitboard::bmi2::pdep_u128(compressed, self.0))
 			}
-			
 		}
 	} else {
 		quote! {
 			#[inline(always)]
 			fn lsb(&self) -> u32 {
-				#[cfg(target_feature = "bmi1")]
-				unsafe {
-					std::arch::x86_64::_tzcnt_u64(self.0 as u64) as u32
-				}
-				#[cfg(not(target_feature = "bmi1"))]
 				self.0.trailing_zeros()
 			}
 			#[inline(always)]
@@ -327,80 +321,18 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 				self.0.ilog2()
 			}
 			#[inline]
-			#[allow(unreachable_code)]
 			fn pop_lsb(&mut self) -> u32 {
-				#[cfg(target_feature = "bmi1")]
-				unsafe {
-					let idx = std::arch::x86_64::_tzcnt_u64(self.0 as u64);
-					self.0 = std::arch::x86_64::_blsr_u64(self.0 as u64) as #storage_ty;
-					return idx as u32;
-				}
-				#[cfg(not(target_feature = "bmi1"))]
-				{
-					// fallback
-					let idx = self.0.trailing_zeros();
-					self.0 &= self.0.wrapping_sub(1);
-					idx
-				}
+				let idx = self.0.trailing_zeros();
+				self.0 &= self.0.wrapping_sub(1);
+				idx
 			}
 			#[inline]
 			fn pext(&self, mask: &Self) -> Self::Storage {
-				#[cfg(target_feature = "bmi2")]
-				unsafe {
-					return std::arch::x86_64::_pext_u64(self.0 as u64, mask.0 as u64) as #storage_ty;
-				}
-				#[cfg(not(target_feature = "bmi2"))]
-				{
-					#[cfg(debug_assertions)]
-					eprintln!("pext fallback");
-					let mut res: #storage_ty = 0;
-					let mut bit: #storage_ty = 1;
-					let mut m = mask.0;
-					let mut v = self.0;
-					
-					while m != 0 {
-						if m & 1 != 0 {
-							if v & 1 != 0 {
-								res |= bit;
-							}
-							bit <<= 1;
-						}
-						m >>= 1;
-						v >>= 1;
-					}
-					res
-				}
+				bitboard::bmi2::pext_u64(self.0 as u64, mask.0 as u64) as #storage_ty
 			}
 			#[inline]
 			fn pdep(&self, compressed: Self::Storage) -> Self {
-				#[cfg(target_feature = "bmi2")]
-				unsafe {
-					//#[cfg(debug_assertions)]
-					//eprintln!("pdep_bmi2");
-					return Self(std::arch::x86_64::_pdep_u64(compressed as u64, self.0 as u64) as #storage_ty);
-				}
-				#[cfg(not(target_feature = "bmi2"))]
-				{
-					#[cfg(debug_assertions)]
-					eprintln!("pdep_bmi2 fallback");
-					
-					let mut res: #storage_ty = 0;
-					let mut bit: #storage_ty = 1;
-					let mut m = self.0;
-					let mut v = compressed;
-					
-					while m != 0 {
-						if m & 1 != 0 {
-							if v & 1 != 0 {
-								res |= bit;
-							}
-							v >>= 1;
-						}
-						bit <<= 1;
-						m >>= 1;
-					}
-					Self(res)
-				}
+				Self(bitboard::bmi2::pdep_u64(compressed as u64, self.0 as u64) as #storage_ty)
 			}
 		}
 	};
@@ -614,36 +546,66 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 			}
 			
 		}
-		impl #struct_ident {
-			/// Returns a `Vec` containing all subsets of the current bitboard.
-			///
-			/// Each subset is represented as a bitboard with a subset of bits set
-			/// that are also set in `self`. The subsets are generated in decreasing
-			/// order of bit patterns, starting from the empty bitboard.
-			///
-			/// # Example
-			/// ```
-			/// let bb = Bitboard::from_index(0) | Bitboard::from_index(1);
-			/// let subsets = bb.all_subsets();
-			/// // subsets will contain all combinations of bits {0, 1}:
-			/// // [empty, bit0, bit1, bit0|bit1]
-			/// ```
-			pub fn all_subsets(&self) -> Vec<Self>
-			{
-				let mut subsets = Vec::new();
-				let zero = Self::empty();
+		bitboard::__with_alloc! {
+			impl #struct_ident {
+				/// Returns a `Vec` containing all subsets of the current bitboard.
+				///
+				/// Each subset is represented as a bitboard with a subset of bits set
+				/// that are also set in `self`. The subsets are generated in decreasing
+				/// order of bit patterns, starting from the empty bitboard.
+				///
+				/// # Example
+				/// ```
+				/// let bb = Bitboard::from_index(0) | Bitboard::from_index(1);
+				/// let subsets = bb.all_subsets();
+				/// // subsets will contain all combinations of bits {0, 1}:
+				/// // [empty, bit0, bit1, bit0|bit1]
+				/// ```
+				pub fn all_subsets(&self) -> bitboard::__private::Vec<Self>
+				{
+					let mut subsets = bitboard::__private::Vec::new();
+					let zero = Self::empty();
 
-				let mut subset = zero.clone();
+					let mut subset = zero.clone();
 
-				loop {
-					subsets.push(subset.clone());
-					subset = Self::from_storage(subset.0.wrapping_sub(self.0)) & *self;
-					if subset == zero {
-						break;
+					loop {
+						subsets.push(subset.clone());
+						subset = Self::from_storage(subset.0.wrapping_sub(self.0)) & *self;
+						if subset == zero {
+							break;
+						}
 					}
+
+					subsets
 				}
+				/// Generates attack tables using `mask_fn` to define relevant squares and `attack_fn`
+				/// to compute attacks given blockers. Returns a vector of attack tables for all squares.
+				pub fn generate_attack_tables_pext(
+					mask_fn: fn(u8) -> Self,
+					attack_fn: fn(u8, Self) -> Self
+				) -> bitboard::__private::Vec<bitboard::__private::Vec<Self>>
+				{
+					let mut tables = bitboard::__private::Vec::with_capacity(Self::NB_SQUARES);
+
+					for sq in 0..Self::NB_SQUARES as u8 {
+						let mask = mask_fn(sq);
+						let bits = mask.count() as u8;
+						let table_size = 1usize << bits;
 
-				subsets
+						let mut table = bitboard::__private::Vec::with_capacity(table_size);
+
+						for index in 0..table_size {
+							//let blockers = mask.pdep(<<Self as bitboard::Bitboard>::Storage as bitboard::IntegerStorage>::from_usize(index));
+							let blockers = bitboard::Bitboard::pdep(&mask, <<Self as bitboard::Bitboard>::Storage as bitboard::IntegerStorage>::from_usize(index));
+							let attacks = attack_fn(sq, blockers);
+							table.push(attacks);
+						}
+
+						tables.push(table);
+					}
+
+					tables
+				}
 			}
 		}
 		impl #struct_ident {
@@ -715,41 +677,13 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 				let cleared = Self::from_storage(self.storage() & !mask.storage());
 				*self = cleared | new;
 			}
-			/// Generates attack tables using `mask_fn` to define relevant squares and `attack_fn`
-			/// to compute attacks given blockers. Returns a vector of attack tables for all squares.
-			pub fn generate_attack_tables_pext(
-				mask_fn: fn(u8) -> Self,
-				attack_fn: fn(u8, Self) -> Self
-			) -> Vec<Vec<Self>>
-			{
-				let mut tables = Vec::new();
-
-				for sq in 0..Self::NB_SQUARES as u8 {
-					let mask = mask_fn(sq);
-					let bits = mask.count() as u8;
-					let table_size = 1usize << bits;
-
-					let mut table = vec![Self::empty(); table_size];
-
-					for index in 0..table_size {
-						//let blockers = mask.pdep(<<Self as bitboard::Bitboard>::Storage as bitboard::IntegerStorage>::from_usize(index));
-						let blockers = bitboard::Bitboard::pdep(&mask, <<Self as bitboard::Bitboard>::Storage as bitboard::IntegerStorage>::from_usize(index));
-						let attacks = attack_fn(sq, blockers);
-						table[index] = attacks;
-					}
-
-					tables.push(table);
-				}
-
-				tables
-			}
 		}
 		impl #struct_ident {
 			/// Returns a bitboard mask for row `y`.
 			#[inline(always)]
 			pub const fn row_mask(y: u8) -> Self {
 				if Self::COL_MAJOR {
-					Self(Self::WEST_BORDER.0 << y)
+					Self(Self::SOUTH_BORDER.0 << y)
 				} else {
 					Self((((1 as #storage_ty) << Self::WIDTH as usize) - 1) << (y as usize * Self::WIDTH as usize))
 				}
@@ -814,13 +748,6 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 				Self(self.0)
 			}
 		}
-		impl PartialEq for #struct_ident {
-			fn eq(&self, other: &Self) -> bool {
-				self.0 == other.0
-			}
-		}
-		impl Eq for #struct_ident {
-		}
 	};
 	let derive_bitboard_mask_res  = bitboard_mask_array_impl(&struct_ident);
 	
@@ -838,20 +765,11 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 			}
 			#[inline]
 			fn is_empty(&self) -> bool {
-				for a in self.0.iter() {
-					if *a != 0 {
-						return false;
-					}
-				}
-				true
+				bitboard::simd::is_zero(&self.0)
 			}
 			#[inline]
 			fn count(&self) -> u32 {
-				let mut counts=0;
-				for a in self.0.iter() {
-					counts+=a.count_ones();
-				}
-				counts
+				bitboard::simd::count_ones(&self.0)
 			}
 			#[inline]
 			fn intersects(&self, other: &Self) -> bool {
@@ -964,65 +882,16 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 				u32::MAX // no bit found
 			}
 			
+			#[inline]
 			fn pext(&self, mask: &Self) -> Self::Storage {
-				let mut out = [0;#array_len];
-				let mut current: u64 = 0;
-				let mut bitpos = 0;
-				let mut out_index = 0;
-				
-				for (a, m) in self.0.iter().zip(mask.0.iter()) {
-					let mut mm = *m;
-					while mm != 0 {
-						let lsb = mm.trailing_zeros();
-						let bit = (a >> lsb) & 1;
-						
-						current |= bit << bitpos;
-						bitpos += 1;
-						
-						if bitpos == 64 {
-							out[out_index] = current;
-							current = 0;
-							bitpos = 0;
-						}
-						
-						mm &= mm - 1; // clear extracted bit
-					}
-				}
-				
-				if bitpos > 0 {
-					out[out_index] = current;
-				}
-				
+				let mut out = [0; #array_len];
+				bitboard::bmi2::pext_words(&self.0, &mask.0, &mut out);
 				out
 			}
-			
+			#[inline]
 			fn pdep(&self, compressed: Self::Storage) -> Self {
-				let mut out = [0;#array_len];
-				
-				let mut src_word_index = 0;
-				let mut src_bit_index = 0;
-				
-				for (out_word, mask_word) in out.iter_mut().zip(self.0.iter()) {
-					let mut mm = *mask_word;
-					
-					while mm != 0 {
-						let dst_bit = mm.trailing_zeros();
-						
-						let src_word = compressed[src_word_index];
-						let src_bit = (src_word >> src_bit_index) & 1;
-						
-						*out_word |= src_bit << dst_bit;
-						
-						src_bit_index += 1;
-						if src_bit_index == 64 {
-							src_bit_index = 0;
-							src_word_index += 1;
-						}
-						
-						mm &= mm - 1;
-					}
-				}
-				
+				let mut out = [0; #array_len];
+				bitboard::bmi2::pdep_words(&compressed, &self.0, &mut out);
 				Self(out)
 			}
 			#[inline(always)]
@@ -1253,13 +1122,6 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 				Self(self.0)
 			}
 		}
-		impl PartialEq for #struct_ident {
-			fn eq(&self, other: &Self) -> bool {
-				self.0 == other.0
-			}
-		}
-		impl Eq for #struct_ident {
-		}
 	};
 	let is_array = total_bits > 128;
 	
@@ -1268,18 +1130,52 @@ pub fn bitboard(attr: TokenStream, item: TokenStream) -> TokenStream {
 	} else {
 		impl_int
 	};
+	// Expands to `Serialize`/`Deserialize` impls when the `serde` feature of `bitboard` is enabled
+	let serde_impl = if is_array {
+		quote! { bitboard::__impl_serde!(array #struct_ident); }
+	} else {
+		quote! { bitboard::__impl_serde!(int #struct_ident); }
+	};
 	
 	let output = quote! {
 		#(#struct_attrs)*
+		#[derive(PartialEq, Eq)]
 		#struct_vis
 		#expanded_struct
 		#expanded_impl
+		#bitboard_impl_hex
+		#bitboard_impl_wrap
+		#bitboard_impl_symmetry
+		#serde_impl
+		#bitboard_tables
+		#companion
 		//#impl_alignments
 	};
 	
 	output.into()
 }
 
+/// Builds a constant of a `#[bitboard]` type from rows of `#` (set) and `.` (empty).
+///
+/// The first row is the top one (highest `y`), and bits are placed with the type's
+/// `index_from_coords`, so column-major boards are handled. Rows that do not match the
+/// width or height of the type are compile errors pointing at the offending row.
+///
+/// ```ignore
+/// const START: Bitboard4x3 = bitboard_lit!(Bitboard4x3,
+///     "#...",
+///     ".#..",
+///     "..##",
+/// );
+/// ```
+#[proc_macro]
+pub fn bitboard_lit(input: TokenStream) -> TokenStream {
+	match bitboard_lit_impl(input.into()) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
 #[proc_macro_derive(BitboardDisplay)]
 pub fn derive_bitboard_display(item: TokenStream) -> TokenStream {
 	use syn::{parse_macro_input, ItemStruct, Fields};
@@ -1301,8 +1197,8 @@ pub fn derive_bitboard_display(item: TokenStream) -> TokenStream {
 	
 	let output = quote! {
 		
-		impl std::fmt::Display for #ident {
-			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		impl core::fmt::Display for #ident {
+			fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 				::bitboard::fmt_bitboard_display(self, f)
 			}
 		}
@@ -1332,9 +1228,9 @@ pub fn derive_bitboard_debug(item: TokenStream) -> TokenStream {
 	}
 	
 	let output = quote! {
-		impl std::fmt::Debug for #ident {
-			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-				::bitboard::fmt_bitboard_debug(self, std::mem::size_of_val(&self.0) * 8, f)
+		impl core::fmt::Debug for #ident {
+			fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+				::bitboard::fmt_bitboard_debug(self, core::mem::size_of_val(&self.0) * 8, f)
 			}
 		}
 	};
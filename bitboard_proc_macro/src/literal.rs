@@ -0,0 +1,92 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{LitStr, Token, Type, parse::{Parse, ParseStream}, punctuated::Punctuated};
+
+/// Input of `bitboard_lit!`: the target type, then the rows from top to bottom.
+struct BitboardLiteral {
+	ty: Type,
+	rows: Punctuated<LitStr, Token![,]>,
+}
+impl Parse for BitboardLiteral {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let ty = input.parse()?;
+		input.parse::<Token![,]>()?;
+		let rows = Punctuated::parse_terminated(input)?;
+		Ok(Self { ty, rows })
+	}
+}
+
+/// Builds a `#[bitboard]` constant from rows of `#` and `.`.
+///
+/// The rows are checked against each other here, and against the `WIDTH`/`HEIGHT` of the
+/// type with `const` assertions spanned on the offending row, since the dimensions of the
+/// type are not known to the proc macro. The storage is computed here for both layouts, in the
+/// representation `#[bitboard]` picks for the dimensions of the rows, and `COL_MAJOR` selects one.
+pub(crate) fn bitboard_lit_impl(input: TokenStream) -> syn::Result<TokenStream> {
+	let BitboardLiteral { ty, rows } = syn::parse2(input)?;
+	let rows: Vec<LitStr> = rows.into_iter().collect();
+	let Some(first) = rows.first() else {
+		return Err(syn::Error::new_spanned(&ty, "expected at least one row of `#` and `.`"));
+	};
+	let width = first.value().chars().count();
+	let nb_rows = rows.len();
+
+	let nb_squares = width * nb_rows;
+	let mut row_major = vec![0u64; nb_squares.div_ceil(64).max(1)];
+	let mut col_major = row_major.clone();
+	let mut checks = Vec::new();
+	for (r, row) in rows.iter().enumerate() {
+		let value = row.value();
+		if let Some(c) = value.chars().find(|&c| c != '#' && c != '.') {
+			return Err(syn::Error::new_spanned(row, format!("unexpected character {:?} in row {}, expected '#' or '.'", c, r)));
+		}
+		let len = value.chars().count();
+		if len != width {
+			return Err(syn::Error::new_spanned(row, format!("row {} has {} cells, the first row has {}", r, len, width)));
+		}
+
+		if r == 0 {
+			// The other rows have the same length
+			let width_msg = format!("rows have {} cells, which does not match the width of the bitboard", len);
+			checks.push(quote_spanned! {row.span()=>
+				const _: () = assert!(#len == <#ty>::WIDTH as usize, #width_msg);
+			});
+		}
+		let height_msg = format!("row {} is beyond the height of the bitboard", r);
+		checks.push(quote_spanned! {row.span()=>
+			const _: () = assert!(#r < <#ty>::HEIGHT as usize, #height_msg);
+		});
+		// Rows are given from the top
+		let y = nb_rows - 1 - r;
+		for (x, c) in value.chars().enumerate() {
+			if c == '#' {
+				for (words, i) in [(&mut row_major, y * width + x), (&mut col_major, x * nb_rows + y)] {
+					words[i / 64] |= 1 << (i % 64);
+				}
+			}
+		}
+	}
+	let last = rows.last().unwrap();
+	let missing_msg = format!("{} rows given, fewer than the height of the bitboard", nb_rows);
+	checks.push(quote_spanned! {last.span()=>
+		const _: () = assert!(#nb_rows >= <#ty>::HEIGHT as usize, #missing_msg);
+	});
+
+	// Integers are cast so that rows of the wrong size only fail the assertions
+	let storage = |words: &[u64]| if nb_squares <= 128 {
+		let value = Literal::u128_suffixed(words.iter().rev().fold(0, |value, &word| value << 64 | word as u128));
+		quote! { #value as _ }
+	} else {
+		let words = words.iter().map(|&word| Literal::u64_suffixed(word));
+		quote! { [#(#words),*] }
+	};
+	let (row_major, col_major) = (storage(&row_major), storage(&col_major));
+
+	Ok(quote! {
+		{
+			#(#checks)*
+			const BOARD: #ty = <#ty>::from_storage(if <#ty>::COL_MAJOR { #col_major } else { #row_major });
+			BOARD
+		}
+	})
+}
@@ -0,0 +1,190 @@
+use quote::quote;
+
+/// Generates the flips, rotations and transposes of square-topology boards, and their
+/// `bitboard::symmetry::Symmetric` impl.
+///
+/// `transposed` is the type with the width and the height exchanged: the board itself when it is
+/// square, its companion type otherwise. The flips move one column or one row at a time, square
+/// boards transpose one diagonal at a time, and non-square boards square by square.
+/// 8x8 boards in a `u64` use byte swaps and delta swaps instead.
+pub(crate) fn symmetry_impl(ident: &syn::Ident, transposed: &syn::Ident, width: usize, height: usize, col_major: bool, is_storage_array: bool) -> proc_macro2::TokenStream {
+	let is_8x8 = width == 8 && height == 8;
+	// Reverses the bits of each byte
+	let mirror_bytes = quote! {
+		let mut b = self.0;
+		b = ((b >> 1) & 0x5555_5555_5555_5555) | ((b & 0x5555_5555_5555_5555) << 1);
+		b = ((b >> 2) & 0x3333_3333_3333_3333) | ((b & 0x3333_3333_3333_3333) << 2);
+		b = ((b >> 4) & 0x0F0F_0F0F_0F0F_0F0F) | ((b & 0x0F0F_0F0F_0F0F_0F0F) << 4);
+		Self(b)
+	};
+	let swap_bytes = quote! { Self(self.0.swap_bytes()) };
+	let by_columns = quote! {
+		let mut res = Self::EMPTY;
+		let mut x = 0;
+		while x < Self::WIDTH {
+			let d = (Self::WIDTH as isize - 1 - 2 * x as isize) * Self::H_OFFSET as isize;
+			res.or_assign_const(&Self::shifted_by_signed(&self.and_const(&Self::col_mask(x)), d));
+			x += 1;
+		}
+		res
+	};
+	let by_rows = quote! {
+		let mut res = Self::EMPTY;
+		let mut y = 0;
+		while y < Self::HEIGHT {
+			let d = (Self::HEIGHT as isize - 1 - 2 * y as isize) * Self::V_OFFSET as isize;
+			res.or_assign_const(&Self::shifted_by_signed(&self.and_const(&Self::row_mask(y)), d));
+			y += 1;
+		}
+		res
+	};
+	// In a column-major 8x8 board the columns are the bytes
+	let (flip_horizontal, flip_vertical) = match (is_8x8 && !is_storage_array, col_major) {
+		(true, false) => (mirror_bytes, swap_bytes),
+		(true, true) => (swap_bytes, mirror_bytes),
+		(false, _) => (by_columns, by_rows),
+	};
+
+	let transpose = if is_8x8 && !is_storage_array {
+		// Same permutation of the indexes for both layouts
+		quote! {
+			let mut b = self.0;
+			let mut t = 0x0F0F_0F0F_0000_0000 & (b ^ (b << 28));
+			b ^= t ^ (t >> 28);
+			t = 0x3333_0000_3333_0000 & (b ^ (b << 14));
+			b ^= t ^ (t >> 14);
+			t = 0x5500_5500_5500_5500 & (b ^ (b << 7));
+			b ^= t ^ (t >> 7);
+			Self(b)
+		}
+	} else if width == height {
+		let nb_diags = 2 * width - 1;
+		quote! {
+			const DIAG_MASKS: [#ident; #nb_diags] = {
+				let mut masks = [#ident::EMPTY; #nb_diags];
+				let mut i = 0;
+				while i < #ident::NB_SQUARES {
+					let (x, y) = #ident::coords_from_index(i);
+					masks[(x as usize + #ident::HEIGHT as usize - 1) - y as usize].set_at_index(i);
+					i += 1;
+				}
+				masks
+			};
+			let mut res = Self::EMPTY;
+			let mut d = 0;
+			while d < #nb_diags {
+				// Distance between the indexes of (x, y) and (y, x), the same along a diagonal
+				let (x, y) = if d + 1 >= Self::HEIGHT as usize { ((d + 1 - Self::HEIGHT as usize) as u8, 0) } else { (0, (Self::HEIGHT as usize - 1 - d) as u8) };
+				let shift = Self::index_from_coords(y, x) as isize - Self::index_from_coords(x, y) as isize;
+				res.or_assign_const(&Self::shifted_by_signed(&self.and_const(&DIAG_MASKS[d]), shift));
+				d += 1;
+			}
+			res
+		}
+	} else {
+		quote! {
+			let mut res = #transposed::EMPTY;
+			let mut i = 0;
+			while i < Self::NB_SQUARES {
+				if self.get_at_index(i) {
+					let (x, y) = Self::coords_from_index(i);
+					res.set_at_index(#transposed::index_from_coords(y, x));
+				}
+				i += 1;
+			}
+			res
+		}
+	};
+
+	// Index `i` goes to `NB_SQUARES - 1 - i` in both layouts
+	let rotate180 = if is_storage_array {
+		quote! { self.flip_horizontal().flip_vertical() }
+	} else {
+		quote! { Self(self.0.reverse_bits() >> (8 * core::mem::size_of::<<Self as bitboard::Bitboard>::Storage>() - Self::NB_SQUARES)) }
+	};
+
+	// All eight symmetries keep the shape of a square board
+	let try_transformed = if width == height {
+		quote! {
+			#[inline]
+			fn try_transformed(&self, symmetry: bitboard::symmetry::Symmetry) -> Option<Self> {
+				Some(bitboard::symmetry::Symmetric::transformed(self, symmetry))
+			}
+		}
+	} else {
+		quote! {}
+	};
+
+	quote! {
+		impl #ident {
+			/// Shifts the storage by a signed offset, left when positive.
+			#[inline(always)]
+			const fn shifted_by_signed(b: &Self, offset: isize) -> Self {
+				if offset >= 0 {
+					b.shl_const(offset as usize)
+				} else {
+					b.shr_const((-offset) as usize)
+				}
+			}
+			/// Mirror across the vertical axis: `x` becomes `WIDTH - 1 - x`.
+			#[inline]
+			pub const fn flip_horizontal(&self) -> Self {
+				#flip_horizontal
+			}
+			/// Mirror across the horizontal axis: `y` becomes `HEIGHT - 1 - y`.
+			#[inline]
+			pub const fn flip_vertical(&self) -> Self {
+				#flip_vertical
+			}
+			/// Mirror across the diagonal through `(0, 0)`: `(x, y)` becomes `(y, x)`.
+			#[inline]
+			pub const fn transpose(&self) -> #transposed {
+				#transpose
+			}
+			/// Half turn.
+			#[inline]
+			pub const fn rotate180(&self) -> Self {
+				#rotate180
+			}
+			/// Quarter turn clockwise: `(x, y)` becomes `(y, WIDTH - 1 - x)`.
+			#[inline]
+			pub const fn rotate90(&self) -> #transposed {
+				self.transpose().flip_vertical()
+			}
+			/// Quarter turn counter-clockwise: `(x, y)` becomes `(HEIGHT - 1 - y, x)`.
+			#[inline]
+			pub const fn rotate270(&self) -> #transposed {
+				self.transpose().flip_horizontal()
+			}
+		}
+		impl bitboard::symmetry::Symmetric for #ident {
+			type Transposed = #transposed;
+
+			#[inline]
+			fn flip_horizontal(&self) -> Self {
+				#ident::flip_horizontal(self)
+			}
+			#[inline]
+			fn flip_vertical(&self) -> Self {
+				#ident::flip_vertical(self)
+			}
+			#[inline]
+			fn transpose(&self) -> #transposed {
+				#ident::transpose(self)
+			}
+			#[inline]
+			fn rotate180(&self) -> Self {
+				#ident::rotate180(self)
+			}
+			#[inline]
+			fn rotate90(&self) -> #transposed {
+				#ident::rotate90(self)
+			}
+			#[inline]
+			fn rotate270(&self) -> #transposed {
+				#ident::rotate270(self)
+			}
+			#try_transformed
+		}
+	}
+}
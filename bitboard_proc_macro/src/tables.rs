@@ -0,0 +1,448 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::{Topology, hex::HEX_DIRECTIONS};
+
+/// Tables that can be requested with `tables(...)` in `#[bitboard(...)]`.
+const KNOWN_TABLES: &[&str] = &[
+	"ray_n", "ray_s", "ray_e", "ray_w", "ray_ne", "ray_nw", "ray_se", "ray_sw",
+	"neighbors_ortho", "neighbors_diag", "neighbors_8",
+	"diag_inc", "diag_dec",
+	"knight", "king",
+	"ray_between",
+	"neighbors_hex",
+	"ray_hex_e", "ray_hex_ne", "ray_hex_nw", "ray_hex_w", "ray_hex_sw", "ray_hex_se",
+	"rook_sliding", "bishop_sliding",
+];
+
+const KNIGHT_OFFSETS: [(i16, i16); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_OFFSETS: [(i16, i16); 8] = [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+const ORTHO_OFFSETS: [(i16, i16); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const DIAG_OFFSETS: [(i16, i16); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+/// Magic candidates tried before growing the slot of a square, as in `bitboard::sliding`.
+const MAGIC_TRIES: u32 = 10_000;
+/// Index bits a slot can get beyond the number of relevant squares, as in `bitboard::sliding`.
+const MAGIC_EXTRA_BITS: u32 = 4;
+
+/// Board geometry used to compute the tables inside the proc macro.
+struct Geometry {
+	width: usize,
+	height: usize,
+	col_major: bool,
+	/// Whether the jumps wrap around the east/west and north/south edges.
+	wrap: (bool, bool),
+	/// Number of 64-bit words of the storage (1 for integer storage).
+	nb_words: usize,
+	is_array: bool,
+}
+
+/// Bits of one table entry, as 64-bit words (least significant first).
+type Mask = Vec<u64>;
+
+impl Geometry {
+	fn nb_squares(&self) -> usize {
+		self.width * self.height
+	}
+	fn index(&self, x: usize, y: usize) -> usize {
+		if self.col_major {
+			x * self.height + y
+		} else {
+			y * self.width + x
+		}
+	}
+	fn coords(&self, i: usize) -> (usize, usize) {
+		if self.col_major {
+			(i / self.height, i % self.height)
+		} else {
+			(i % self.width, i / self.width)
+		}
+	}
+	fn in_bounds(&self, x: i16, y: i16) -> bool {
+		x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+	}
+	fn empty(&self) -> Mask {
+		vec![0; self.nb_words]
+	}
+	fn set(&self, mask: &mut Mask, x: i16, y: i16) {
+		let i = self.index(x as usize, y as usize);
+		mask[i / 64] |= 1 << (i % 64);
+	}
+
+	/// Squares reached with a single step of each offset, wrapping around the wrapped edges.
+	fn jumps(&self, square: usize, offsets: &[(i16, i16)]) -> Mask {
+		let (x, y) = self.coords(square);
+		let mut mask = self.empty();
+		for &(dx, dy) in offsets {
+			let (mut nx, mut ny) = (x as i16 + dx, y as i16 + dy);
+			if self.wrap.0 {
+				nx = nx.rem_euclid(self.width as i16);
+			}
+			if self.wrap.1 {
+				ny = ny.rem_euclid(self.height as i16);
+			}
+			if self.in_bounds(nx, ny) {
+				self.set(&mut mask, nx, ny);
+			}
+		}
+		mask
+	}
+	/// Squares from `square` (excluded) to the board edge in direction `(dx, dy)`.
+	fn ray(&self, square: usize, dx: i16, dy: i16) -> Mask {
+		let (x, y) = self.coords(square);
+		let mut mask = self.empty();
+		let (mut nx, mut ny) = (x as i16 + dx, y as i16 + dy);
+		while self.in_bounds(nx, ny) {
+			self.set(&mut mask, nx, ny);
+			nx += dx;
+			ny += dy;
+		}
+		mask
+	}
+	/// Full line through `square` in direction `(dx, dy)`, `square` included.
+	fn line(&self, square: usize, dx: i16, dy: i16) -> Mask {
+		let (x, y) = self.coords(square);
+		let mut mask = self.ray(square, dx, dy);
+		for (w, r) in mask.iter_mut().zip(self.ray(square, -dx, -dy)) {
+			*w |= r;
+		}
+		self.set(&mut mask, x as i16, y as i16);
+		mask
+	}
+	/// Squares strictly between `from` and `to` when they share a line (empty otherwise).
+	fn between(&self, from: usize, to: usize) -> Mask {
+		let (fx, fy) = self.coords(from);
+		let (tx, ty) = self.coords(to);
+		let (fx, fy, tx, ty) = (fx as i16, fy as i16, tx as i16, ty as i16);
+		let mut mask = self.empty();
+		if !(fx == tx || fy == ty || fx - fy == tx - ty || fx + fy == tx + ty) {
+			return mask;
+		}
+		let (dx, dy) = ((tx - fx).signum(), (ty - fy).signum());
+		let (mut x, mut y) = (fx + dx, fy + dy);
+		while x != tx || y != ty {
+			self.set(&mut mask, x, y);
+			x += dx;
+			y += dy;
+		}
+		mask
+	}
+
+	/// Rays of `directions` from `square`, without their last square, which never blocks anything.
+	fn sliding_mask(&self, square: usize, directions: &[(i16, i16)]) -> Mask {
+		let (x, y) = self.coords(square);
+		let mut mask = self.empty();
+		for &(dx, dy) in directions {
+			let (mut nx, mut ny) = (x as i16 + dx, y as i16 + dy);
+			while self.in_bounds(nx + dx, ny + dy) {
+				self.set(&mut mask, nx, ny);
+				nx += dx;
+				ny += dy;
+			}
+		}
+		mask
+	}
+	/// Squares attacked from `square` along `directions`, up to and including the first one of `occupancy`.
+	fn sliding_attacks(&self, square: usize, occupancy: &Mask, directions: &[(i16, i16)]) -> Mask {
+		let (x, y) = self.coords(square);
+		let mut attacks = self.empty();
+		for &(dx, dy) in directions {
+			let (mut nx, mut ny) = (x as i16 + dx, y as i16 + dy);
+			while self.in_bounds(nx, ny) {
+				self.set(&mut attacks, nx, ny);
+				let i = self.index(nx as usize, ny as usize);
+				if occupancy[i / 64] >> (i % 64) & 1 != 0 {
+					break;
+				}
+				nx += dx;
+				ny += dy;
+			}
+		}
+		attacks
+	}
+
+	/// Entries and flat attack array of a `bitboard::sliding::SlidingTable`, with the same
+	/// layout as `SlidingTable::new`: indexed with PEXT when `pext`, with magics otherwise.
+	fn sliding_table(&self, directions: &[(i16, i16)], pext: bool) -> Result<(Vec<SlidingEntry>, Vec<Mask>), String> {
+		let mut entries = Vec::with_capacity(self.nb_squares());
+		let mut attacks = Vec::new();
+		let mut rng = MagicRng(0x9E37_79B9_7F4A_7C15);
+		for square in 0..self.nb_squares() {
+			let mask = self.sliding_mask(square, directions);
+			let mask_indices: Vec<usize> = (0..self.nb_squares()).filter(|&i| mask[i / 64] >> (i % 64) & 1 != 0).collect();
+			let bits = mask_indices.len();
+			if bits > 32 {
+				return Err(format!("{} relevant squares is too many for square {}", bits, square));
+			}
+			// Subset `i` of the mask is also its PEXT index
+			let occupancies: Vec<Mask> = (0..1usize << bits).map(|subset| {
+				let mut occupancy = self.empty();
+				for (bit, &i) in mask_indices.iter().enumerate() {
+					if subset >> bit & 1 != 0 {
+						occupancy[i / 64] |= 1 << (i % 64);
+					}
+				}
+				occupancy
+			}).collect();
+			let square_attacks: Vec<Mask> = occupancies.iter().map(|occupancy| self.sliding_attacks(square, occupancy, directions)).collect();
+
+			let mut entry = SlidingEntry { mask, offset: attacks.len(), magic: 0, shift: 64 - bits as u32, rot: 0 };
+			if !pext {
+				entry.rot = (0..64).find(|&rot| fold(&entry.mask, &entry.mask, rot).count_ones() as usize == bits)
+					.ok_or_else(|| format!("cannot fold the mask of square {} into 64 bits", square))?;
+				(entry.magic, entry.shift) = find_magic(&entry, &occupancies, &square_attacks, &mut rng)
+					.ok_or_else(|| format!("no magic found for square {}", square))?;
+			}
+			attacks.resize(attacks.len() + (1 << (64 - entry.shift)), self.empty());
+			for (subset, (occupancy, square_attacks)) in occupancies.iter().zip(square_attacks).enumerate() {
+				let index = if pext { subset } else { entry.magic_index(occupancy) };
+				attacks[entry.offset + index] = square_attacks;
+			}
+			entries.push(entry);
+		}
+		Ok((entries, attacks))
+	}
+
+	/// Computes the entry of a one-dimensional table for `square`.
+	fn entry(&self, table: &str, square: usize) -> Mask {
+		match table {
+			"ray_n" => self.ray(square, 0, 1),
+			"ray_s" => self.ray(square, 0, -1),
+			"ray_e" => self.ray(square, 1, 0),
+			"ray_w" => self.ray(square, -1, 0),
+			"ray_ne" => self.ray(square, 1, 1),
+			"ray_nw" => self.ray(square, -1, 1),
+			"ray_se" => self.ray(square, 1, -1),
+			"ray_sw" => self.ray(square, -1, -1),
+			"neighbors_ortho" => self.jumps(square, &ORTHO_OFFSETS),
+			"neighbors_diag" => self.jumps(square, &DIAG_OFFSETS),
+			"neighbors_8" | "king" => self.jumps(square, &KING_OFFSETS),
+			"diag_inc" => self.line(square, 1, 1),
+			"diag_dec" => self.line(square, 1, -1),
+			"knight" => self.jumps(square, &KNIGHT_OFFSETS),
+			"neighbors_hex" => self.jumps(square, &HEX_DIRECTIONS.map(|(_, dx, dy)| (dx, dy))),
+			_ if table.starts_with("ray_hex_") => {
+				let (_, dx, dy) = HEX_DIRECTIONS.iter().find(|(name, _, _)| table["ray_hex_".len()..] == **name).unwrap();
+				self.ray(square, *dx, *dy)
+			}
+			_ => unreachable!("unknown table {}", table),
+		}
+	}
+
+	/// Emits the bitboard expression of a mask.
+	fn to_tokens(&self, ident: &syn::Ident, mask: &Mask) -> TokenStream {
+		if mask.iter().all(|&w| w == 0) {
+			quote! { #ident::EMPTY }
+		} else if self.is_array {
+			let words = mask.iter().map(|&w| Literal::u64_unsuffixed(w));
+			quote! { #ident([#(#words),*]) }
+		} else {
+			let value = mask.iter().rev().fold(0u128, |acc, &w| (acc << 64) | w as u128);
+			let value = Literal::u128_unsuffixed(value);
+			quote! { #ident(#value) }
+		}
+	}
+}
+
+/// Per-square data of a sliding table, as `bitboard::sliding::SlidingEntry`.
+struct SlidingEntry {
+	mask: Mask,
+	offset: usize,
+	magic: u64,
+	shift: u32,
+	rot: u32,
+}
+
+impl SlidingEntry {
+	fn magic_index(&self, occupancy: &Mask) -> usize {
+		fold(occupancy, &self.mask, self.rot).wrapping_mul(self.magic).checked_shr(self.shift).unwrap_or(0) as usize
+	}
+}
+
+/// Folds `bits & mask` into a single word, rotating word `i` by `rot * i`.
+fn fold(bits: &Mask, mask: &Mask, rot: u32) -> u64 {
+	bits.iter().zip(mask).enumerate().fold(0, |key, (i, (b, m))| key ^ (b & m).rotate_left(rot * i as u32))
+}
+
+/// Searches a magic for the entry, returning it with its shift, as `SlidingTable::new` does.
+fn find_magic(entry: &SlidingEntry, occupancies: &[Mask], attacks: &[Mask], rng: &mut MagicRng) -> Option<(u64, u32)> {
+	let keys: Vec<u64> = occupancies.iter().map(|occupancy| fold(occupancy, &entry.mask, entry.rot)).collect();
+	if keys.len() == 1 {
+		return Some((0, 64));
+	}
+	let mask_key = fold(&entry.mask, &entry.mask, entry.rot);
+	for shift in (entry.shift - MAGIC_EXTRA_BITS + 1..=entry.shift).rev() {
+		let mut used: Vec<Option<usize>> = vec![None; 1 << (64 - shift)];
+		for _ in 0..MAGIC_TRIES {
+			let magic = rng.sparse();
+			if keys.len() >= 64 && (mask_key.wrapping_mul(magic) >> 56).count_ones() < 6 {
+				continue;
+			}
+			used.iter_mut().for_each(|u| *u = None);
+			let ok = keys.iter().enumerate().all(|(i, key)| {
+				let index = (key.wrapping_mul(magic) >> shift) as usize;
+				match used[index] {
+					None => { used[index] = Some(i); true }
+					Some(j) => attacks[j] == attacks[i],
+				}
+			});
+			if ok {
+				return Some((magic, shift));
+			}
+		}
+	}
+	None
+}
+
+/// Deterministic xorshift generator used to search magics.
+struct MagicRng(u64);
+impl MagicRng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+	/// Candidates with few bits set make better magics.
+	fn sparse(&mut self) -> u64 {
+		self.next() & self.next() & self.next()
+	}
+}
+
+/// Generates the tables requested with `tables(...)`.
+///
+/// Tables are computed here, in the proc macro, and emitted as `static` arrays so that
+/// large ones (e.g. `ray_between`, which has `NB_SQUARES²` entries) do not go through
+/// const evaluation. Each table `name` gives:
+/// - an associated constant `NAME` referencing the static array;
+/// - `name_mask(index)`, returning the entry of square `index`;
+/// - `name(&self, index)`, returning `self` masked by that entry.
+///
+/// `ray_between` is indexed by two squares: `ray_between_mask(from, to)` and `ray_between(&self, from, to)`.
+/// `rook_sliding` and `bishop_sliding` are `bitboard::sliding::SlidingTable`s, indexed with PEXT
+/// when the target has BMI2 and with magics otherwise: `ROOK_SLIDING` references the table and
+/// `rook_sliding(&self, index)` returns the attacks from `index`, `self` being the occupancy.
+/// The `*_hex*` tables are only available with `topology = "hex"`. With `wrap_x`/`wrap_y`,
+/// the neighbour, `knight` and `king` tables wrap around the edges, the rays and lines do not.
+pub(crate) fn tables_impl(ident: &syn::Ident, width: usize, height: usize, col_major: bool, topology: Topology, wrap: (bool, bool), tables: &[syn::Ident]) -> syn::Result<TokenStream> {
+	let total_bits = width * height;
+	let geometry = Geometry {
+		width,
+		height,
+		col_major,
+		wrap,
+		nb_words: total_bits.div_ceil(64),
+		is_array: total_bits > 128,
+	};
+	let nb_squares = geometry.nb_squares();
+	let prefix = ident.to_string().to_uppercase();
+
+	let mut statics = Vec::new();
+	let mut items = Vec::new();
+	for table in tables {
+		let name = table.to_string();
+		if !KNOWN_TABLES.contains(&name.as_str()) {
+			return Err(syn::Error::new_spanned(table, format!(
+				"unknown table `{}`, expected one of: {}", name, KNOWN_TABLES.join(", ")
+			)));
+		}
+		if name.contains("hex") && topology != Topology::Hex {
+			return Err(syn::Error::new_spanned(table, format!("table `{}` requires `topology = \"hex\"`", name)));
+		}
+		let const_ident = format_ident!("{}", name.to_uppercase());
+		let static_ident = format_ident!("__{}_{}", prefix, name.to_uppercase());
+		let mask_fn = format_ident!("{}_mask", name);
+		let access_fn = table;
+
+		if let Some(directions) = match name.as_str() {
+			"rook_sliding" => Some(ORTHO_OFFSETS),
+			"bishop_sliding" => Some(DIAG_OFFSETS),
+			_ => None,
+		} {
+			for pext in [true, false] {
+				let (entries, attacks) = geometry.sliding_table(&directions, pext)
+					.map_err(|msg| syn::Error::new_spanned(table, format!("sliding table `{}`: {}", name, msg)))?;
+				let entries = entries.iter().map(|entry| {
+					let SlidingEntry { mask, offset, magic, shift, rot } = entry;
+					let (mask, shift, rot) = (geometry.to_tokens(ident, mask), *shift as u8, *rot as u8);
+					quote! { bitboard::sliding::SlidingEntry::new(#mask, #offset, #magic, #shift, #rot) }
+				});
+				let nb_attacks = attacks.len();
+				let attacks = attacks.iter().map(|mask| geometry.to_tokens(ident, mask));
+				let entries_ident = format_ident!("{}_ENTRIES", static_ident);
+				let attacks_ident = format_ident!("{}_ATTACKS", static_ident);
+				let cfg = if pext { quote! { #[cfg(target_feature = "bmi2")] } } else { quote! { #[cfg(not(target_feature = "bmi2"))] } };
+				statics.push(quote! {
+					#cfg
+					static #entries_ident: [bitboard::sliding::SlidingEntry<#ident>; #nb_squares] = [#(#entries),*];
+					#cfg
+					static #attacks_ident: [#ident; #nb_attacks] = [#(#attacks),*];
+					#cfg
+					static #static_ident: bitboard::sliding::SlidingTable<#ident> = bitboard::sliding::SlidingTable::from_static(&#entries_ident, &#attacks_ident, #pext);
+				});
+			}
+			let doc = format!("`{}` attack table, generated by the macro.", name);
+			items.push(quote! {
+				#[doc = #doc]
+				pub const #const_ident: &'static bitboard::sliding::SlidingTable<Self> = &#static_ident;
+				/// Returns the sliding attacks from the square at `index`, `self` being the occupancy.
+				#[inline(always)]
+				pub fn #access_fn(&self, index: usize) -> Self {
+					Self::#const_ident.attacks(index, self).clone_const()
+				}
+			});
+		} else if name == "ray_between" {
+			let rows = (0..nb_squares).map(|from| {
+				let entries = (0..nb_squares).map(|to| geometry.to_tokens(ident, &geometry.between(from, to)));
+				quote! { [#(#entries),*] }
+			});
+			statics.push(quote! {
+				static #static_ident: [[#ident; #nb_squares]; #nb_squares] = [#(#rows),*];
+			});
+			items.push(quote! {
+				/// Masks of the squares strictly between two squares on the same line, generated by the macro.
+				pub const #const_ident: &'static [[Self; #nb_squares]; #nb_squares] = &#static_ident;
+				/// Returns the squares strictly between `from` and `to` (empty if they are not aligned).
+				#[inline(always)]
+				pub fn #mask_fn(from: usize, to: usize) -> Self {
+					Self::#const_ident[from][to].clone_const()
+				}
+				/// Returns `self` masked by the squares strictly between `from` and `to`.
+				#[inline(always)]
+				pub fn #access_fn(&self, from: usize, to: usize) -> Self {
+					self.and_const(&Self::#const_ident[from][to])
+				}
+			});
+		} else {
+			let entries = (0..nb_squares).map(|sq| geometry.to_tokens(ident, &geometry.entry(&name, sq)));
+			let doc = format!("`{}` masks of every square, generated by the macro.", name);
+			statics.push(quote! {
+				static #static_ident: [#ident; #nb_squares] = [#(#entries),*];
+			});
+			items.push(quote! {
+				#[doc = #doc]
+				pub const #const_ident: &'static [Self; #nb_squares] = &#static_ident;
+				/// Returns the mask of the square at `index`.
+				#[inline(always)]
+				pub fn #mask_fn(index: usize) -> Self {
+					Self::#const_ident[index].clone_const()
+				}
+				/// Returns `self` masked by the mask of the square at `index`.
+				#[inline(always)]
+				pub fn #access_fn(&self, index: usize) -> Self {
+					self.and_const(&Self::#const_ident[index])
+				}
+			});
+		}
+	}
+
+	if items.is_empty() {
+		return Ok(TokenStream::new());
+	}
+	Ok(quote! {
+		#(#statics)*
+		impl #ident {
+			#(#items)*
+		}
+	})
+}
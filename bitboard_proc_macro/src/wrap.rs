@@ -0,0 +1,224 @@
+use quote::{format_ident, quote};
+
+/// The eight square directions as `(name, dx, dy)`.
+const DIRECTIONS: [(&str, i16, i16); 8] = [
+	("n", 0, 1), ("s", 0, -1), ("e", 1, 0), ("w", -1, 0),
+	("ne", 1, 1), ("nw", -1, 1), ("se", 1, -1), ("sw", -1, -1),
+];
+
+/// Alignment axes as `(name, dx, dy)`, with the names of the square alignment functions.
+const AXES: [(&str, i16, i16); 4] = [
+	("horizontal", 1, 0), ("vertical", 0, 1), ("diag_dec", 1, -1), ("diag_inc", 1, 1),
+];
+
+/// Generates the shifts and alignments of boards with `wrap_x = true` and/or `wrap_y = true`.
+///
+/// They replace the square ones, with the same names: a square leaving the board through a
+/// wrapped edge comes back on the opposite edge, while non-wrapped edges still drop it.
+/// Shifts step one column and one row at a time, with masks computed from the coordinates,
+/// so that they hold for row-major, column-major and array storage alike.
+pub(crate) fn wrap_impl(ident: &syn::Ident) -> proc_macro2::TokenStream {
+	let mut direction_items = Vec::new();
+	for (name, dx, dy) in DIRECTIONS {
+		let upper = name.to_uppercase();
+		let shift = format_ident!("shift_{}", name);
+		let shift_by = format_ident!("shift_{}_by", name);
+		let shifted = format_ident!("shifted_{}", name);
+		let shift_doc = format!("Shift bitboard one square {}, wrapping around the wrapped edges.", upper);
+		let shift_by_doc = format!("Shift bitboard n squares {}, wrapping around the wrapped edges.", upper);
+		let shifted_doc = format!("Shifted bitboard one square {}, wrapping around the wrapped edges.", upper);
+		direction_items.push(quote! {
+			#[doc = #shift_doc]
+			#[inline(always)]
+			pub const fn #shift(&mut self) {
+				*self = self.#shifted();
+			}
+			#[doc = #shift_by_doc]
+			#[inline(always)]
+			pub const fn #shift_by(&mut self, n: u8) {
+				let mut i = 0;
+				while i < n {
+					*self = self.#shifted();
+					i += 1;
+				}
+			}
+			#[doc = #shifted_doc]
+			#[inline(always)]
+			pub const fn #shifted(&self) -> Self {
+				self.step_x(#dx).step_y(#dy)
+			}
+		});
+	}
+
+	let mut axis_items = Vec::new();
+	for (name, dx, dy) in AXES {
+		let has_aligned = format_ident!("has_aligned_{}", name);
+		let has_n_aligned = format_ident!("has_n_aligned_{}", name);
+		let count_aligned = format_ident!("count_aligned_{}", name);
+		axis_items.push(quote! {
+			#[inline]
+			pub const fn #has_aligned<const N: usize>(&self) -> bool {
+				N == 0 || self.get_aligned_wrap(N, #dx, #dy).any()
+			}
+			#[inline]
+			pub const fn #has_n_aligned(&self, n: u8) -> bool {
+				n == 0 || self.get_aligned_wrap(n as usize, #dx, #dy).any()
+			}
+			#[inline]
+			pub fn #count_aligned<const N: usize>(&self) -> u32 {
+				self.get_aligned_wrap(N, #dx, #dy).count()
+			}
+		});
+	}
+
+	quote! {
+		impl #ident {
+			/// Computes the squares of column `x`.
+			const fn compute_wrap_col_mask(x: u8) -> Self {
+				let mut bb = Self::EMPTY;
+				let mut y = 0;
+				while y < Self::HEIGHT {
+					bb.set_at_index(Self::index_from_coords(x, y));
+					y += 1;
+				}
+				bb
+			}
+			/// Computes the squares of row `y`.
+			const fn compute_wrap_row_mask(y: u8) -> Self {
+				let mut bb = Self::EMPTY;
+				let mut x = 0;
+				while x < Self::WIDTH {
+					bb.set_at_index(Self::index_from_coords(x, y));
+					x += 1;
+				}
+				bb
+			}
+			const WRAP_WEST_COL: Self = Self::compute_wrap_col_mask(0);
+			const WRAP_EAST_COL: Self = Self::compute_wrap_col_mask(Self::WIDTH - 1);
+			const WRAP_SOUTH_ROW: Self = Self::compute_wrap_row_mask(0);
+			const WRAP_NORTH_ROW: Self = Self::compute_wrap_row_mask(Self::HEIGHT - 1);
+
+			/// Moves every square one column east (`dx > 0`) or west (`dx < 0`).
+			#[inline(always)]
+			const fn step_x(&self, dx: i16) -> Self {
+				let span = (Self::WIDTH as usize - 1) * Self::H_OFFSET;
+				if dx > 0 {
+					let mut res = self.and_const(&Self::WRAP_EAST_COL.not_const()).shl_const(Self::H_OFFSET);
+					if Self::WRAP_X {
+						res.or_assign_const(&self.and_const(&Self::WRAP_EAST_COL).shr_const(span));
+					}
+					res
+				} else if dx < 0 {
+					let mut res = self.and_const(&Self::WRAP_WEST_COL.not_const()).shr_const(Self::H_OFFSET);
+					if Self::WRAP_X {
+						res.or_assign_const(&self.and_const(&Self::WRAP_WEST_COL).shl_const(span));
+					}
+					res
+				} else {
+					self.clone_const()
+				}
+			}
+			/// Moves every square one row north (`dy > 0`) or south (`dy < 0`).
+			#[inline(always)]
+			const fn step_y(&self, dy: i16) -> Self {
+				let span = (Self::HEIGHT as usize - 1) * Self::V_OFFSET;
+				if dy > 0 {
+					let mut res = self.and_const(&Self::WRAP_NORTH_ROW.not_const()).shl_const(Self::V_OFFSET);
+					if Self::WRAP_Y {
+						res.or_assign_const(&self.and_const(&Self::WRAP_NORTH_ROW).shr_const(span));
+					}
+					res
+				} else if dy < 0 {
+					let mut res = self.and_const(&Self::WRAP_SOUTH_ROW.not_const()).shr_const(Self::V_OFFSET);
+					if Self::WRAP_Y {
+						res.or_assign_const(&self.and_const(&Self::WRAP_SOUTH_ROW).shl_const(span));
+					}
+					res
+				} else {
+					self.clone_const()
+				}
+			}
+			#[inline(always)]
+			pub fn shifted(&self, dx: i16, dy: i16) -> Self {
+				let mut res = self.clone_const();
+				res.shift(dx, dy);
+				res
+			}
+			#[inline(always)]
+			pub fn shift(&mut self, dx: i16, dy: i16) {
+				// Past the board size, wrapped shifts loop and the others are empty
+				let (w, h) = (Self::WIDTH as i16, Self::HEIGHT as i16);
+				let dx = if Self::WRAP_X { dx.rem_euclid(w) } else { dx.clamp(-w, w) };
+				let dy = if Self::WRAP_Y { dy.rem_euclid(h) } else { dy.clamp(-h, h) };
+				for _ in 0..dx.abs() {
+					*self = self.step_x(dx.signum());
+				}
+				for _ in 0..dy.abs() {
+					*self = self.step_y(dy.signum());
+				}
+			}
+			#(#direction_items)*
+
+			/// Number of distinct squares of a line along `(dx, dy)` when it loops around the
+			/// board, `usize::MAX` when it ends on a non-wrapped edge.
+			const fn wrap_cycle_len(dx: i16, dy: i16) -> usize {
+				let (w, h) = (Self::WIDTH as usize, Self::HEIGHT as usize);
+				if (dx != 0 && !Self::WRAP_X) || (dy != 0 && !Self::WRAP_Y) {
+					usize::MAX
+				} else if dy == 0 {
+					w
+				} else if dx == 0 {
+					h
+				} else {
+					// lcm(w, h)
+					let (mut a, mut b) = (w, h);
+					while b != 0 {
+						let t = a % b;
+						a = b;
+						b = t;
+					}
+					w / a * h
+				}
+			}
+			/// Squares starting a run of `n` set squares along `(dx, dy)`, runs going through
+			/// the wrapped edges included. A run cannot visit the same square twice.
+			#[inline]
+			const fn get_aligned_wrap(&self, n: usize, dx: i16, dy: i16) -> Self {
+				if n > Self::wrap_cycle_len(dx, dy) {
+					return Self::EMPTY;
+				}
+				let mut temp = self.clone_const();
+				let mut i = 1;
+				while i < n {
+					// Keeps the squares whose next square along the axis is in the run
+					let next = temp.step_x(-dx).step_y(-dy);
+					temp.and_assign_const(&next);
+					i += 1;
+				}
+				temp
+			}
+			#[inline]
+			pub const fn has_aligned<const N: usize>(&self) -> bool {
+				self.has_aligned_horizontal::<N>() ||
+					self.has_aligned_vertical::<N>() ||
+					self.has_aligned_diag_dec::<N>() ||
+					self.has_aligned_diag_inc::<N>()
+			}
+			#[inline]
+			pub const fn has_n_aligned(&self, n: u8) -> bool {
+				self.has_n_aligned_horizontal(n) ||
+					self.has_n_aligned_vertical(n) ||
+					self.has_n_aligned_diag_dec(n) ||
+					self.has_n_aligned_diag_inc(n)
+			}
+			#[inline]
+			pub fn count_aligned<const N: usize>(&self) -> u32 {
+				self.count_aligned_horizontal::<N>()
+					+ self.count_aligned_vertical::<N>()
+					+ self.count_aligned_diag_dec::<N>()
+					+ self.count_aligned_diag_inc::<N>()
+			}
+			#(#axis_items)*
+		}
+	}
+}